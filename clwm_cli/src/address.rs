@@ -0,0 +1,173 @@
+use std::io::{IsTerminal, Write};
+
+use anyhow::Context;
+use clwm_lib::{clwm::Clwm, clwm_error::ClwmError, table::encode_cell};
+
+/// Resolves a noun given as an id or a name. Exact name matches win over
+/// partial ones, and partial or several matches are offered as a choice.
+pub async fn noun_id(clwm: &mut Clwm, reference: &str) -> anyhow::Result<i64> {
+    if let Ok(id) = reference.parse::<i64>() {
+        return Ok(id);
+    }
+    let mut nouns = clwm.find_nouns_by_name(reference.to_string()).await?;
+    let exact = nouns.iter().any(|noun| noun.name == reference);
+    if exact {
+        nouns.retain(|noun| noun.name == reference);
+    }
+    if nouns.is_empty() {
+        return Err(ClwmError::NounNotFound).context(format!("no noun is named \"{}\"", reference));
+    }
+    nouns.sort_by_key(|noun| noun.noun_id);
+    let noun = choose("noun", reference, nouns, !exact, |noun| {
+        format!(
+            "{}. {} ({})",
+            noun.noun_id.unwrap(),
+            noun.name,
+            noun.noun_type
+        )
+    })?;
+    Ok(noun.noun_id.unwrap())
+}
+
+pub async fn noun_type_id(clwm: &mut Clwm, reference: &str) -> anyhow::Result<i64> {
+    if let Ok(id) = reference.parse::<i64>() {
+        return Ok(id);
+    }
+    match clwm.get_noun_type_by_name(reference.to_string()).await? {
+        Some(noun_type) => Ok(noun_type.noun_type_id.unwrap()),
+        None => Err(ClwmError::NounTypeNotFound)
+            .context(format!("no noun type is named \"{}\"", reference)),
+    }
+}
+
+pub async fn attribute_type_id(clwm: &mut Clwm, reference: &str) -> anyhow::Result<i64> {
+    if let Ok(id) = reference.parse::<i64>() {
+        return Ok(id);
+    }
+    match clwm
+        .get_attribute_type_by_name(reference.to_string())
+        .await?
+    {
+        Some(attribute_type) => Ok(attribute_type.attribute_type_id.unwrap()),
+        None => Err(ClwmError::AttributeTypeNotFound)
+            .context(format!("no attribute type is named \"{}\"", reference)),
+    }
+}
+
+/// Resolves an attribute given as an id or as a path of a noun followed by
+/// attribute type names, such as `Gandalf/inventory/staff`.
+pub async fn attribute_id(clwm: &mut Clwm, reference: &str) -> anyhow::Result<i64> {
+    if let Ok(id) = reference.parse::<i64>() {
+        return Ok(id);
+    }
+    let mut segments: Vec<String> = reference.split('/').map(str::to_string).collect();
+    if segments.len() < 2 {
+        return Err(ClwmError::AttributeNotFound).context(format!(
+            "\"{}\" is not an attribute id or a noun/attribute type path",
+            reference
+        ));
+    }
+    let noun_id = noun_id(clwm, &segments.remove(0)).await?;
+    let mut attributes = clwm.find_attributes_by_path(noun_id, &segments).await?;
+    if attributes.is_empty() {
+        return Err(ClwmError::AttributeNotFound)
+            .context(format!("there is no attribute at \"{}\"", reference));
+    }
+    attributes.sort_by_key(|attribute| attribute.attribute_id);
+    let attribute = choose("attribute", reference, attributes, false, |attribute| {
+        format!(
+            "{}. {}",
+            attribute.attribute_id.unwrap(),
+            encode_cell(&attribute.data)
+        )
+    })?;
+    Ok(attribute.attribute_id.unwrap())
+}
+
+pub async fn optional_noun_id(
+    clwm: &mut Clwm,
+    reference: &Option<String>,
+) -> anyhow::Result<Option<i64>> {
+    match reference {
+        Some(reference) => Ok(Some(noun_id(clwm, reference).await?)),
+        None => Ok(None),
+    }
+}
+
+pub async fn optional_attribute_type_id(
+    clwm: &mut Clwm,
+    reference: &Option<String>,
+) -> anyhow::Result<Option<i64>> {
+    match reference {
+        Some(reference) => Ok(Some(attribute_type_id(clwm, reference).await?)),
+        None => Ok(None),
+    }
+}
+
+pub async fn optional_attribute_id(
+    clwm: &mut Clwm,
+    reference: &Option<String>,
+) -> anyhow::Result<Option<i64>> {
+    match reference {
+        Some(reference) => Ok(Some(attribute_id(clwm, reference).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Picks one of several matches, asking on the terminal. Partial matches
+/// are always confirmed, even when there is only one. Prompts go to stderr
+/// so structured output on stdout stays parseable.
+fn choose<T>(
+    kind: &str,
+    reference: &str,
+    mut candidates: Vec<T>,
+    partial: bool,
+    describe: impl Fn(&T) -> String,
+) -> anyhow::Result<T> {
+    if candidates.len() == 1 && !partial {
+        return Ok(candidates.remove(0));
+    }
+    let descriptions: Vec<String> = candidates.iter().map(describe).collect();
+    if !std::io::stdin().is_terminal() {
+        if partial {
+            anyhow::bail!(
+                "no {} is named \"{}\", use an id or the full name of one of: {}",
+                kind,
+                reference,
+                descriptions.join(", ")
+            );
+        }
+        anyhow::bail!(
+            "\"{}\" matches {} {}s, use an id instead: {}",
+            reference,
+            candidates.len(),
+            kind,
+            descriptions.join(", ")
+        );
+    }
+    if partial {
+        eprintln!(
+            "No {} is named \"{}\", but some partly match:",
+            kind, reference
+        );
+    } else {
+        eprintln!("\"{}\" matches several {}s:", reference, kind);
+    }
+    for (index, description) in descriptions.iter().enumerate() {
+        eprintln!("  {}) {}", index + 1, description);
+    }
+    loop {
+        eprint!("Which one? [1-{}]: ", candidates.len());
+        std::io::stderr().flush()?;
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer)? == 0 {
+            anyhow::bail!("no {} was chosen for \"{}\"", kind, reference);
+        }
+        match answer.trim().parse::<usize>() {
+            Ok(choice) if (1..=candidates.len()).contains(&choice) => {
+                return Ok(candidates.swap_remove(choice - 1))
+            }
+            _ => eprintln!("Enter a number from the list"),
+        }
+    }
+}
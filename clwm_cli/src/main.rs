@@ -1,12 +1,38 @@
+pub mod address;
 pub mod command_macros;
-use std::{fs::File, io::Read, path::PathBuf};
+pub mod output;
+pub mod shell;
+pub mod tui;
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+};
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use clwm_lib::{
+    calendar::{
+        date_to_day, day_to_date, era_of_year, format_date, month_length, moon_phases, weekday,
+        weekday_index, CalendarDate,
+    },
     clwm::Clwm,
     data_interface::DataInterfaceType,
-    model::{DataObject, DataTypeDefinition},
+    graph::{render_dot, render_mermaid},
+    model::{
+        Attribute, AttributeRequirement, AttributeType, CalendarDefinition, DataObject,
+        DataTypeDefinition, EventRole, GraphFilter, Noun, NounValidationIssue, RedactionRules,
+        Relationship, RelationshipCardinality, RollNounFilter, RollResult, RollTable, Template,
+        TimelineFilter, TraversalDirection, TraversalOptions, VaultImportPlan, VaultMapping,
+        Viewer, Visibility, WorldBundle,
+    },
+    roll::RollGenerator,
+    table::NounTable,
+    vault::VaultNote,
+    wiki::WikiPage,
 };
+use output::{Output, OutputFormat};
+use serde::Serialize;
 
 #[derive(Parser)] // requires `derive` feature
 #[command(name = "clwm")]
@@ -14,6 +40,10 @@ use clwm_lib::{
 struct Cli {
     #[arg(short, long)]
     file: Option<String>,
+    #[arg(long, global = true)]
+    viewer: Option<String>,
+    #[arg(long, global = true, value_enum)]
+    output: Option<OutputFormat>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -40,6 +70,150 @@ enum Commands {
         #[command(subcommand)]
         command: GetSubcommands,
     },
+    Asset {
+        #[command(subcommand)]
+        command: AssetSubcommands,
+    },
+    Schema {
+        #[command(subcommand)]
+        command: SchemaSubcommands,
+    },
+    Check {
+        #[command(subcommand)]
+        command: CheckSubcommands,
+    },
+    Template {
+        #[command(subcommand)]
+        command: TemplateSubcommands,
+    },
+    Relate {
+        source_noun_id: String,
+        relationship: String,
+        target_noun_id: String,
+        #[arg(short, long)]
+        metadata: Option<String>,
+    },
+    Unrelate {
+        source_noun_id: String,
+        relationship: String,
+        target_noun_id: String,
+    },
+    Relations {
+        id: String,
+    },
+    #[command(args_conflicts_with_subcommands = true)]
+    Graph {
+        #[command(subcommand)]
+        command: Option<GraphSubcommands>,
+        #[command(flatten)]
+        render: GraphRenderArgs,
+    },
+    Tree {
+        root_id: String,
+        #[arg(short, long)]
+        ancestors: bool,
+    },
+    Event {
+        #[command(subcommand)]
+        command: EventSubcommands,
+    },
+    Timeline {
+        #[arg(short, long, num_args = 2, value_names = ["FROM", "TO"])]
+        between: Option<Vec<String>>,
+        #[arg(short, long)]
+        involving: Option<String>,
+    },
+    RollTable {
+        #[command(subcommand)]
+        command: RollTableSubcommands,
+    },
+    Roll {
+        #[arg(required_unless_present_any = ["noun_type", "attribute_type"])]
+        roll_table: Option<String>,
+        #[arg(short = 't', long, conflicts_with = "roll_table")]
+        noun_type: Option<String>,
+        #[arg(long, requires = "noun_type")]
+        include_subtypes: bool,
+        #[arg(short, long, conflicts_with = "roll_table")]
+        attribute_type: Option<String>,
+        #[arg(short, long)]
+        seed: Option<u64>,
+        #[arg(short, long, default_value_t = 1)]
+        count: usize,
+        #[arg(long, value_name = "NOUN_TYPE")]
+        save_as: Option<String>,
+    },
+    RollDice {
+        noun_id: String,
+        attribute_type: String,
+        #[arg(short, long)]
+        seed: Option<u64>,
+        #[arg(short, long, default_value_t = 1)]
+        count: usize,
+    },
+    Calendar {
+        #[arg(short, long, global = true)]
+        calendar: Option<String>,
+        #[command(subcommand)]
+        command: CalendarSubcommands,
+    },
+    Move {
+        id: String,
+        #[arg(short, long, required_unless_present = "root")]
+        into: Option<String>,
+        #[arg(short, long, conflicts_with = "into")]
+        root: bool,
+    },
+    Visibility {
+        #[command(subcommand)]
+        command: VisibilitySubcommands,
+    },
+    Export {
+        path: String,
+        #[arg(requires = "redact")]
+        database: Option<String>,
+        #[arg(short = 'o', long)]
+        format: Option<String>,
+        #[arg(long, conflicts_with = "redact")]
+        history: bool,
+        #[arg(
+            long,
+            value_name = "RULES",
+            requires = "database",
+            conflicts_with = "format"
+        )]
+        redact: Option<PathBuf>,
+        #[arg(long = "type", value_name = "NOUN_TYPE", conflicts_with = "redact")]
+        noun_type: Option<String>,
+    },
+    Import {
+        path: PathBuf,
+        #[arg(short = 'o', long)]
+        format: Option<String>,
+        #[arg(long)]
+        dry_run: bool,
+        #[arg(long, value_name = "MAPPING")]
+        mapping: Option<PathBuf>,
+        #[arg(long = "type", value_name = "NOUN_TYPE")]
+        noun_type: Option<String>,
+    },
+    Site {
+        #[command(subcommand)]
+        command: SiteSubcommands,
+    },
+    Shell,
+    Tui,
+}
+
+#[derive(Subcommand)]
+enum SiteSubcommands {
+    Build { out_dir: PathBuf },
+}
+
+#[derive(Subcommand)]
+enum VisibilitySubcommands {
+    Noun { id: String, visibility: String },
+    Attribute { id: String, visibility: String },
 }
 
 #[derive(Subcommand)]
@@ -51,11 +225,17 @@ enum NewSubcommands {
         r#type: Option<String>,
         #[arg(short, long)]
         metadata: Option<String>,
+        #[arg(long)]
+        template: Option<String>,
+        #[arg(short, long, value_parser = parse_placeholder, requires = "template")]
+        set: Vec<(String, String)>,
     },
     NounType {
         #[arg(short, long)]
         r#type: Option<String>,
         #[arg(short, long)]
+        parent: Option<String>,
+        #[arg(short, long)]
         metadata: Option<String>,
     },
     DataType {
@@ -71,16 +251,18 @@ enum NewSubcommands {
         data_type: Option<String>,
         #[arg(short = 'u', long)]
         multiple_allowed: Option<bool>,
+        #[arg(short = 'x', long)]
+        formula: Option<String>,
         #[arg(short, long)]
         metadata: Option<String>,
     },
     Attribute {
         #[arg(short = 'o', long)]
-        parent_noun_id: Option<i64>,
+        parent_noun_id: Option<String>,
         #[arg(short = 't', long)]
-        parent_attribute_id: Option<i64>,
+        parent_attribute_id: Option<String>,
         #[arg(short, long)]
-        attribute_type_id: Option<i64>,
+        attribute_type_id: Option<String>,
         #[arg(short, long)]
         data: Option<PathBuf>,
         #[arg(short = 'v', long)]
@@ -88,6 +270,16 @@ enum NewSubcommands {
         #[arg(short, long)]
         metadata: Option<String>,
     },
+    RelationshipType {
+        #[arg(short, long)]
+        name: Option<String>,
+        #[arg(short, long)]
+        inverse_name: Option<String>,
+        #[arg(short, long)]
+        cardinality: Option<String>,
+        #[arg(short, long)]
+        metadata: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -97,6 +289,8 @@ enum FindSubcommands {
         name: Option<String>,
         #[arg(short, long)]
         r#type: Option<String>,
+        #[arg(short, long, requires = "type")]
+        include_subtypes: bool,
     },
     NounType {
         #[arg(short, long)]
@@ -114,22 +308,29 @@ enum FindSubcommands {
     },
     Attribute {
         #[arg(short = 'o', long)]
-        parent_noun_id: Option<i64>,
+        parent_noun_id: Option<String>,
         #[arg(short = 't', long)]
-        parent_attribute_id: Option<i64>,
+        parent_attribute_id: Option<String>,
         #[arg(short, long)]
-        attribute_type_id: Option<i64>,
+        attribute_type_id: Option<String>,
         #[arg(short, long)]
         data: Option<PathBuf>,
         #[arg(short = 'v', long)]
         data_type_version: Option<i64>,
     },
+    MapEntry {
+        #[arg(short, long)]
+        attribute_type_id: String,
+        #[arg(short, long)]
+        key: String,
+    },
+    RelationshipType,
 }
 
 #[derive(Subcommand)]
 enum UpdateSubcommands {
     Noun {
-        id: i64,
+        id: String,
         #[arg(short, long)]
         name: Option<String>,
         #[arg(short, long)]
@@ -138,10 +339,12 @@ enum UpdateSubcommands {
         metadata: Option<String>,
     },
     NounType {
-        id: i64,
+        id: String,
         #[arg(short, long)]
         r#type: Option<String>,
         #[arg(short, long)]
+        parent: Option<String>,
+        #[arg(short, long)]
         metadata: Option<String>,
     },
     DataType {
@@ -150,16 +353,18 @@ enum UpdateSubcommands {
         defintion: Option<PathBuf>,
     },
     AttributeType {
-        id: i64,
+        id: String,
         #[arg(short, long)]
         name: Option<String>,
         #[arg(short = 'u', long)]
         multiple_allowed: Option<bool>,
+        #[arg(short = 'x', long)]
+        formula: Option<String>,
         #[arg(short, long)]
         metadata: Option<String>,
     },
     Attribute {
-        id: i64,
+        id: String,
         #[arg(short, long)]
         data: Option<PathBuf>,
         #[arg(short = 'v', long)]
@@ -171,11 +376,225 @@ enum UpdateSubcommands {
 
 #[derive(Subcommand)]
 enum GetSubcommands {
-    Noun { id: i64 },
-    NounType { id: i64 },
+    Noun { id: String },
+    NounType { id: String },
     DataType { name: String },
-    AttributeType { id: i64 },
-    Attribute { id: i64 },
+    AttributeType { id: String },
+    Attribute { id: String },
+}
+
+#[derive(Subcommand)]
+enum AssetSubcommands {
+    Add {
+        path: PathBuf,
+        #[arg(short, long)]
+        mime_type: Option<String>,
+    },
+    Get {
+        hash: String,
+        output: PathBuf,
+    },
+    List,
+    Gc,
+}
+
+#[derive(Subcommand)]
+enum SchemaSubcommands {
+    Set {
+        noun_type: String,
+        #[arg(short, long)]
+        attribute_type_id: String,
+        #[arg(short, long)]
+        requirement: String,
+        #[arg(long)]
+        min_count: Option<i64>,
+        #[arg(long)]
+        max_count: Option<i64>,
+        #[arg(short, long)]
+        default: Option<PathBuf>,
+    },
+    Remove {
+        noun_type: String,
+        #[arg(short, long)]
+        attribute_type_id: String,
+    },
+    Show {
+        noun_type: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum CheckSubcommands {
+    Noun { id: String },
+}
+
+#[derive(Subcommand)]
+enum GraphSubcommands {
+    Traverse {
+        id: String,
+        #[command(flatten)]
+        traversal: TraversalArgs,
+    },
+    Path {
+        from_id: String,
+        to_id: String,
+        #[command(flatten)]
+        traversal: TraversalArgs,
+    },
+    Reindex,
+}
+
+#[derive(Args)]
+struct GraphRenderArgs {
+    #[arg(long)]
+    root: Option<String>,
+    #[arg(short, long, requires = "root")]
+    depth: Option<i64>,
+    #[arg(short = 't', long = "type")]
+    noun_type: Vec<String>,
+    #[arg(short = 'o', long, default_value = "dot")]
+    format: String,
+}
+
+#[derive(Args)]
+struct TraversalArgs {
+    #[arg(short, long, default_value_t = 5)]
+    depth: i64,
+    #[arg(short = 'r', long, default_value = "outgoing")]
+    direction: String,
+    #[arg(short, long)]
+    attribute_type_id: Vec<String>,
+    #[arg(short = 'l', long)]
+    relationship: Vec<String>,
+}
+
+impl TraversalArgs {
+    async fn to_options(&self, clwm: &mut Clwm) -> anyhow::Result<TraversalOptions> {
+        let mut attribute_type_ids = Vec::new();
+        for attribute_type in self.attribute_type_id.iter() {
+            attribute_type_ids.push(address::attribute_type_id(clwm, attribute_type).await?);
+        }
+        Ok(TraversalOptions {
+            max_depth: self.depth,
+            direction: self.direction.parse::<TraversalDirection>()?,
+            attribute_type_ids: if attribute_type_ids.is_empty() {
+                None
+            } else {
+                Some(attribute_type_ids)
+            },
+            relationship_types: if self.relationship.is_empty() {
+                None
+            } else {
+                Some(self.relationship.clone())
+            },
+        })
+    }
+}
+
+#[derive(Subcommand)]
+enum EventSubcommands {
+    Set {
+        id: String,
+        #[arg(short, long, allow_hyphen_values = true)]
+        start: String,
+        #[arg(short, long, allow_hyphen_values = true)]
+        end: Option<String>,
+    },
+    Add {
+        event_id: String,
+        noun_id: String,
+        #[arg(short, long, default_value = "participant")]
+        role: String,
+    },
+    Remove {
+        event_id: String,
+        noun_id: String,
+        #[arg(short, long, default_value = "participant")]
+        role: String,
+    },
+    Show {
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum RollTableSubcommands {
+    Set { path: PathBuf },
+    Remove { roll_table_name: String },
+    Show { roll_table_name: String },
+    List,
+}
+
+#[derive(Subcommand)]
+enum CalendarSubcommands {
+    Set {
+        calendar_name: String,
+        path: PathBuf,
+    },
+    Remove {
+        calendar_name: String,
+    },
+    List,
+    Show {
+        #[arg(allow_hyphen_values = true)]
+        year: i64,
+        month: usize,
+    },
+    Convert {
+        #[arg(allow_hyphen_values = true)]
+        date: String,
+    },
+    Add {
+        #[arg(allow_hyphen_values = true)]
+        date: String,
+        #[arg(allow_hyphen_values = true)]
+        days: i64,
+    },
+    Diff {
+        #[arg(allow_hyphen_values = true)]
+        from: String,
+        #[arg(allow_hyphen_values = true)]
+        to: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TemplateSubcommands {
+    Set { path: PathBuf },
+    Remove { template_name: String },
+    Show { template_name: String },
+    List,
+}
+
+#[derive(Serialize)]
+struct MapEntryMatch {
+    attribute: Attribute,
+    value: DataObject,
+}
+
+#[derive(Serialize)]
+struct VaultImportReport {
+    plan: VaultImportPlan,
+    nouns: Vec<Noun>,
+}
+
+#[derive(Serialize)]
+struct WorldDay {
+    day: i64,
+    date: Option<String>,
+    weekday: Option<String>,
+    moons: Vec<MoonPhase>,
+}
+
+#[derive(Serialize)]
+struct MoonPhase {
+    moon: String,
+    phase: String,
+}
+
+#[derive(Serialize)]
+struct DayDifference {
+    days: i64,
 }
 
 #[tokio::main]
@@ -191,28 +610,72 @@ async fn main() -> anyhow::Result<()> {
             )
             .await?;
         }
+        Commands::Shell => shell::run_shell(&cli).await?,
+        Commands::Tui => tui::run_tui(&cli).await?,
+        _ => {
+            let mut clwm = get_clwm(&cli).await?;
+            run_command(&cli, &mut clwm).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn run_command(cli: &Cli, clwm: &mut Clwm) -> anyhow::Result<()> {
+    let output = Output::new(cli.output);
+
+    match &cli.command {
+        Commands::Create { .. } | Commands::Shell | Commands::Tui => {
+            anyhow::bail!("this command cannot be run from the shell")
+        }
         Commands::New { command } => match command {
             NewSubcommands::Noun {
                 name,
                 r#type,
                 metadata,
+                template,
+                set,
             } => {
-                let mut clwm = get_clwm(&cli).await?;
                 let name = arg_input!(name, "What is the name of this noun?:");
-                let noun_type = arg_input!(r#type, "What is the type of this noun?:");
-                let metadata = arg_input!(metadata, "What is the metadata of this noun?:");
-                let noun = clwm.new_noun(name, noun_type, metadata).await?;
-                println!("New {:?}", noun);
+                let noun = match template {
+                    Some(template) => {
+                        let metadata = metadata.clone().unwrap_or_default();
+                        clwm.instantiate_template(
+                            template.to_string(),
+                            name,
+                            r#type.clone(),
+                            set.iter().cloned().collect(),
+                            metadata,
+                        )
+                        .await?
+                    }
+                    None => {
+                        let noun_type = arg_input!(r#type, "What is the type of this noun?:");
+                        let metadata = arg_input!(metadata, "What is the metadata of this noun?:");
+                        clwm.new_noun(name, noun_type, metadata).await?
+                    }
+                };
+                output.record(&noun, format!("New noun {}", describe_noun(&noun)))?;
             }
-            NewSubcommands::NounType { r#type, metadata } => {
-                let mut clwm = get_clwm(&cli).await?;
+            NewSubcommands::NounType {
+                r#type,
+                parent,
+                metadata,
+            } => {
                 let noun_type = arg_input!(r#type, "What is the name of this noun type?:");
                 let metadata = arg_input!(metadata, "What is the metadata of this noun type?");
-                let noun_type = clwm.new_noun_type(noun_type, metadata).await?;
-                println!("New {:?}", noun_type);
+                let noun_type = clwm
+                    .new_noun_type(noun_type, parent.clone(), metadata)
+                    .await?;
+                output.record(
+                    &noun_type,
+                    format!(
+                        "New noun type {}. {}",
+                        noun_type.noun_type_id.unwrap(),
+                        noun_type.noun_type
+                    ),
+                )?;
             }
             NewSubcommands::DataType { name, defintion } => {
-                let mut clwm = get_clwm(&cli).await?;
                 let name = arg_input!(name, "What is the name of this data type?:");
                 let defintion_string = match defintion {
                     Some(defintion) => read_file(defintion.to_path_buf())?,
@@ -220,15 +683,57 @@ async fn main() -> anyhow::Result<()> {
                 };
                 let defintion = toml::from_str::<DataTypeDefinition>(&defintion_string)?;
                 let data_type = clwm.new_data_type(name, defintion).await?;
-                println!("New {:?}", data_type);
+                output.record(
+                    &data_type,
+                    format!(
+                        "New data type {} version {}",
+                        data_type.name,
+                        data_type.version.unwrap()
+                    ),
+                )?;
+            }
+            NewSubcommands::RelationshipType {
+                name,
+                inverse_name,
+                cardinality,
+                metadata,
+            } => {
+                let name = arg_input!(name, "What is the name of this relationship type?:");
+                let inverse_name = arg_input!(
+                    inverse_name,
+                    "What is the name of the inverse of this relationship type?:"
+                );
+                let cardinality = arg_input!(
+                    cardinality,
+                    "What is the cardinality of this relationship type? (OneToOne/OneToMany/ManyToOne/ManyToMany):"
+                );
+                let metadata =
+                    arg_input!(metadata, "What is the metadata of this relationship type?:");
+                let relationship_type = clwm
+                    .new_relationship_type(
+                        name,
+                        inverse_name,
+                        cardinality.parse::<RelationshipCardinality>()?,
+                        metadata,
+                    )
+                    .await?;
+                output.record(
+                    &relationship_type,
+                    format!(
+                        "New relationship type {}. {} / {}",
+                        relationship_type.relationship_type_id.unwrap(),
+                        relationship_type.relationship_name,
+                        relationship_type.inverse_name
+                    ),
+                )?;
             }
             NewSubcommands::AttributeType {
                 name,
                 data_type,
                 multiple_allowed,
+                formula,
                 metadata,
             } => {
-                let mut clwm = get_clwm(&cli).await?;
                 let name = arg_input!(name, "What is the name of this attribute type?:");
                 let data_type =
                     arg_input!(data_type, "What is the data type of this attribute type?:");
@@ -240,9 +745,21 @@ async fn main() -> anyhow::Result<()> {
                 let metadata =
                     arg_input!(metadata, "What is the metadata of this attribute type?:");
                 let attribute_type = clwm
-                    .new_attribute_type(name, multiple_allowed, data_type, metadata)
+                    .new_attribute_type(
+                        name,
+                        multiple_allowed,
+                        data_type,
+                        formula.clone(),
+                        metadata,
+                    )
                     .await?;
-                println!("New {:?}", attribute_type);
+                output.record(
+                    &attribute_type,
+                    format!(
+                        "New attribute type {}",
+                        describe_attribute_type(&attribute_type)
+                    ),
+                )?;
             }
             NewSubcommands::Attribute {
                 parent_noun_id,
@@ -252,49 +769,61 @@ async fn main() -> anyhow::Result<()> {
                 data_type_version,
                 metadata,
             } => {
-                let mut clwm = get_clwm(&cli).await?;
-                let attribute_type_id =
-                    arg_input!(attribute_type_id, "What is the id of the attribute type?:")
-                        .parse::<i64>()?;
-                let (parent_noun_id, parent_attribute_id) = if parent_noun_id.is_none()
-                    && parent_attribute_id.is_none()
-                {
-                    let parent_type = arg_input!(
-                        None::<String>,
-                        "What is the parent of this attribute? (Attribute/Noun):"
-                    );
-                    if parent_type.to_lowercase() == "attribute" {
-                        (
-                            None,
-                            Some(
-                                arg_input!(
-                                    parent_attribute_id,
-                                    "What is the id of the parent attribute?:"
-                                )
-                                .parse::<i64>()?,
-                            ),
-                        )
-                    } else if parent_type.to_lowercase() == "noun" {
+                let attribute_type_id = address::attribute_type_id(
+                    clwm,
+                    &arg_input!(attribute_type_id, "What is the attribute type?:"),
+                )
+                .await?;
+                let (parent_noun_id, parent_attribute_id) =
+                    if parent_noun_id.is_none() && parent_attribute_id.is_none() {
+                        let parent_type = arg_input!(
+                            None::<String>,
+                            "What is the parent of this attribute? (Attribute/Noun):"
+                        );
+                        if parent_type.to_lowercase() == "attribute" {
+                            (
+                                None,
+                                Some(
+                                    address::attribute_id(
+                                        clwm,
+                                        &arg_input!(
+                                            parent_attribute_id,
+                                            "What is the parent attribute?:"
+                                        ),
+                                    )
+                                    .await?,
+                                ),
+                            )
+                        } else if parent_type.to_lowercase() == "noun" {
+                            (
+                                Some(
+                                    address::noun_id(
+                                        clwm,
+                                        &arg_input!(parent_noun_id, "What is the parent noun?:"),
+                                    )
+                                    .await?,
+                                ),
+                                None,
+                            )
+                        } else {
+                            anyhow::bail!("invalid parent type {:?}", parent_type)
+                        }
+                    } else {
                         (
-                            Some(
-                                arg_input!(parent_noun_id, "What is the id of the parent noun?:")
-                                    .parse::<i64>()?,
-                            ),
-                            None,
+                            address::optional_noun_id(clwm, parent_noun_id).await?,
+                            address::optional_attribute_id(clwm, parent_attribute_id).await?,
                         )
-                    } else {
-                        println!("Invalid parent type");
-                        return Ok(());
-                    }
-                } else {
-                    (*parent_noun_id, *parent_attribute_id)
-                };
-                let data_string = match data {
-                    Some(data) => read_file(data.to_path_buf())?,
-                    None => open_editor("toml".to_string())?,
+                    };
+                // Computed attributes hold no data of their own, so none is asked for.
+                let computed = clwm
+                    .get_attribute_type_by_id(attribute_type_id)
+                    .await?
+                    .is_some_and(|attribute_type| attribute_type.formula.is_some());
+                let data = match data {
+                    Some(data) => toml::from_str::<DataObject>(&read_file(data.to_path_buf())?)?,
+                    None if computed => DataObject::Null,
+                    None => toml::from_str::<DataObject>(&open_editor("toml".to_string())?)?,
                 };
-
-                let data = toml::from_str::<DataObject>(&data_string)?;
                 let data_type_version =
                     arg_input!(data_type_version, "What is the version of the data type?:")
                         .parse::<i64>()?;
@@ -309,56 +838,106 @@ async fn main() -> anyhow::Result<()> {
                         metadata,
                     )
                     .await?;
-                println!("New {:?}", attribute);
+                output.record(
+                    &attribute,
+                    format!("New attribute {}", describe_attribute(&attribute)),
+                )?;
             }
         },
         Commands::Find { command } => match command {
-            FindSubcommands::Noun { name, r#type } => {
-                let mut clwm = get_clwm(&cli).await?;
-                for noun in clwm.get_all_nouns().await?.iter() {
-                    println!(
-                        "{}. {} {} {}",
-                        noun.noun_id.unwrap(),
-                        noun.name,
-                        noun.noun_type,
-                        noun.last_changed.unwrap().to_rfc3339()
-                    );
-                }
+            FindSubcommands::Noun {
+                name,
+                r#type,
+                include_subtypes,
+            } => {
+                let nouns = match r#type {
+                    Some(noun_type) => {
+                        clwm.find_nouns_by_noun_type(noun_type.to_string(), *include_subtypes)
+                            .await?
+                    }
+                    None => clwm.get_all_nouns().await?,
+                };
+                let rows = nouns
+                    .iter()
+                    .map(|noun| {
+                        vec![
+                            noun.noun_id.unwrap().to_string(),
+                            noun.name.clone(),
+                            noun.noun_type.clone(),
+                            noun.last_changed.unwrap().to_rfc3339(),
+                        ]
+                    })
+                    .collect();
+                output.list(&nouns, &["ID", "NAME", "TYPE", "CHANGED"], rows)?;
             }
             FindSubcommands::NounType { r#type } => {
-                let mut clwm = get_clwm(&cli).await?;
-                for noun_type in clwm.get_all_noun_types().await?.iter() {
-                    println!(
-                        "{}. {} {}",
-                        noun_type.noun_type_id.unwrap(),
-                        noun_type.noun_type,
-                        noun_type.last_changed.unwrap().to_rfc3339()
-                    );
-                }
+                let noun_types = clwm.get_all_noun_types().await?;
+                let rows = noun_types
+                    .iter()
+                    .map(|noun_type| {
+                        vec![
+                            noun_type.noun_type_id.unwrap().to_string(),
+                            noun_type.noun_type.clone(),
+                            optional_id(noun_type.parent_noun_type_id),
+                            noun_type.last_changed.unwrap().to_rfc3339(),
+                        ]
+                    })
+                    .collect();
+                output.list(&noun_types, &["ID", "TYPE", "PARENT", "CHANGED"], rows)?;
             }
             FindSubcommands::DataType { name } => {
-                let mut clwm = get_clwm(&cli).await?;
-                for data_type in clwm.get_all_data_types().await?.iter() {
-                    println!(
-                        "{}. {} {}",
-                        data_type.name,
-                        data_type.version.unwrap(),
-                        data_type.change_date.unwrap().to_rfc3339()
-                    );
-                }
+                let data_types = clwm.get_all_data_types().await?;
+                let rows = data_types
+                    .iter()
+                    .map(|data_type| {
+                        vec![
+                            data_type.name.clone(),
+                            data_type.version.unwrap().to_string(),
+                            data_type.change_date.unwrap().to_rfc3339(),
+                        ]
+                    })
+                    .collect();
+                output.list(&data_types, &["NAME", "VERSION", "CHANGED"], rows)?;
+            }
+            FindSubcommands::RelationshipType => {
+                let relationship_types = clwm.get_all_relationship_types().await?;
+                let rows = relationship_types
+                    .iter()
+                    .map(|relationship_type| {
+                        vec![
+                            relationship_type.relationship_type_id.unwrap().to_string(),
+                            relationship_type.relationship_name.clone(),
+                            relationship_type.inverse_name.clone(),
+                            format!("{:?}", relationship_type.cardinality),
+                            relationship_type.last_changed.unwrap().to_rfc3339(),
+                        ]
+                    })
+                    .collect();
+                output.list(
+                    &relationship_types,
+                    &["ID", "NAME", "INVERSE", "CARDINALITY", "CHANGED"],
+                    rows,
+                )?;
             }
             FindSubcommands::AttributeType { name, data_type } => {
-                let mut clwm = get_clwm(&cli).await?;
-                for attribute_type in clwm.get_all_attribute_types().await?.iter() {
-                    println!(
-                        "{}. {} {} {} {}",
-                        attribute_type.attribute_type_id.unwrap(),
-                        attribute_type.attribute_name,
-                        attribute_type.data_type,
-                        attribute_type.multiple_allowed,
-                        attribute_type.last_changed.unwrap().to_rfc3339()
-                    );
-                }
+                let attribute_types = clwm.get_all_attribute_types().await?;
+                let rows = attribute_types
+                    .iter()
+                    .map(|attribute_type| {
+                        vec![
+                            attribute_type.attribute_type_id.unwrap().to_string(),
+                            attribute_type.attribute_name.clone(),
+                            attribute_type.data_type.clone(),
+                            attribute_type.multiple_allowed.to_string(),
+                            attribute_type.last_changed.unwrap().to_rfc3339(),
+                        ]
+                    })
+                    .collect();
+                output.list(
+                    &attribute_types,
+                    &["ID", "NAME", "DATA TYPE", "MULTIPLE", "CHANGED"],
+                    rows,
+                )?;
             }
             FindSubcommands::Attribute {
                 parent_noun_id,
@@ -367,19 +946,52 @@ async fn main() -> anyhow::Result<()> {
                 data,
                 data_type_version,
             } => {
-                let mut clwm = get_clwm(&cli).await?;
-                for attribute in clwm.get_all_attributes().await?.iter() {
-                    println!(
-                        "{}. {} {} {} {} {} {}",
-                        attribute.attribute_id.unwrap(),
-                        attribute.attribute_type_id,
-                        attribute.parent_noun_id.unwrap_or(0),
-                        attribute.parent_attribute_id.unwrap_or(0),
-                        attribute.data_type_version,
-                        attribute.last_changed.unwrap().to_rfc3339(),
-                        attribute.metadata
-                    );
-                }
+                let attributes = clwm.get_all_attributes().await?;
+                let rows = attributes
+                    .iter()
+                    .map(|attribute| {
+                        vec![
+                            attribute.attribute_id.unwrap().to_string(),
+                            attribute.attribute_type_id.to_string(),
+                            optional_id(attribute.parent_noun_id),
+                            optional_id(attribute.parent_attribute_id),
+                            attribute.data_type_version.to_string(),
+                            attribute.last_changed.unwrap().to_rfc3339(),
+                            attribute.metadata.clone(),
+                        ]
+                    })
+                    .collect();
+                output.list(
+                    &attributes,
+                    &[
+                        "ID", "TYPE", "NOUN", "PARENT", "VERSION", "CHANGED", "METADATA",
+                    ],
+                    rows,
+                )?;
+            }
+            FindSubcommands::MapEntry {
+                attribute_type_id,
+                key,
+            } => {
+                let attribute_type_id = address::attribute_type_id(clwm, attribute_type_id).await?;
+                let entries: Vec<MapEntryMatch> = clwm
+                    .find_attributes_by_map_key(attribute_type_id, key.to_string())
+                    .await?
+                    .into_iter()
+                    .map(|(attribute, value)| MapEntryMatch { attribute, value })
+                    .collect();
+                let rows = entries
+                    .iter()
+                    .map(|entry| {
+                        vec![
+                            entry.attribute.attribute_id.unwrap().to_string(),
+                            optional_id(entry.attribute.parent_noun_id),
+                            optional_id(entry.attribute.parent_attribute_id),
+                            serde_json::to_string(&entry.value).unwrap_or_default(),
+                        ]
+                    })
+                    .collect();
+                output.list(&entries, &["ID", "NOUN", "PARENT", "VALUE"], rows)?;
             }
         },
         Commands::Update { command } => match command {
@@ -389,8 +1001,8 @@ async fn main() -> anyhow::Result<()> {
                 r#type,
                 metadata,
             } => {
-                let mut clwm = get_clwm(&cli).await?;
-                if let Some(mut noun) = clwm.get_noun_by_id(*id).await? {
+                let id = address::noun_id(clwm, id).await?;
+                if let Some(mut noun) = clwm.get_noun_by_id(id).await? {
                     if let Some(name_change) = name {
                         noun.name = name_change.to_string();
                     }
@@ -403,34 +1015,56 @@ async fn main() -> anyhow::Result<()> {
 
                     let updated_noun = clwm.update_noun(noun).await?;
 
-                    println!("Updated {:?}", updated_noun);
+                    output.record(
+                        &updated_noun,
+                        format!("Updated noun {}", describe_noun(&updated_noun)),
+                    )?;
                 } else {
-                    println!("No noun exists with id {}", id);
+                    output.message(format!("No noun exists with id {}", id))?;
                 }
             }
             UpdateSubcommands::NounType {
                 id,
                 r#type,
+                parent,
                 metadata,
             } => {
-                let mut clwm = get_clwm(&cli).await?;
-                if let Some(mut noun_type) = clwm.get_noun_type_by_id(*id).await? {
+                let id = address::noun_type_id(clwm, id).await?;
+                if let Some(mut noun_type) = clwm.get_noun_type_by_id(id).await? {
                     if let Some(noun_type_change) = r#type {
                         noun_type.noun_type = noun_type_change.to_string();
                     }
+                    if let Some(parent_change) = parent {
+                        noun_type.parent_noun_type_id = if parent_change.is_empty() {
+                            None
+                        } else if let Some(parent_noun_type) = clwm
+                            .get_noun_type_by_name(parent_change.to_string())
+                            .await?
+                        {
+                            parent_noun_type.noun_type_id
+                        } else {
+                            output.message(format!(
+                                "No noun type exists with name {}",
+                                parent_change
+                            ))?;
+                            return Ok(());
+                        };
+                    }
                     if let Some(metadata_change) = metadata {
                         noun_type.metadata = metadata_change.to_string();
                     }
 
                     let updated_noun_type = clwm.update_noun_type(noun_type).await?;
 
-                    println!("Updated {:?}", updated_noun_type);
+                    output.record(
+                        &updated_noun_type,
+                        format!("Updated noun type {}. {}", id, updated_noun_type.noun_type),
+                    )?;
                 } else {
-                    println!("No noun exists with id {}", id);
+                    output.message(format!("No noun type exists with id {}", id))?;
                 }
             }
             UpdateSubcommands::DataType { name, defintion } => {
-                let mut clwm = get_clwm(&cli).await?;
                 if let Some(mut data_type) =
                     clwm.get_latest_data_type_by_name(name.to_string()).await?
                 {
@@ -441,34 +1075,55 @@ async fn main() -> anyhow::Result<()> {
                     }
                     let updated_data_type = clwm.update_data_type(data_type).await?;
 
-                    println!("Updated {:?}", updated_data_type);
+                    output.record(
+                        &updated_data_type,
+                        format!(
+                            "Updated data type {} to version {}",
+                            updated_data_type.name,
+                            updated_data_type.version.unwrap()
+                        ),
+                    )?;
                 } else {
-                    println!("No data type exists with name {}", name);
+                    output.message(format!("No data type exists with name {}", name))?;
                 }
             }
             UpdateSubcommands::AttributeType {
                 id,
                 name,
                 multiple_allowed,
+                formula,
                 metadata,
             } => {
-                let mut clwm = get_clwm(&cli).await?;
-                if let Some(mut attribute_type) = clwm.get_attribute_type_by_id(*id).await? {
+                let id = address::attribute_type_id(clwm, id).await?;
+                if let Some(mut attribute_type) = clwm.get_attribute_type_by_id(id).await? {
                     if let Some(name_change) = name {
                         attribute_type.attribute_name = name_change.to_string();
                     }
                     if let Some(multiple_allowed_change) = multiple_allowed {
                         attribute_type.multiple_allowed = *multiple_allowed_change;
                     }
+                    if let Some(formula_change) = formula {
+                        attribute_type.formula = if formula_change.is_empty() {
+                            None
+                        } else {
+                            Some(formula_change.to_string())
+                        };
+                    }
                     if let Some(metadata_change) = metadata {
                         attribute_type.metadata = metadata_change.to_string();
                     }
 
                     let updated_attribute_type = clwm.update_attribute_type(attribute_type).await?;
 
-                    println!("Updated {:?}", updated_attribute_type);
+                    output.record(
+                        &updated_attribute_type,
+                        format!(
+                            "Updated attribute type {}",
+                            describe_attribute_type(&updated_attribute_type)
+                        ),
+                    )?;
                 } else {
-                    println!("No attribute type exists with id {}", id);
+                    output.message(format!("No attribute type exists with id {}", id))?;
                 }
             }
             UpdateSubcommands::Attribute {
@@ -477,8 +1132,8 @@ async fn main() -> anyhow::Result<()> {
                 data_type_version,
                 metadata,
             } => {
-                let mut clwm = get_clwm(&cli).await?;
-                if let Some(mut attribute) = clwm.get_attribute_by_id(*id).await? {
+                let id = address::attribute_id(clwm, id).await?;
+                if let Some(mut attribute) = clwm.get_attribute_by_id(id).await? {
                     if let Some(data_change) = data {
                         let data_string = read_file(data_change.to_path_buf())?;
                         let data = toml::from_str::<DataObject>(&data_string)?;
@@ -493,71 +1148,970 @@ async fn main() -> anyhow::Result<()> {
 
                     let updated_attribute = clwm.update_attribute(attribute).await?;
 
-                    println!("Updated {:?}", updated_attribute);
+                    output.record(
+                        &updated_attribute,
+                        format!(
+                            "Updated attribute {}",
+                            describe_attribute(&updated_attribute)
+                        ),
+                    )?;
                 } else {
-                    println!("No attribute exists with id {}", id);
+                    output.message(format!("No attribute exists with id {}", id))?;
                 }
             }
         },
         Commands::Get { command } => match command {
             GetSubcommands::Noun { id } => {
-                let mut clwm = get_clwm(&cli).await?;
-                if let Some(mut noun) = clwm.get_noun_by_id(*id).await? {
+                let id = address::noun_id(clwm, id).await?;
+                if let Some(mut noun) = clwm.get_noun_by_id(id).await? {
                     clwm.populate_noun(&mut noun).await?;
-                    println!("{}", toml::to_string(&noun)?);
+                    output.show(&noun)?;
                 } else {
-                    println!("No noun exists with id {}", id);
+                    output.message(format!("No noun exists with id {}", id))?;
                 }
             }
             GetSubcommands::NounType { id } => {
-                let mut clwm = get_clwm(&cli).await?;
-                if let Some(noun_type) = clwm.get_noun_type_by_id(*id).await? {
-                    println!("{}", toml::to_string(&noun_type)?);
+                let id = address::noun_type_id(clwm, id).await?;
+                if let Some(noun_type) = clwm.get_noun_type_by_id(id).await? {
+                    output.show(&noun_type)?;
                 } else {
-                    println!("No noun type exists with id {}", id);
+                    output.message(format!("No noun type exists with id {}", id))?;
                 }
             }
             GetSubcommands::DataType { name } => {
-                let mut clwm = get_clwm(&cli).await?;
                 if let Some(data_type) = clwm
                     .get_all_data_type_by_name(name.to_string())
                     .await?
                     .last()
                 {
-                    println!("{}", toml::to_string(&data_type)?);
+                    output.show(&data_type)?;
                 } else {
-                    println!("No data type exists with name {}", name);
+                    output.message(format!("No data type exists with name {}", name))?;
                 }
             }
             GetSubcommands::AttributeType { id } => {
-                let mut clwm = get_clwm(&cli).await?;
-                if let Some(attribute_type) = clwm.get_attribute_type_by_id(*id).await? {
-                    println!("{}", toml::to_string(&attribute_type)?);
+                let id = address::attribute_type_id(clwm, id).await?;
+                if let Some(attribute_type) = clwm.get_attribute_type_by_id(id).await? {
+                    output.show(&attribute_type)?;
                 } else {
-                    println!("No attribute type exists with id {}", id);
+                    output.message(format!("No attribute type exists with id {}", id))?;
                 }
             }
             GetSubcommands::Attribute { id } => {
-                let mut clwm = get_clwm(&cli).await?;
-                if let Some(mut attribute) = clwm.get_attribute_by_id(*id).await? {
+                let id = address::attribute_id(clwm, id).await?;
+                if let Some(mut attribute) = clwm.get_attribute_by_id(id).await? {
                     clwm.populate_attribute(&mut attribute).await?;
-                    println!("{}", toml::to_string(&attribute)?);
+                    output.show(&attribute)?;
+                } else {
+                    output.message(format!("No attribute exists with id {}", id))?;
+                }
+            }
+        },
+        Commands::Asset { command } => match command {
+            AssetSubcommands::Add { path, mime_type } => {
+                let content = fs::read(path)?;
+                let mime_type = match mime_type {
+                    Some(mime_type) => mime_type.to_string(),
+                    None => guess_mime_type(path),
+                };
+                let stored = clwm
+                    .get_asset_by_hash(Clwm::asset_hash(&content))
+                    .await?
+                    .is_some();
+                let asset = clwm.new_asset(content, mime_type).await?;
+                let message = if stored {
+                    format!(
+                        "Asset {} ({}) is already stored",
+                        asset.asset_hash, asset.mime_type
+                    )
+                } else {
+                    format!("New asset {} ({})", asset.asset_hash, asset.mime_type)
+                };
+                output.record(&asset, message)?;
+            }
+            AssetSubcommands::Get {
+                hash,
+                output: output_path,
+            } => {
+                if let Some(content) = clwm.get_asset_content(hash.to_string()).await? {
+                    fs::write(output_path, content)?;
+                } else {
+                    output.message(format!("No asset exists with hash {}", hash))?;
+                }
+            }
+            AssetSubcommands::List => {
+                let assets = clwm.get_all_assets().await?;
+                let rows = assets
+                    .iter()
+                    .map(|asset| {
+                        vec![
+                            asset.asset_hash.clone(),
+                            asset.mime_type.clone(),
+                            asset.size.to_string(),
+                            asset.last_changed.unwrap().to_rfc3339(),
+                        ]
+                    })
+                    .collect();
+                output.list(&assets, &["HASH", "MIME TYPE", "SIZE", "CHANGED"], rows)?;
+            }
+            AssetSubcommands::Gc => {
+                let assets = clwm.collect_asset_garbage().await?;
+                let rows = assets
+                    .iter()
+                    .map(|asset| vec![asset.asset_hash.clone(), asset.mime_type.clone()])
+                    .collect();
+                output.list(&assets, &["REMOVED", "MIME TYPE"], rows)?;
+            }
+        },
+        Commands::Schema { command } => match command {
+            SchemaSubcommands::Set {
+                noun_type,
+                attribute_type_id,
+                requirement,
+                min_count,
+                max_count,
+                default,
+            } => {
+                let attribute_type_id = address::attribute_type_id(clwm, attribute_type_id).await?;
+                let default_data = match default {
+                    Some(default) => Some(toml::from_str::<DataObject>(&read_file(
+                        default.to_path_buf(),
+                    )?)?),
+                    None => None,
+                };
+                let rule = clwm
+                    .set_noun_type_attribute_rule(
+                        noun_type.to_string(),
+                        attribute_type_id,
+                        requirement.parse::<AttributeRequirement>()?,
+                        *min_count,
+                        *max_count,
+                        default_data,
+                    )
+                    .await?;
+                output.record(
+                    &rule,
+                    format!(
+                        "Set {:?} attribute type {} on noun type {}",
+                        rule.requirement, rule.attribute_type_id, noun_type
+                    ),
+                )?;
+            }
+            SchemaSubcommands::Remove {
+                noun_type,
+                attribute_type_id,
+            } => {
+                let attribute_type_id = address::attribute_type_id(clwm, attribute_type_id).await?;
+                clwm.remove_noun_type_attribute_rule(noun_type.to_string(), attribute_type_id)
+                    .await?;
+                output.message(format!(
+                    "Removed attribute type {} from noun type {}",
+                    attribute_type_id, noun_type
+                ))?;
+            }
+            SchemaSubcommands::Show { noun_type } => {
+                let rules = clwm
+                    .get_noun_type_attribute_rules(noun_type.to_string())
+                    .await?;
+                let rows = rules
+                    .iter()
+                    .map(|rule| {
+                        vec![
+                            rule.attribute_type_id.to_string(),
+                            format!("{:?}", rule.requirement),
+                            rule.min_count.to_string(),
+                            rule.max_count
+                                .map_or("*".to_string(), |max_count| max_count.to_string()),
+                        ]
+                    })
+                    .collect();
+                output.list(
+                    &rules,
+                    &["ATTRIBUTE TYPE", "REQUIREMENT", "MIN", "MAX"],
+                    rows,
+                )?;
+            }
+        },
+        Commands::Check { command } => match command {
+            CheckSubcommands::Noun { id } => {
+                let id = address::noun_id(clwm, id).await?;
+                let issues = clwm.validate_noun(id).await?;
+                if issues.is_empty() && !output.is_structured() {
+                    output.message(format!("Noun {} is valid", id))?;
                 } else {
-                    println!("No attribute exists with id {}", id);
+                    let rows = issues
+                        .iter()
+                        .map(|issue| vec![describe_issue(issue)])
+                        .collect();
+                    output.list(&issues, &["ISSUE"], rows)?;
+                }
+            }
+        },
+        Commands::Template { command } => match command {
+            TemplateSubcommands::Set { path } => {
+                let template = toml::from_str::<Template>(&read_file(path.to_path_buf())?)?;
+                let template = clwm.set_template(template).await?;
+                output.record(
+                    &template,
+                    format!(
+                        "Set template {} ({})",
+                        template.template_name, template.noun_type
+                    ),
+                )?;
+            }
+            TemplateSubcommands::Remove { template_name } => {
+                clwm.remove_template(template_name.to_string()).await?;
+                output.message(format!("Removed template {}", template_name))?;
+            }
+            TemplateSubcommands::Show { template_name } => {
+                match clwm.get_template_by_name(template_name.to_string()).await? {
+                    Some(template) => output.show(&template)?,
+                    None => {
+                        output.message(format!("No template exists with name {}", template_name))?
+                    }
+                }
+            }
+            TemplateSubcommands::List => {
+                let templates = clwm.get_all_templates().await?;
+                let rows = templates
+                    .iter()
+                    .map(|template| {
+                        vec![template.template_name.clone(), template.noun_type.clone()]
+                    })
+                    .collect();
+                output.list(&templates, &["NAME", "NOUN TYPE"], rows)?;
+            }
+        },
+        Commands::Relate {
+            source_noun_id,
+            relationship,
+            target_noun_id,
+            metadata,
+        } => {
+            let source_noun_id = address::noun_id(clwm, source_noun_id).await?;
+            let target_noun_id = address::noun_id(clwm, target_noun_id).await?;
+            let relationship = clwm
+                .link_nouns(
+                    source_noun_id,
+                    relationship.to_string(),
+                    target_noun_id,
+                    metadata.clone().unwrap_or_default(),
+                )
+                .await?;
+            output.record(
+                &relationship,
+                describe_relationship("Related", &relationship),
+            )?;
+        }
+        Commands::Unrelate {
+            source_noun_id,
+            relationship,
+            target_noun_id,
+        } => {
+            let source_noun_id = address::noun_id(clwm, source_noun_id).await?;
+            let target_noun_id = address::noun_id(clwm, target_noun_id).await?;
+            let relationship = clwm
+                .unlink_nouns(source_noun_id, relationship.to_string(), target_noun_id)
+                .await?;
+            output.record(
+                &relationship,
+                describe_relationship("Unrelated", &relationship),
+            )?;
+        }
+        Commands::Relations { id } => {
+            let id = address::noun_id(clwm, id).await?;
+            let relations = clwm.get_noun_relations(id).await?;
+            let mut rows = Vec::new();
+            for relation in relations.iter() {
+                let other_noun_name = clwm
+                    .get_noun_by_id(relation.other_noun_id)
+                    .await?
+                    .map_or(String::new(), |noun| noun.name);
+                rows.push(vec![
+                    relation.relation_name.clone(),
+                    relation.other_noun_id.to_string(),
+                    other_noun_name,
+                ]);
+            }
+            output.list(&relations, &["RELATION", "ID", "NAME"], rows)?;
+        }
+        Commands::Graph {
+            command: None,
+            render,
+        } => {
+            let root_noun_id = address::optional_noun_id(clwm, &render.root).await?;
+            let graph = clwm
+                .noun_graph(GraphFilter {
+                    noun_types: render.noun_type.clone(),
+                    root_noun_id,
+                    max_depth: render.depth,
+                })
+                .await?;
+            match render.format.as_str() {
+                _ if output.is_structured() => output.show(&graph)?,
+                "dot" => print!("{}", render_dot(&graph)),
+                "mermaid" => print!("{}", render_mermaid(&graph)),
+                format => anyhow::bail!("unknown graph format {:?}", format),
+            }
+        }
+        Commands::Graph {
+            command: Some(command),
+            ..
+        } => match command {
+            GraphSubcommands::Traverse { id, traversal } => {
+                let id = address::noun_id(clwm, id).await?;
+                let options = traversal.to_options(clwm).await?;
+                let results = clwm.traverse(id, options).await?;
+                let mut rows = Vec::new();
+                for result in results.iter() {
+                    let noun_name = clwm
+                        .get_noun_by_id(result.noun_id)
+                        .await?
+                        .map_or(String::new(), |noun| noun.name);
+                    rows.push(vec![
+                        result.noun_id.to_string(),
+                        noun_name,
+                        result.depth.to_string(),
+                    ]);
+                }
+                output.list(&results, &["ID", "NAME", "DEPTH"], rows)?;
+            }
+            GraphSubcommands::Path {
+                from_id,
+                to_id,
+                traversal,
+            } => {
+                let from_id = address::noun_id(clwm, from_id).await?;
+                let to_id = address::noun_id(clwm, to_id).await?;
+                let options = traversal.to_options(clwm).await?;
+                match clwm.find_shortest_path(from_id, to_id, options).await? {
+                    Some(result) => {
+                        let mut nouns = Vec::new();
+                        for noun_id in result.path.iter() {
+                            nouns.extend(clwm.get_noun_by_id(*noun_id).await?);
+                        }
+                        let rows = nouns
+                            .iter()
+                            .map(|noun| vec![noun.noun_id.unwrap().to_string(), noun.name.clone()])
+                            .collect();
+                        output.list(&nouns, &["ID", "NAME"], rows)?;
+                    }
+                    None => {
+                        output.message(format!("No path found from {} to {}", from_id, to_id))?
+                    }
+                }
+            }
+            GraphSubcommands::Reindex => {
+                let count = clwm.rebuild_noun_references().await?;
+                output.message(format!("Indexed {} noun references", count))?;
+            }
+        },
+        Commands::Tree { root_id, ancestors } => {
+            let root_id = address::noun_id(clwm, root_id).await?;
+            let root = match clwm.get_noun_by_id(root_id).await? {
+                Some(root) => root,
+                None => {
+                    output.message(format!("No noun exists with id {}", root_id))?;
+                    return Ok(());
+                }
+            };
+            let nouns = if *ancestors {
+                let mut nouns = clwm.get_noun_ancestors(root_id).await?;
+                nouns.reverse();
+                nouns.push(root.clone());
+                nouns
+            } else {
+                let mut nouns = vec![root.clone()];
+                nouns.extend(clwm.get_noun_descendants(root_id).await?);
+                nouns
+            };
+            match output.format_or(OutputFormat::Plain) {
+                OutputFormat::Plain if *ancestors => {
+                    let path: Vec<String> = nouns
+                        .iter()
+                        .map(|noun| format!("{}. {}", noun.noun_id.unwrap(), noun.name))
+                        .collect();
+                    println!("{}", path.join(" > "));
+                }
+                OutputFormat::Plain => {
+                    let mut children: HashMap<i64, Vec<Noun>> = HashMap::new();
+                    for noun in nouns.iter().skip(1) {
+                        children
+                            .entry(noun.parent_noun_id.unwrap())
+                            .or_default()
+                            .push(noun.clone());
+                    }
+                    print_noun_tree(&root, &children, 0);
+                }
+                _ => {
+                    let rows = nouns
+                        .iter()
+                        .map(|noun| {
+                            vec![
+                                noun.noun_id.unwrap().to_string(),
+                                noun.name.clone(),
+                                noun.noun_type.clone(),
+                                optional_id(noun.parent_noun_id),
+                            ]
+                        })
+                        .collect();
+                    output.list(&nouns, &["ID", "NAME", "TYPE", "PARENT"], rows)?;
+                }
+            }
+        }
+        Commands::Event { command } => match command {
+            EventSubcommands::Set { id, start, end } => {
+                let id = address::noun_id(clwm, id).await?;
+                let start_day = clwm.parse_world_date(None, start).await?;
+                let end_day = match end {
+                    Some(end) => Some(clwm.parse_world_date(None, end).await?),
+                    None => None,
+                };
+                let event = clwm.set_event(id, start_day, end_day).await?;
+                output.record(
+                    &event,
+                    format!(
+                        "Set event {} from day {} to day {}",
+                        event.noun_id,
+                        event.start_day,
+                        event.end_day.unwrap_or(event.start_day)
+                    ),
+                )?;
+            }
+            EventSubcommands::Add {
+                event_id,
+                noun_id,
+                role,
+            } => {
+                let event_id = address::noun_id(clwm, event_id).await?;
+                let noun_id = address::noun_id(clwm, noun_id).await?;
+                let participant = clwm
+                    .add_event_participant(event_id, noun_id, role.parse::<EventRole>()?)
+                    .await?;
+                output.record(
+                    &participant,
+                    format!(
+                        "Added noun {} to event {} as {:?}",
+                        participant.noun_id, event_id, participant.role
+                    ),
+                )?;
+            }
+            EventSubcommands::Remove {
+                event_id,
+                noun_id,
+                role,
+            } => {
+                let event_id = address::noun_id(clwm, event_id).await?;
+                let noun_id = address::noun_id(clwm, noun_id).await?;
+                clwm.remove_event_participant(event_id, noun_id, role.parse::<EventRole>()?)
+                    .await?;
+                output.message(format!("Removed noun {} from event {}", noun_id, event_id))?;
+            }
+            EventSubcommands::Show { id } => {
+                let id = address::noun_id(clwm, id).await?;
+                match clwm.get_event(id).await? {
+                    Some(event) => output.show(&event)?,
+                    None => output.message(format!("No event exists for noun {}", id))?,
+                }
+            }
+        },
+        Commands::Timeline { between, involving } => {
+            let involving = address::optional_noun_id(clwm, involving).await?;
+            let mut filter = TimelineFilter {
+                involving_noun_id: involving,
+                ..Default::default()
+            };
+            if let Some(between) = between {
+                filter.from_day = Some(clwm.parse_world_date(None, &between[0]).await?);
+                filter.to_day = Some(clwm.parse_world_date(None, &between[1]).await?);
+            }
+            let entries = clwm.timeline(filter).await?;
+            let mut rows = Vec::new();
+            for entry in entries.iter() {
+                let start = clwm.format_world_date(None, entry.event.start_day).await?;
+                let days = match entry.event.end_day {
+                    Some(end_day) if end_day != entry.event.start_day => {
+                        let end = clwm.format_world_date(None, end_day).await?;
+                        format!("{} - {}", start, end)
+                    }
+                    _ => start,
+                };
+                let mut participants = Vec::new();
+                for participant in entry.event.participants.iter().flatten() {
+                    let participant_name = clwm
+                        .get_noun_by_id(participant.noun_id)
+                        .await?
+                        .map_or(String::new(), |noun| noun.name);
+                    participants.push(format!(
+                        "{:?} {}. {}",
+                        participant.role, participant.noun_id, participant_name
+                    ));
+                }
+                rows.push(vec![
+                    days,
+                    entry.noun.noun_id.unwrap().to_string(),
+                    entry.noun.name.clone(),
+                    entry.noun.noun_type.clone(),
+                    participants.join(", "),
+                ]);
+            }
+            output.list(
+                &entries,
+                &["DATE", "ID", "NAME", "TYPE", "PARTICIPANTS"],
+                rows,
+            )?;
+        }
+        Commands::Move { id, into, root } => {
+            let id = address::noun_id(clwm, id).await?;
+            let into = address::optional_noun_id(clwm, into).await?;
+            let parent_noun_id = if *root { None } else { into };
+            let noun = clwm.move_noun(id, parent_noun_id).await?;
+            output.record(&noun, format!("Moved noun {}", describe_noun(&noun)))?;
+        }
+        Commands::Export {
+            path,
+            database,
+            format,
+            history,
+            redact,
+            noun_type,
+        } => {
+            if let (Some(redact), Some(database)) = (redact, database) {
+                let rules = toml::from_str::<RedactionRules>(&read_file(redact.to_path_buf())?)?;
+                let summary = clwm
+                    .export_redacted(rules, path.to_string(), database.to_string())
+                    .await?;
+                output.record(
+                    &summary,
+                    format!(
+                        "Exported {} ({} nouns removed, {} attributes removed, {} placeholders, {} assets removed)",
+                        path,
+                        summary.nouns_removed,
+                        summary.attributes_removed,
+                        summary.placeholders_created,
+                        summary.assets_removed
+                    ),
+                )?;
+            } else if is_csv_table(format, noun_type, path) {
+                let noun_type = match noun_type {
+                    Some(noun_type) => noun_type.to_string(),
+                    None => anyhow::bail!("--type is required for csv exports"),
+                };
+                let table = clwm.export_noun_table(noun_type).await?;
+                let mut writer = csv::Writer::from_path(path)?;
+                writer.write_record(&table.headers)?;
+                for row in table.rows.iter() {
+                    writer.write_record(row)?;
+                }
+                writer.flush()?;
+                output.message(format!("Exported {} nouns to {}", table.rows.len(), path))?;
+            } else if format.as_deref() == Some("markdown") {
+                let pages = clwm.export_wiki().await?;
+                write_pages(path, &pages)?;
+                output.message(format!("Exported {} pages to {}", pages.len(), path))?;
+            } else {
+                let bundle = clwm.export_world(*history).await?;
+                let content = if is_toml_bundle(format, path)? {
+                    toml::to_string(&bundle)?
+                } else {
+                    serde_json::to_string_pretty(&bundle)?
+                };
+                fs::write(path, content)?;
+                output.message(format!("Exported {} nouns to {}", bundle.nouns.len(), path))?;
+            }
+        }
+        Commands::Site { command } => match command {
+            SiteSubcommands::Build { out_dir } => {
+                let files = clwm.build_site().await?;
+                for file in files.iter() {
+                    write_output_file(out_dir, &file.path, &file.content)?;
+                }
+                output.message(format!(
+                    "Built {} files in {}",
+                    files.len(),
+                    out_dir.display()
+                ))?;
+            }
+        },
+        Commands::Import {
+            path,
+            format,
+            dry_run,
+            mapping,
+            noun_type,
+        } => {
+            if is_csv_table(format, noun_type, &path.to_string_lossy()) {
+                let noun_type = match noun_type {
+                    Some(noun_type) => noun_type.to_string(),
+                    None => anyhow::bail!("--type is required for csv imports"),
+                };
+                let mut reader = csv::Reader::from_path(path)?;
+                let mut table = NounTable {
+                    headers: reader.headers()?.iter().map(|x| x.to_owned()).collect(),
+                    rows: Vec::new(),
+                };
+                for record in reader.records() {
+                    table
+                        .rows
+                        .push(record?.iter().map(|x| x.to_owned()).collect());
+                }
+                let summary = clwm.import_noun_table(noun_type, table).await?;
+                if !summary.errors.is_empty() {
+                    let rows = summary
+                        .errors
+                        .iter()
+                        .map(|error| {
+                            vec![
+                                error.row.to_string(),
+                                error.column.clone().unwrap_or_default(),
+                                error.message.clone(),
+                            ]
+                        })
+                        .collect();
+                    output.list(&summary.errors, &["ROW", "COLUMN", "MESSAGE"], rows)?;
+                    anyhow::bail!(
+                        "{} errors in {}; nothing was imported",
+                        summary.errors.len(),
+                        path.display()
+                    )
+                }
+                output.record(
+                    &summary,
+                    format!(
+                        "Imported {} ({} created, {} updated)",
+                        path.display(),
+                        summary.created,
+                        summary.updated
+                    ),
+                )?;
+                return Ok(());
+            }
+            if matches!(format.as_deref(), Some("obsidian" | "markdown"))
+                || (format.is_none() && path.is_dir())
+            {
+                let mapping = match mapping {
+                    Some(mapping) => {
+                        toml::from_str::<VaultMapping>(&read_file(mapping.to_path_buf())?)?
+                    }
+                    None => VaultMapping::default(),
+                };
+                let mut notes = Vec::new();
+                read_vault(path, path, &mut notes)?;
+                let plan = clwm.plan_vault_import(&notes, &mapping).await?;
+                let summary = describe_vault_plan(&plan);
+                if *dry_run {
+                    output.record(&plan, summary.trim_end())?;
+                    return Ok(());
+                }
+                let nouns = clwm.import_vault(plan.clone()).await?;
+                let summary = format!(
+                    "{}Imported {} nouns from {}",
+                    summary,
+                    nouns.len(),
+                    path.display()
+                );
+                output.record(&VaultImportReport { plan, nouns }, summary)?;
+                return Ok(());
+            }
+            if *dry_run || mapping.is_some() {
+                anyhow::bail!("--dry-run and --mapping are only supported for vault imports")
+            }
+            let content = read_file(path.to_path_buf())?;
+            let bundle: WorldBundle = if is_toml_bundle(format, &path.to_string_lossy())? {
+                toml::from_str(&content)?
+            } else {
+                serde_json::from_str(&content)?
+            };
+            let noun_count = bundle.nouns.len();
+            clwm.import_world(bundle).await?;
+            output.message(format!(
+                "Imported {} nouns from {}",
+                noun_count,
+                path.display()
+            ))?;
+        }
+        Commands::Visibility { command } => match command {
+            VisibilitySubcommands::Noun { id, visibility } => {
+                let id = address::noun_id(clwm, id).await?;
+                let noun = clwm
+                    .set_noun_visibility(id, visibility.parse::<Visibility>()?)
+                    .await?;
+                output.record(
+                    &noun,
+                    format!("Set noun {} to {:?}", describe_noun(&noun), noun.visibility),
+                )?;
+            }
+            VisibilitySubcommands::Attribute { id, visibility } => {
+                let id = address::attribute_id(clwm, id).await?;
+                let attribute = clwm
+                    .set_attribute_visibility(id, visibility.parse::<Visibility>()?)
+                    .await?;
+                output.record(
+                    &attribute,
+                    format!(
+                        "Set attribute {} to {:?}",
+                        describe_attribute(&attribute),
+                        attribute.visibility
+                    ),
+                )?;
+            }
+        },
+        Commands::RollTable { command } => match command {
+            RollTableSubcommands::Set { path } => {
+                let roll_table = toml::from_str::<RollTable>(&read_file(path.to_path_buf())?)?;
+                let roll_table = clwm.set_roll_table(roll_table).await?;
+                output.record(
+                    &roll_table,
+                    format!(
+                        "Set roll table {} ({} entries)",
+                        roll_table.roll_table_name,
+                        roll_table.entries.len()
+                    ),
+                )?;
+            }
+            RollTableSubcommands::Remove { roll_table_name } => {
+                clwm.remove_roll_table(roll_table_name.to_string()).await?;
+                output.message(format!("Removed roll table {}", roll_table_name))?;
+            }
+            RollTableSubcommands::Show { roll_table_name } => {
+                match clwm
+                    .get_roll_table_by_name(roll_table_name.to_string())
+                    .await?
+                {
+                    Some(roll_table) => output.show(&roll_table)?,
+                    None => output.message(format!(
+                        "No roll table exists with name {}",
+                        roll_table_name
+                    ))?,
                 }
             }
+            RollTableSubcommands::List => {
+                let roll_tables = clwm.get_all_roll_tables().await?;
+                let rows = roll_tables
+                    .iter()
+                    .map(|roll_table| {
+                        vec![
+                            roll_table.roll_table_name.clone(),
+                            roll_table.entries.len().to_string(),
+                        ]
+                    })
+                    .collect();
+                output.list(&roll_tables, &["NAME", "ENTRIES"], rows)?;
+            }
+        },
+        Commands::Roll {
+            roll_table,
+            noun_type,
+            include_subtypes,
+            attribute_type,
+            seed,
+            count,
+            save_as,
+        } => {
+            let mut generator = RollGenerator::new(*seed);
+            let mut results = Vec::new();
+            let mut saved_nouns = Vec::new();
+            for _ in 0..*count {
+                let result = match roll_table {
+                    Some(roll_table) => clwm.roll(roll_table.to_string(), &mut generator).await?,
+                    None => {
+                        let attribute_type =
+                            address::optional_attribute_type_id(clwm, attribute_type).await?;
+                        let filter = RollNounFilter {
+                            noun_type: noun_type.clone(),
+                            include_subtypes: *include_subtypes,
+                            attribute_type_id: attribute_type,
+                        };
+                        let noun = clwm.roll_noun(filter, &mut generator).await?;
+                        RollResult {
+                            text: noun.name,
+                            noun_id: noun.noun_id,
+                        }
+                    }
+                };
+                if let Some(save_as) = save_as {
+                    let noun = clwm
+                        .new_noun(result.text.clone(), save_as.to_string(), String::new())
+                        .await?;
+                    saved_nouns.push(noun);
+                }
+                results.push(result);
+            }
+            if save_as.is_some() {
+                let rows = saved_nouns
+                    .iter()
+                    .map(|noun| {
+                        vec![
+                            noun.noun_id.unwrap().to_string(),
+                            noun.name.clone(),
+                            noun.noun_type.clone(),
+                        ]
+                    })
+                    .collect();
+                output.list(&saved_nouns, &["ID", "NAME", "TYPE"], rows)?;
+            } else {
+                let rows = results
+                    .iter()
+                    .map(|result| vec![result.text.clone(), optional_id(result.noun_id)])
+                    .collect();
+                output.list(&results, &["RESULT", "NOUN"], rows)?;
+            }
+        }
+        Commands::RollDice {
+            noun_id,
+            attribute_type,
+            seed,
+            count,
+        } => {
+            let noun_id = address::noun_id(clwm, noun_id).await?;
+            let mut generator = RollGenerator::new(*seed);
+            let mut dice_rolls = Vec::new();
+            for _ in 0..*count {
+                dice_rolls.extend(
+                    clwm.roll_attribute_dice(noun_id, attribute_type.to_string(), &mut generator)
+                        .await?,
+                );
+            }
+            let rows = dice_rolls
+                .iter()
+                .map(|dice_roll| {
+                    vec![
+                        dice_roll.expression.to_string(),
+                        dice_roll.roll.total.to_string(),
+                        format!("{:?}", dice_roll.roll.rolls),
+                        dice_roll.expression.minimum().to_string(),
+                        dice_roll.expression.maximum().to_string(),
+                        dice_roll.expression.mean().to_string(),
+                    ]
+                })
+                .collect();
+            output.list(
+                &dice_rolls,
+                &["EXPRESSION", "TOTAL", "ROLLS", "MIN", "MAX", "MEAN"],
+                rows,
+            )?;
+        }
+        Commands::Calendar { calendar, command } => match command {
+            CalendarSubcommands::Set {
+                calendar_name,
+                path,
+            } => {
+                let definition: CalendarDefinition = toml::from_str(&read_file(path.clone())?)?;
+                let calendar = clwm.set_calendar(calendar_name.clone(), definition).await?;
+                output.record(
+                    &calendar,
+                    format!("Set calendar {}", calendar.calendar_name),
+                )?;
+            }
+            CalendarSubcommands::Remove { calendar_name } => {
+                clwm.remove_calendar(calendar_name.clone()).await?;
+                output.message(format!("Removed calendar {}", calendar_name))?;
+            }
+            CalendarSubcommands::List => {
+                let calendars = clwm.get_all_calendars().await?;
+                let rows = calendars
+                    .iter()
+                    .map(|calendar| {
+                        let months: Vec<&str> = calendar
+                            .definition
+                            .months
+                            .iter()
+                            .map(|month| month.name.as_str())
+                            .collect();
+                        vec![calendar.calendar_name.clone(), months.join(", ")]
+                    })
+                    .collect();
+                output.list(&calendars, &["NAME", "MONTHS"], rows)?;
+            }
+            CalendarSubcommands::Show { year, month } => {
+                let Some(world_calendar) = clwm.get_calendar(calendar.clone()).await? else {
+                    output.message("No calendar found, name one with --calendar")?;
+                    return Ok(());
+                };
+                let definition = &world_calendar.definition;
+                let first_day = date_to_day(
+                    definition,
+                    CalendarDate {
+                        year: *year,
+                        month: *month,
+                        day: 1,
+                    },
+                )?;
+                let last_day = first_day + month_length(definition, *year, *month) - 1;
+                let entries = clwm
+                    .timeline(TimelineFilter {
+                        from_day: Some(first_day),
+                        to_day: Some(last_day),
+                        involving_noun_id: None,
+                    })
+                    .await?;
+                let event_days: Vec<i64> = (first_day..=last_day)
+                    .filter(|day| {
+                        entries.iter().any(|entry| {
+                            entry.event.start_day <= *day
+                                && entry.event.end_day.unwrap_or(entry.event.start_day) >= *day
+                        })
+                    })
+                    .collect();
+                if !output.is_structured() {
+                    print_month_grid(definition, first_day, last_day, &event_days);
+                }
+                let rows = entries
+                    .iter()
+                    .map(|entry| {
+                        let start = format_date(definition, entry.event.start_day);
+                        let days = match entry.event.end_day {
+                            Some(end_day) if end_day != entry.event.start_day => {
+                                format!("{} - {}", start, format_date(definition, end_day))
+                            }
+                            _ => start,
+                        };
+                        vec![
+                            days,
+                            entry.noun.noun_id.unwrap().to_string(),
+                            entry.noun.name.clone(),
+                        ]
+                    })
+                    .collect();
+                output.list(&entries, &["DATE", "ID", "NAME"], rows)?;
+            }
+            CalendarSubcommands::Convert { date } => {
+                let day = clwm.parse_world_date(calendar.clone(), date).await?;
+                let world_day = get_world_day(clwm, calendar.clone(), day).await?;
+                output.record(&world_day, describe_world_day(&world_day))?;
+            }
+            CalendarSubcommands::Add { date, days } => {
+                let day = clwm.parse_world_date(calendar.clone(), date).await? + days;
+                let world_day = get_world_day(clwm, calendar.clone(), day).await?;
+                output.record(&world_day, describe_world_day(&world_day))?;
+            }
+            CalendarSubcommands::Diff { from, to } => {
+                let from_day = clwm.parse_world_date(calendar.clone(), from).await?;
+                let to_day = clwm.parse_world_date(calendar.clone(), to).await?;
+                let difference = DayDifference {
+                    days: to_day - from_day,
+                };
+                output.record(&difference, format!("{} days", difference.days))?;
+            }
         },
     }
     Ok(())
 }
 
 async fn get_clwm(cli: &Cli) -> anyhow::Result<Clwm> {
-    let file_name = if cli.file.is_some() {
+    let mut clwm = Clwm::new(world_file_name(cli)).await?;
+    if let Some(viewer) = &cli.viewer {
+        clwm.set_viewer(viewer.parse::<Viewer>()?);
+    }
+    Ok(clwm)
+}
+
+fn world_file_name(cli: &Cli) -> String {
+    if cli.file.is_some() {
         cli.file.as_ref().unwrap().to_string()
     } else {
         "world.clwm".to_string()
-    };
-    Clwm::new(file_name).await
+    }
 }
 
 fn open_editor(extension: String) -> anyhow::Result<String> {
@@ -571,3 +2125,325 @@ fn read_file(file_path: PathBuf) -> anyhow::Result<String> {
         .read_to_string(&mut file_content)?;
     Ok(file_content)
 }
+
+fn guess_mime_type(path: &Path) -> String {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "txt" => "text/plain",
+        "md" => "text/markdown",
+        _ => "application/octet-stream",
+    }
+    .to_string()
+}
+
+fn parse_placeholder(placeholder: &str) -> Result<(String, String), String> {
+    match placeholder.split_once('=') {
+        Some((key, value)) => Ok((key.trim().to_string(), value.to_string())),
+        None => Err(format!("expected KEY=VALUE but found {:?}", placeholder)),
+    }
+}
+
+fn print_noun_tree(noun: &Noun, children: &HashMap<i64, Vec<Noun>>, depth: usize) {
+    println!(
+        "{}{}. {} ({})",
+        "  ".repeat(depth),
+        noun.noun_id.unwrap(),
+        noun.name,
+        noun.noun_type
+    );
+    if let Some(noun_children) = children.get(&noun.noun_id.unwrap()) {
+        for child in noun_children.iter() {
+            print_noun_tree(child, children, depth + 1);
+        }
+    }
+}
+
+async fn get_world_day(
+    clwm: &mut Clwm,
+    calendar_name: Option<String>,
+    day: i64,
+) -> anyhow::Result<WorldDay> {
+    let mut world_day = WorldDay {
+        day,
+        date: None,
+        weekday: None,
+        moons: Vec::new(),
+    };
+    if let Some(calendar) = clwm.get_calendar(calendar_name).await? {
+        let definition = &calendar.definition;
+        world_day.date = Some(format_date(definition, day));
+        world_day.weekday = weekday(definition, day).map(|weekday| weekday.to_string());
+        world_day.moons = moon_phases(definition, day)
+            .into_iter()
+            .map(|(moon, phase)| MoonPhase {
+                moon: moon.to_string(),
+                phase: phase.to_string(),
+            })
+            .collect();
+    }
+    Ok(world_day)
+}
+
+fn describe_world_day(world_day: &WorldDay) -> String {
+    let mut lines = vec![format!("Day {}", world_day.day)];
+    lines.extend(world_day.date.clone());
+    lines.extend(world_day.weekday.clone());
+    for moon in world_day.moons.iter() {
+        lines.push(format!("{}: {}", moon.moon, moon.phase));
+    }
+    lines.join("\n")
+}
+
+fn print_month_grid(
+    definition: &CalendarDefinition,
+    first_day: i64,
+    last_day: i64,
+    event_days: &[i64],
+) {
+    let date = day_to_date(definition, first_day);
+    let month_name = &definition.months[date.month - 1].name;
+    match era_of_year(definition, date.year) {
+        Some(era) => println!(
+            "{} {} {}",
+            month_name,
+            date.year - era.start_year + 1,
+            era.abbreviation
+        ),
+        None => println!("{} {}", month_name, date.year),
+    }
+    let columns = if definition.weekdays.is_empty() {
+        7
+    } else {
+        let headers: Vec<String> = definition
+            .weekdays
+            .iter()
+            .map(|weekday| format!("{:>4}", weekday.chars().take(3).collect::<String>()))
+            .collect();
+        println!("{}", headers.join(""));
+        definition.weekdays.len()
+    };
+    let column_of =
+        |day: i64| weekday_index(definition, day).unwrap_or(((day - first_day) % 7) as usize);
+    let mut line = "    ".repeat(column_of(first_day));
+    for day in first_day..=last_day {
+        let marker = if event_days.contains(&day) { '*' } else { ' ' };
+        line.push_str(&format!("{:>3}{}", day - first_day + 1, marker));
+        if column_of(day) + 1 == columns {
+            println!("{}", line.trim_end());
+            line.clear();
+        }
+    }
+    if !line.is_empty() {
+        println!("{}", line.trim_end());
+    }
+}
+
+fn write_pages(directory: &str, pages: &[WikiPage]) -> anyhow::Result<()> {
+    for page in pages.iter() {
+        write_output_file(
+            &PathBuf::from(directory),
+            &page.path,
+            page.content.as_bytes(),
+        )?;
+    }
+    Ok(())
+}
+
+fn write_output_file(directory: &Path, path: &str, content: &[u8]) -> anyhow::Result<()> {
+    let file_path = directory.join(path);
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(file_path, content)?;
+    Ok(())
+}
+
+fn read_vault(root: &Path, directory: &Path, notes: &mut Vec<VaultNote>) -> anyhow::Result<()> {
+    for entry in fs::read_dir(directory)? {
+        let entry_path = entry?.path();
+        let hidden = entry_path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if entry_path.is_dir() {
+            read_vault(root, &entry_path, notes)?;
+        } else if entry_path.extension().is_some_and(|x| x == "md") {
+            let relative_path = entry_path.strip_prefix(root)?;
+            notes.push(VaultNote {
+                path: relative_path
+                    .components()
+                    .map(|x| x.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/"),
+                content: fs::read_to_string(&entry_path)?,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn describe_vault_plan(plan: &VaultImportPlan) -> String {
+    let status = |exists: bool| if exists { "existing" } else { "new" };
+    let mut text = "Noun types:\n".to_owned();
+    for noun_type in plan.noun_types.iter() {
+        text.push_str(&format!(
+            "  {} ({})\n",
+            noun_type.name,
+            status(noun_type.exists)
+        ));
+    }
+    text.push_str("Data types:\n");
+    for data_type in plan.data_types.iter() {
+        text.push_str(&format!(
+            "  {} = {:?} ({})\n",
+            data_type.name,
+            data_type.definition,
+            status(data_type.exists)
+        ));
+    }
+    text.push_str("Attribute types:\n");
+    for attribute_type in plan.attribute_types.iter() {
+        text.push_str(&format!(
+            "  {} <- {} : {} ({})\n",
+            attribute_type.attribute_name,
+            attribute_type.source,
+            attribute_type.data_type,
+            status(attribute_type.exists)
+        ));
+    }
+    text.push_str("Nouns:\n");
+    for noun in plan.nouns.iter() {
+        let attribute_names: Vec<&str> = noun
+            .attributes
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        text.push_str(&format!(
+            "  {} -> {} ({}) [{}]\n",
+            noun.path,
+            noun.name,
+            noun.noun_type,
+            attribute_names.join(", ")
+        ));
+    }
+    if !plan.unresolved_links.is_empty() {
+        text.push_str("Unresolved links:\n");
+        for issue in plan.unresolved_links.iter() {
+            text.push_str(&format!("  {}: {}\n", issue.path, issue.detail));
+        }
+    }
+    if !plan.skipped_fields.is_empty() {
+        text.push_str("Skipped fields:\n");
+        for issue in plan.skipped_fields.iter() {
+            text.push_str(&format!("  {}: {}\n", issue.path, issue.detail));
+        }
+    }
+    text
+}
+
+fn describe_noun(noun: &Noun) -> String {
+    format!(
+        "{}. {} ({})",
+        noun.noun_id.unwrap(),
+        noun.name,
+        noun.noun_type
+    )
+}
+
+fn describe_attribute_type(attribute_type: &AttributeType) -> String {
+    format!(
+        "{}. {} ({})",
+        attribute_type.attribute_type_id.unwrap(),
+        attribute_type.attribute_name,
+        attribute_type.data_type
+    )
+}
+
+fn describe_attribute(attribute: &Attribute) -> String {
+    match (attribute.parent_noun_id, attribute.parent_attribute_id) {
+        (Some(parent_noun_id), _) => format!(
+            "{} of type {} on noun {}",
+            attribute.attribute_id.unwrap(),
+            attribute.attribute_type_id,
+            parent_noun_id
+        ),
+        (None, Some(parent_attribute_id)) => format!(
+            "{} of type {} on attribute {}",
+            attribute.attribute_id.unwrap(),
+            attribute.attribute_type_id,
+            parent_attribute_id
+        ),
+        (None, None) => format!(
+            "{} of type {}",
+            attribute.attribute_id.unwrap(),
+            attribute.attribute_type_id
+        ),
+    }
+}
+
+fn describe_relationship(action: &str, relationship: &Relationship) -> String {
+    format!(
+        "{} {} {} {}",
+        action,
+        relationship.source_noun_id,
+        relationship.relationship_type,
+        relationship.target_noun_id
+    )
+}
+
+fn describe_issue(issue: &NounValidationIssue) -> String {
+    match issue {
+        NounValidationIssue::MissingRequiredAttribute {
+            attribute_type,
+            min_count,
+            found,
+        } => format!(
+            "Missing {}: requires at least {}, found {}",
+            attribute_type, min_count, found
+        ),
+        NounValidationIssue::TooManyAttributes {
+            attribute_type,
+            max_count,
+            found,
+        } => format!(
+            "Too many {}: allows at most {}, found {}",
+            attribute_type, max_count, found
+        ),
+        NounValidationIssue::ForbiddenAttribute {
+            attribute_type,
+            found,
+        } => format!("Forbidden {}: found {}", attribute_type, found),
+    }
+}
+
+fn optional_id(id: Option<i64>) -> String {
+    id.map_or(String::new(), |id| id.to_string())
+}
+
+fn is_csv_table(format: &Option<String>, noun_type: &Option<String>, path: &str) -> bool {
+    match format.as_deref() {
+        Some(format) => format == "csv",
+        None => noun_type.is_some() || path.ends_with(".csv"),
+    }
+}
+
+fn is_toml_bundle(format: &Option<String>, path: &str) -> anyhow::Result<bool> {
+    match format.as_deref() {
+        Some("toml") => Ok(true),
+        Some("json") => Ok(false),
+        Some(format) => anyhow::bail!("unknown bundle format {:?}", format),
+        None => Ok(path.ends_with(".toml")),
+    }
+}
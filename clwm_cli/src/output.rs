@@ -0,0 +1,152 @@
+use std::fmt::Display;
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::Value;
+
+/// How a command writes its result to stdout.
+///
+/// * `json` and `toml` write the model objects as they are stored. Commands
+///   that create, change or show one thing write that object, commands that
+///   list things write `{ items = [...] }` and commands that only report what
+///   they did write `{ message = "..." }`.
+/// * `table` writes lists as aligned columns under a header row and single
+///   objects as aligned `field value` rows.
+/// * `plain` writes lists as tab separated rows without a header and single
+///   objects as a one line summary.
+///
+/// Without `--output`, lists default to `table`, `get` and `show` commands
+/// default to `toml` and everything else defaults to `plain`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Toml,
+    Table,
+    Plain,
+}
+
+#[derive(Serialize)]
+struct Items<'a, T> {
+    items: &'a [T],
+}
+
+#[derive(Serialize)]
+struct Message {
+    message: String,
+}
+
+pub struct Output {
+    format: Option<OutputFormat>,
+}
+
+impl Output {
+    pub fn new(format: Option<OutputFormat>) -> Output {
+        Output { format }
+    }
+
+    pub fn format_or(&self, default: OutputFormat) -> OutputFormat {
+        self.format.unwrap_or(default)
+    }
+
+    pub fn is_structured(&self) -> bool {
+        matches!(self.format, Some(OutputFormat::Json | OutputFormat::Toml))
+    }
+
+    /// Writes an object a command created or changed.
+    pub fn record<T: Serialize>(&self, value: &T, summary: impl Display) -> anyhow::Result<()> {
+        match self.format_or(OutputFormat::Plain) {
+            OutputFormat::Plain => println!("{}", summary),
+            format => self.write_value(format, value)?,
+        }
+        Ok(())
+    }
+
+    /// Writes an object a command looked up.
+    pub fn show<T: Serialize>(&self, value: &T) -> anyhow::Result<()> {
+        self.write_value(self.format_or(OutputFormat::Toml), value)
+    }
+
+    /// Writes a list of objects, with `rows` holding the human readable cells
+    /// of each item in the same order as `headers`.
+    pub fn list<T: Serialize>(
+        &self,
+        items: &[T],
+        headers: &[&str],
+        rows: Vec<Vec<String>>,
+    ) -> anyhow::Result<()> {
+        match self.format_or(OutputFormat::Table) {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&Items { items })?),
+            OutputFormat::Toml => print!("{}", toml::to_string(&Items { items })?),
+            OutputFormat::Table => {
+                let headers = headers.iter().map(|header| header.to_string()).collect();
+                print_table(&[vec![headers], rows].concat());
+            }
+            OutputFormat::Plain => {
+                for row in rows.iter() {
+                    println!("{}", row.join("\t"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a status line for commands that have no object to return.
+    pub fn message(&self, message: impl Display) -> anyhow::Result<()> {
+        let message = message.to_string();
+        match self.format_or(OutputFormat::Plain) {
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&Message { message })?)
+            }
+            OutputFormat::Toml => print!("{}", toml::to_string(&Message { message })?),
+            OutputFormat::Table | OutputFormat::Plain => println!("{}", message),
+        }
+        Ok(())
+    }
+
+    fn write_value<T: Serialize>(&self, format: OutputFormat, value: &T) -> anyhow::Result<()> {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+            OutputFormat::Toml => print!("{}", toml::to_string(value)?),
+            OutputFormat::Table | OutputFormat::Plain => {
+                let rows: Vec<Vec<String>> = match serde_json::to_value(value)? {
+                    Value::Object(fields) => fields
+                        .iter()
+                        .map(|(field, value)| vec![field.clone(), cell(value)])
+                        .collect(),
+                    value => vec![vec![cell(&value)]],
+                };
+                print_table(&rows);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn print_table(rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = Vec::new();
+    for row in rows.iter() {
+        for (index, text) in row.iter().enumerate() {
+            let width = text.chars().count();
+            match widths.get_mut(index) {
+                Some(column_width) => *column_width = (*column_width).max(width),
+                None => widths.push(width),
+            }
+        }
+    }
+    for row in rows.iter() {
+        let line: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(index, text)| format!("{:<width$}", text, width = widths[index]))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+}
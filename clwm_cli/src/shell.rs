@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use clap::{CommandFactory, Parser};
+use clwm_lib::{clwm::Clwm, model::Viewer};
+use rustyline::{
+    completion::{Completer, Pair},
+    error::ReadlineError,
+    highlight::Highlighter,
+    hint::Hinter,
+    history::DefaultHistory,
+    validate::Validator,
+    Context, Editor, Helper,
+};
+
+use crate::{get_clwm, run_command, world_file_name, Cli};
+
+const SHELL_COMMANDS: [&str; 6] = ["begin", "commit", "rollback", "help", "exit", "quit"];
+
+struct ShellHelper {
+    commands: Vec<String>,
+    subcommands: HashMap<String, Vec<String>>,
+    names: Vec<String>,
+}
+
+impl ShellHelper {
+    fn new() -> ShellHelper {
+        let mut commands: Vec<String> = SHELL_COMMANDS.iter().map(|x| x.to_string()).collect();
+        let mut subcommands = HashMap::new();
+        for command in Cli::command().get_subcommands() {
+            commands.push(command.get_name().to_string());
+            subcommands.insert(
+                command.get_name().to_string(),
+                command
+                    .get_subcommands()
+                    .map(|subcommand| subcommand.get_name().to_string())
+                    .collect(),
+            );
+        }
+        ShellHelper {
+            commands,
+            subcommands,
+            names: Vec::new(),
+        }
+    }
+
+    async fn refresh_names(&mut self, clwm: &mut Clwm) -> anyhow::Result<()> {
+        let mut names: Vec<String> = Vec::new();
+        names.extend(clwm.get_all_nouns().await?.into_iter().map(|x| x.name));
+        names.extend(
+            clwm.get_all_noun_types()
+                .await?
+                .into_iter()
+                .map(|x| x.noun_type),
+        );
+        names.extend(
+            clwm.get_all_attribute_types()
+                .await?
+                .into_iter()
+                .map(|x| x.attribute_name),
+        );
+        names.extend(
+            clwm.get_all_templates()
+                .await?
+                .into_iter()
+                .map(|x| x.template_name),
+        );
+        names.extend(
+            clwm.get_all_roll_tables()
+                .await?
+                .into_iter()
+                .map(|x| x.roll_table_name),
+        );
+        names.sort();
+        names.dedup();
+        self.names = names;
+        Ok(())
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _context: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let before = &line[..pos];
+        // Inside an open quote the word being completed starts at the quote.
+        let start = if before.matches('"').count() % 2 == 1 {
+            before.rfind('"').unwrap()
+        } else {
+            before
+                .rfind(char::is_whitespace)
+                .map_or(0, |index| index + 1)
+        };
+        let word = before[start..].trim_start_matches('"');
+        let previous: Vec<&str> = before[..start].split_whitespace().collect();
+        let candidates = match previous.as_slice() {
+            [] => &self.commands,
+            [command]
+                if self
+                    .subcommands
+                    .get(*command)
+                    .is_some_and(|x| !x.is_empty()) =>
+            {
+                &self.subcommands[*command]
+            }
+            _ => &self.names,
+        };
+        let pairs = candidates
+            .iter()
+            .filter(|candidate| candidate.starts_with(word))
+            .map(|candidate| Pair {
+                display: candidate.clone(),
+                replacement: shell_words::quote(candidate).into_owned(),
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+pub(crate) async fn run_shell(cli: &Cli) -> anyhow::Result<()> {
+    let mut clwm = get_clwm(cli).await?;
+    let history_path = format!("{}.history", world_file_name(cli));
+    let mut helper = ShellHelper::new();
+    helper.refresh_names(&mut clwm).await?;
+    let mut editor: Editor<ShellHelper, DefaultHistory> = Editor::new()?;
+    editor.set_helper(Some(helper));
+    let _ = editor.load_history(&history_path);
+
+    loop {
+        let prompt = if clwm.in_unit_of_work() {
+            "clwm*> "
+        } else {
+            "clwm> "
+        };
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(error) => return Err(error.into()),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line)?;
+        match line {
+            "exit" | "quit" => break,
+            "help" => {
+                println!("Shell commands:");
+                println!("  begin     open a unit of work");
+                println!("  commit    commit the open unit of work");
+                println!("  rollback  roll back the open unit of work");
+                println!("  exit      leave the shell");
+                println!("Any clwm command can be run without the leading `clwm`,");
+                println!("use `<command> --help` for its arguments.");
+            }
+            "begin" => match clwm.begin_unit_of_work().await {
+                Ok(()) => println!("Began unit of work"),
+                Err(error) => eprintln!("Error: {:#}", error),
+            },
+            "commit" => match clwm.commit_unit_of_work().await {
+                Ok(()) => println!("Committed unit of work"),
+                Err(error) => eprintln!("Error: {:#}", error),
+            },
+            "rollback" => match clwm.rollback_unit_of_work().await {
+                Ok(()) => println!("Rolled back unit of work"),
+                Err(error) => eprintln!("Error: {:#}", error),
+            },
+            _ => {
+                if let Err(error) = run_line(cli, &mut clwm, line).await {
+                    eprintln!("Error: {:#}", error);
+                    continue;
+                }
+                if let Some(helper) = editor.helper_mut() {
+                    helper.refresh_names(&mut clwm).await?;
+                }
+            }
+        }
+    }
+
+    if clwm.in_unit_of_work() {
+        clwm.rollback_unit_of_work().await?;
+        println!("Rolled back the open unit of work");
+    }
+    editor.save_history(&history_path)?;
+    Ok(())
+}
+
+async fn run_line(cli: &Cli, clwm: &mut Clwm, line: &str) -> anyhow::Result<()> {
+    let words = shell_words::split(line)?;
+    let mut line_cli = match Cli::try_parse_from(std::iter::once("clwm".to_string()).chain(words)) {
+        Ok(line_cli) => line_cli,
+        Err(error) => {
+            error.print()?;
+            return Ok(());
+        }
+    };
+    if line_cli.file.is_some() {
+        anyhow::bail!("the shell is already connected to {}", world_file_name(cli));
+    }
+    if line_cli.output.is_none() {
+        line_cli.output = cli.output;
+    }
+
+    let session_viewer = clwm.viewer.clone();
+    if let Some(viewer) = &line_cli.viewer {
+        clwm.set_viewer(viewer.parse::<Viewer>()?);
+    }
+    let savepoint = if clwm.in_unit_of_work() {
+        Some(clwm.savepoint().await?)
+    } else {
+        None
+    };
+    let result = run_command(&line_cli, clwm).await;
+    clwm.set_viewer(session_viewer);
+    if let Some(savepoint) = savepoint {
+        // A failed command is undone without discarding the rest of the unit.
+        match &result {
+            Ok(()) => savepoint.commit().await?,
+            Err(_) => savepoint.rollback().await?,
+        }
+    }
+    result
+}
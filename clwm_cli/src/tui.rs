@@ -0,0 +1,749 @@
+use std::collections::HashMap;
+
+use clwm_lib::{
+    clwm::Clwm,
+    model::{Attribute, AttributeType, DataObject, DataTypeDefinition, Noun, NounType},
+    table::{column_value, decode_cell, encode_cell, set_column_value},
+};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, Paragraph},
+    DefaultTerminal, Frame,
+};
+
+use crate::{get_clwm, Cli};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    NounTypes,
+    Nouns,
+    Attributes,
+}
+
+enum Mode {
+    Browse,
+    History {
+        title: String,
+        lines: Vec<String>,
+        scroll: u16,
+    },
+    Edit {
+        label: String,
+        input: String,
+        target: EditTarget,
+    },
+}
+
+enum EditTarget {
+    NounName,
+    Value(Box<ValueEdit>),
+}
+
+#[derive(Clone)]
+struct ValueEdit {
+    attribute: Attribute,
+    path: Vec<String>,
+    definition: DataTypeDefinition,
+}
+
+/// One row of the attribute tree: an attribute, a `Custom` field or an
+/// element of a list or map.
+struct AttributeLine {
+    depth: usize,
+    label: String,
+    value: String,
+    attribute_id: i64,
+    reference: Option<i64>,
+    edit: Option<ValueEdit>,
+}
+
+struct App {
+    noun_types: Vec<(usize, NounType)>,
+    nouns: Vec<Noun>,
+    noun: Option<Noun>,
+    lines: Vec<AttributeLine>,
+    attribute_types: HashMap<i64, AttributeType>,
+    definitions: HashMap<(String, i64), DataTypeDefinition>,
+    noun_type_state: ListState,
+    noun_state: ListState,
+    line_state: ListState,
+    pane: Pane,
+    mode: Mode,
+    back: Vec<i64>,
+    status: String,
+}
+
+pub(crate) async fn run_tui(cli: &Cli) -> anyhow::Result<()> {
+    let mut clwm = get_clwm(cli).await?;
+    let mut app = App::new(&mut clwm).await?;
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal, &mut clwm).await;
+    ratatui::restore();
+    result
+}
+
+impl App {
+    async fn new(clwm: &mut Clwm) -> anyhow::Result<App> {
+        let mut app = App {
+            noun_types: Vec::new(),
+            nouns: Vec::new(),
+            noun: None,
+            lines: Vec::new(),
+            attribute_types: HashMap::new(),
+            definitions: HashMap::new(),
+            noun_type_state: ListState::default(),
+            noun_state: ListState::default(),
+            line_state: ListState::default(),
+            pane: Pane::NounTypes,
+            mode: Mode::Browse,
+            back: Vec::new(),
+            status:
+                "Tab switch pane, Enter open, h history, e edit, n rename, Backspace back, q quit"
+                    .to_string(),
+        };
+        let noun_types = clwm.get_all_noun_types().await?;
+        push_noun_type_tree(&noun_types, None, 0, &mut app.noun_types);
+        for attribute_type in clwm.get_all_attribute_types().await? {
+            app.attribute_types
+                .insert(attribute_type.attribute_type_id.unwrap(), attribute_type);
+        }
+        for data_type in clwm.get_all_data_types().await? {
+            app.definitions.insert(
+                (data_type.name, data_type.version.unwrap()),
+                data_type.definition,
+            );
+        }
+        if !app.noun_types.is_empty() {
+            app.noun_type_state.select(Some(0));
+            app.load_nouns(clwm).await?;
+        }
+        Ok(app)
+    }
+
+    async fn run(&mut self, terminal: &mut DefaultTerminal, clwm: &mut Clwm) -> anyhow::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let result = match &mut self.mode {
+                Mode::Browse => match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    code => self.browse_key(clwm, code).await,
+                },
+                Mode::History { scroll, .. } => {
+                    match key.code {
+                        KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+                        KeyCode::Down | KeyCode::Char('j') => *scroll = scroll.saturating_add(1),
+                        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('h') => {
+                            self.mode = Mode::Browse
+                        }
+                        _ => {}
+                    }
+                    Ok(())
+                }
+                Mode::Edit { input, .. } => match key.code {
+                    KeyCode::Char(character) => {
+                        input.push(character);
+                        Ok(())
+                    }
+                    KeyCode::Backspace => {
+                        input.pop();
+                        Ok(())
+                    }
+                    KeyCode::Esc => {
+                        self.mode = Mode::Browse;
+                        self.status = "Edit cancelled".to_string();
+                        Ok(())
+                    }
+                    KeyCode::Enter => self.save_edit(clwm).await,
+                    _ => Ok(()),
+                },
+            };
+            if let Err(error) = result {
+                self.status = format!("Error: {:#}", error);
+            }
+        }
+    }
+
+    async fn browse_key(&mut self, clwm: &mut Clwm, code: KeyCode) -> anyhow::Result<()> {
+        match code {
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => {
+                self.pane = match self.pane {
+                    Pane::NounTypes => Pane::Nouns,
+                    Pane::Nouns => Pane::Attributes,
+                    Pane::Attributes => Pane::NounTypes,
+                }
+            }
+            KeyCode::BackTab | KeyCode::Left => {
+                self.pane = match self.pane {
+                    Pane::NounTypes => Pane::Attributes,
+                    Pane::Nouns => Pane::NounTypes,
+                    Pane::Attributes => Pane::Nouns,
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(clwm, -1).await?,
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(clwm, 1).await?,
+            KeyCode::Enter => match self.pane {
+                Pane::NounTypes => self.pane = Pane::Nouns,
+                Pane::Nouns => self.pane = Pane::Attributes,
+                Pane::Attributes => {
+                    let reference = self
+                        .line_state
+                        .selected()
+                        .and_then(|index| self.lines.get(index))
+                        .and_then(|line| line.reference);
+                    match reference {
+                        Some(noun_id) => {
+                            if let Some(noun) = &self.noun {
+                                self.back.push(noun.noun_id.unwrap());
+                            }
+                            self.open_noun(clwm, noun_id).await?;
+                        }
+                        None => self.status = "This value is not a noun reference".to_string(),
+                    }
+                }
+            },
+            KeyCode::Backspace => {
+                if let Some(noun_id) = self.back.pop() {
+                    self.open_noun(clwm, noun_id).await?;
+                }
+            }
+            KeyCode::Char('h') => self.show_history(clwm).await?,
+            KeyCode::Char('e') => self.start_value_edit(),
+            KeyCode::Char('n') => {
+                if let Some(noun) = &self.noun {
+                    self.mode = Mode::Edit {
+                        label: "Name".to_string(),
+                        input: noun.name.clone(),
+                        target: EditTarget::NounName,
+                    };
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn move_selection(&mut self, clwm: &mut Clwm, step: isize) -> anyhow::Result<()> {
+        match self.pane {
+            Pane::NounTypes => {
+                if step_selection(&mut self.noun_type_state, self.noun_types.len(), step) {
+                    self.load_nouns(clwm).await?;
+                }
+            }
+            Pane::Nouns => {
+                if step_selection(&mut self.noun_state, self.nouns.len(), step) {
+                    self.load_noun(clwm).await?;
+                }
+            }
+            Pane::Attributes => {
+                step_selection(&mut self.line_state, self.lines.len(), step);
+            }
+        }
+        Ok(())
+    }
+
+    async fn load_nouns(&mut self, clwm: &mut Clwm) -> anyhow::Result<()> {
+        self.nouns = match self.selected_noun_type() {
+            Some(noun_type) => {
+                clwm.find_nouns_by_noun_type(noun_type.noun_type.clone(), false)
+                    .await?
+            }
+            None => Vec::new(),
+        };
+        self.nouns.sort_by(|a, b| a.name.cmp(&b.name));
+        self.noun_state
+            .select(if self.nouns.is_empty() { None } else { Some(0) });
+        self.load_noun(clwm).await
+    }
+
+    async fn load_noun(&mut self, clwm: &mut Clwm) -> anyhow::Result<()> {
+        let noun_id = self
+            .noun_state
+            .selected()
+            .and_then(|index| self.nouns.get(index))
+            .and_then(|noun| noun.noun_id);
+        self.noun = None;
+        self.lines.clear();
+        if let Some(noun_id) = noun_id {
+            if let Some(mut noun) = clwm.get_noun_by_id(noun_id).await? {
+                clwm.populate_noun(&mut noun).await?;
+                for attribute in noun.attributes.iter().flatten() {
+                    self.push_attribute_lines(attribute, 0);
+                }
+                for line in self.lines.iter_mut() {
+                    if let Some(reference) = line.reference {
+                        let name = clwm
+                            .get_noun_by_id(reference)
+                            .await?
+                            .map_or("(hidden)".to_string(), |noun| noun.name);
+                        line.value = format!("-> {}. {}", reference, name);
+                    }
+                }
+                self.noun = Some(noun);
+            }
+        }
+        let selected = self.line_state.selected().unwrap_or(0);
+        self.line_state.select(if self.lines.is_empty() {
+            None
+        } else {
+            Some(selected.min(self.lines.len() - 1))
+        });
+        Ok(())
+    }
+
+    /// Selects the noun's type and the noun itself, as if the user had
+    /// browsed to it.
+    async fn open_noun(&mut self, clwm: &mut Clwm, noun_id: i64) -> anyhow::Result<()> {
+        let Some(noun) = clwm.get_noun_by_id(noun_id).await? else {
+            self.status = format!("Noun {} cannot be found", noun_id);
+            return Ok(());
+        };
+        let type_index = self
+            .noun_types
+            .iter()
+            .position(|(_, noun_type)| noun_type.noun_type == noun.noun_type);
+        self.noun_type_state.select(type_index);
+        self.load_nouns(clwm).await?;
+        let noun_index = self
+            .nouns
+            .iter()
+            .position(|candidate| candidate.noun_id == Some(noun_id));
+        self.noun_state.select(noun_index);
+        self.line_state.select(None);
+        self.load_noun(clwm).await?;
+        self.pane = Pane::Attributes;
+        self.status = format!("Opened {}. {}", noun_id, noun.name);
+        Ok(())
+    }
+
+    fn push_attribute_lines(&mut self, attribute: &Attribute, depth: usize) {
+        let attribute_type = self.attribute_types.get(&attribute.attribute_type_id);
+        let label = attribute_type.map_or(attribute.attribute_type_id.to_string(), |x| {
+            x.attribute_name.clone()
+        });
+        let definition = attribute_type.and_then(|attribute_type| {
+            self.definitions
+                .get(&(
+                    attribute_type.data_type.clone(),
+                    attribute.data_type_version,
+                ))
+                .cloned()
+        });
+        let computed = attribute_type.is_some_and(|x| x.formula.is_some());
+        self.push_value_lines(
+            attribute,
+            depth,
+            label,
+            &attribute.data,
+            definition.as_ref().filter(|_| !computed),
+            Some(Vec::new()),
+        );
+        for child in attribute.children.iter().flatten() {
+            self.push_attribute_lines(child, depth + 1);
+        }
+    }
+
+    /// Adds a line for `data` and lines for its fields or elements. `path` is
+    /// the `Custom` field path from the attribute's value, or `None` inside
+    /// lists and maps, which are edited as a whole.
+    fn push_value_lines(
+        &mut self,
+        attribute: &Attribute,
+        depth: usize,
+        label: String,
+        data: &DataObject,
+        definition: Option<&DataTypeDefinition>,
+        path: Option<Vec<String>>,
+    ) {
+        let edit = match (definition, &path) {
+            (Some(definition), Some(path)) => Some(ValueEdit {
+                attribute: attribute.clone(),
+                path: path.clone(),
+                definition: definition.clone(),
+            }),
+            _ => None,
+        };
+        let value = match data {
+            DataObject::Custom(_) => String::new(),
+            DataObject::Array(items) => format!("[{} items]", items.len()),
+            DataObject::Map(map) => format!("{{{} entries}}", map.0.len()),
+            data => encode_cell(data),
+        };
+        let reference = match data {
+            DataObject::NounReference(noun_id) => Some(*noun_id),
+            _ => None,
+        };
+        self.lines.push(AttributeLine {
+            depth,
+            label,
+            value,
+            attribute_id: attribute.attribute_id.unwrap(),
+            reference,
+            edit,
+        });
+        match data {
+            DataObject::Custom(custom) => {
+                let mut fields: Vec<(&String, &DataObject)> = custom.0.iter().collect();
+                fields.sort_by_key(|(field, _)| *field);
+                for (field, field_data) in fields {
+                    let field_definition = match definition {
+                        Some(DataTypeDefinition::Custom(custom)) => custom.0.get(field),
+                        _ => None,
+                    };
+                    let field_path = path.clone().map(|mut path| {
+                        path.push(field.clone());
+                        path
+                    });
+                    self.push_value_lines(
+                        attribute,
+                        depth + 1,
+                        field.clone(),
+                        field_data,
+                        field_definition,
+                        field_path,
+                    );
+                }
+            }
+            DataObject::Array(items) => {
+                let item_definition = match definition {
+                    Some(DataTypeDefinition::Array(item_definition)) => Some(&**item_definition),
+                    _ => None,
+                };
+                for (index, item) in items.iter().enumerate() {
+                    self.push_value_lines(
+                        attribute,
+                        depth + 1,
+                        format!("[{}]", index),
+                        item,
+                        item_definition,
+                        None,
+                    );
+                }
+            }
+            DataObject::Map(map) => {
+                let value_definition = match definition {
+                    Some(DataTypeDefinition::Map(_, value_definition)) => Some(&**value_definition),
+                    _ => None,
+                };
+                for (key, item) in map.0.iter() {
+                    self.push_value_lines(
+                        attribute,
+                        depth + 1,
+                        encode_cell(key),
+                        item,
+                        value_definition,
+                        None,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn start_value_edit(&mut self) {
+        let line = match self.line_state.selected().and_then(|x| self.lines.get(x)) {
+            Some(line) if self.pane == Pane::Attributes => line,
+            _ => {
+                self.status = "Select a value in the attribute pane to edit it".to_string();
+                return;
+            }
+        };
+        match &line.edit {
+            Some(edit) => {
+                let input = column_value(&edit.attribute.data, &edit.path)
+                    .map_or(String::new(), encode_cell);
+                self.mode = Mode::Edit {
+                    label: line.label.clone(),
+                    input,
+                    target: EditTarget::Value(Box::new(edit.clone())),
+                };
+            }
+            None => {
+                self.status =
+                    "This value cannot be edited here, edit the value that contains it".to_string()
+            }
+        }
+    }
+
+    async fn save_edit(&mut self, clwm: &mut Clwm) -> anyhow::Result<()> {
+        let Mode::Edit { input, target, .. } = &self.mode else {
+            return Ok(());
+        };
+        match target {
+            EditTarget::NounName => {
+                let Some(mut noun) = self.noun.clone() else {
+                    return Ok(());
+                };
+                noun.name = input.clone();
+                noun.attributes = None;
+                let noun = clwm.update_noun(noun).await?;
+                self.status = format!("Renamed noun {} to {}", noun.noun_id.unwrap(), noun.name);
+            }
+            EditTarget::Value(edit) => {
+                let value = match decode_cell(input, &edit.definition) {
+                    Ok(value) => value,
+                    Err(message) => {
+                        // Stay in the editor so the value can be corrected.
+                        self.status = format!("Invalid value: {}", message);
+                        return Ok(());
+                    }
+                };
+                let mut attribute = edit.attribute.clone();
+                set_column_value(&mut attribute.data, &edit.path, value);
+                attribute.children = None;
+                clwm.update_attribute(attribute).await?;
+                self.status = "Saved".to_string();
+            }
+        }
+        self.mode = Mode::Browse;
+        let noun_id = self.noun.as_ref().and_then(|noun| noun.noun_id);
+        self.load_nouns(clwm).await?;
+        self.noun_state.select(
+            self.nouns
+                .iter()
+                .position(|noun| noun.noun_id.is_some() && noun.noun_id == noun_id),
+        );
+        self.load_noun(clwm).await
+    }
+
+    async fn show_history(&mut self, clwm: &mut Clwm) -> anyhow::Result<()> {
+        let Some(noun) = &self.noun else {
+            return Ok(());
+        };
+        let line = self.line_state.selected().and_then(|x| self.lines.get(x));
+        let mut lines = Vec::new();
+        let title = match line {
+            Some(line) if self.pane == Pane::Attributes => {
+                for history in clwm.get_attribute_history(line.attribute_id).await? {
+                    lines.push(format!(
+                        "Change set {} at {}",
+                        history.change_set_id.unwrap_or_default(),
+                        history
+                            .change_date
+                            .map_or(String::new(), |x| x.to_rfc3339())
+                    ));
+                    push_diff_lines(&mut lines, "data", &history.diff_data);
+                    push_diff_lines(
+                        &mut lines,
+                        "data type version",
+                        &history.diff_data_type_version,
+                    );
+                    push_diff_lines(&mut lines, "metadata", &history.diff_metadata);
+                    push_diff_lines(&mut lines, "visibility", &history.diff_visibility);
+                }
+                format!("History of attribute {}", line.attribute_id)
+            }
+            _ => {
+                let noun_id = noun.noun_id.unwrap();
+                for history in clwm.get_noun_history(noun_id).await? {
+                    lines.push(format!(
+                        "Change set {} at {}",
+                        history.change_set_id.unwrap_or_default(),
+                        history
+                            .change_date
+                            .map_or(String::new(), |x| x.to_rfc3339())
+                    ));
+                    push_diff_lines(&mut lines, "name", &history.diff_name);
+                    push_diff_lines(&mut lines, "noun type", &history.diff_noun_type);
+                    push_diff_lines(&mut lines, "parent", &history.diff_parent_noun_id);
+                    push_diff_lines(&mut lines, "metadata", &history.diff_metadata);
+                    push_diff_lines(&mut lines, "visibility", &history.diff_visibility);
+                }
+                format!("History of {}. {}", noun_id, noun.name)
+            }
+        };
+        if lines.is_empty() {
+            lines.push("No recorded changes".to_string());
+        }
+        self.mode = Mode::History {
+            title,
+            lines,
+            scroll: 0,
+        };
+        Ok(())
+    }
+
+    fn selected_noun_type(&self) -> Option<&NounType> {
+        self.noun_type_state
+            .selected()
+            .and_then(|index| self.noun_types.get(index))
+            .map(|(_, noun_type)| noun_type)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main_area, status_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [noun_type_area, noun_area, detail_area] = Layout::horizontal([
+            Constraint::Percentage(20),
+            Constraint::Percentage(25),
+            Constraint::Percentage(55),
+        ])
+        .areas(main_area);
+
+        let noun_types: Vec<ListItem> = self
+            .noun_types
+            .iter()
+            .map(|(depth, noun_type)| {
+                ListItem::new(format!("{}{}", "  ".repeat(*depth), noun_type.noun_type))
+            })
+            .collect();
+        frame.render_stateful_widget(
+            self.list(noun_types, "Noun types", Pane::NounTypes),
+            noun_type_area,
+            &mut self.noun_type_state,
+        );
+
+        let nouns: Vec<ListItem> = self
+            .nouns
+            .iter()
+            .map(|noun| ListItem::new(format!("{}. {}", noun.noun_id.unwrap(), noun.name)))
+            .collect();
+        frame.render_stateful_widget(
+            self.list(nouns, "Nouns", Pane::Nouns),
+            noun_area,
+            &mut self.noun_state,
+        );
+
+        match &self.mode {
+            Mode::History {
+                title,
+                lines,
+                scroll,
+            } => {
+                let lines: Vec<Line> = lines
+                    .iter()
+                    .map(|line| {
+                        let style = if line.starts_with('+') && !line.starts_with("+++") {
+                            Style::new().fg(Color::Green)
+                        } else if line.starts_with('-') && !line.starts_with("---") {
+                            Style::new().fg(Color::Red)
+                        } else if line.starts_with("Change set") {
+                            Style::new().add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::new()
+                        };
+                        Line::styled(line.clone(), style)
+                    })
+                    .collect();
+                frame.render_widget(
+                    Paragraph::new(lines)
+                        .scroll((*scroll, 0))
+                        .block(Block::bordered().title(title.clone())),
+                    detail_area,
+                );
+            }
+            _ => {
+                let title = self.noun.as_ref().map_or("Attributes".to_string(), |noun| {
+                    format!(
+                        "{}. {} ({})",
+                        noun.noun_id.unwrap(),
+                        noun.name,
+                        noun.noun_type
+                    )
+                });
+                let lines: Vec<ListItem> = self
+                    .lines
+                    .iter()
+                    .map(|line| {
+                        let value_style = if line.reference.is_some() {
+                            Style::new().fg(Color::Cyan)
+                        } else {
+                            Style::new()
+                        };
+                        ListItem::new(Line::from(vec![
+                            Span::raw("  ".repeat(line.depth)),
+                            Span::styled(
+                                format!("{}: ", line.label),
+                                Style::new().add_modifier(Modifier::BOLD),
+                            ),
+                            Span::styled(line.value.clone(), value_style),
+                        ]))
+                    })
+                    .collect();
+                frame.render_stateful_widget(
+                    self.list(lines, &title, Pane::Attributes),
+                    detail_area,
+                    &mut self.line_state,
+                );
+            }
+        }
+
+        let status = match &self.mode {
+            Mode::Edit { label, input, .. } => Line::from(vec![
+                Span::styled(
+                    format!("{}: ", label),
+                    Style::new().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!("{}_", input)),
+                Span::styled(
+                    format!("  {}", self.status),
+                    Style::new().fg(Color::DarkGray),
+                ),
+            ]),
+            _ => Line::raw(self.status.clone()),
+        };
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+
+    fn list<'a>(&self, items: Vec<ListItem<'a>>, title: &str, pane: Pane) -> List<'a> {
+        let highlight = if self.pane == pane {
+            Style::new().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::new().add_modifier(Modifier::BOLD)
+        };
+        List::new(items)
+            .block(Block::bordered().title(title.to_string()))
+            .highlight_style(highlight)
+    }
+}
+
+fn push_noun_type_tree(
+    noun_types: &[NounType],
+    parent_noun_type_id: Option<i64>,
+    depth: usize,
+    tree: &mut Vec<(usize, NounType)>,
+) {
+    let mut children: Vec<&NounType> = noun_types
+        .iter()
+        .filter(|noun_type| noun_type.parent_noun_type_id == parent_noun_type_id)
+        .collect();
+    children.sort_by(|a, b| a.noun_type.cmp(&b.noun_type));
+    for noun_type in children {
+        tree.push((depth, noun_type.clone()));
+        push_noun_type_tree(noun_types, noun_type.noun_type_id, depth + 1, tree);
+    }
+}
+
+/// Moves the selection by `step` within `length` items and reports whether
+/// it changed.
+fn step_selection(state: &mut ListState, length: usize, step: isize) -> bool {
+    if length == 0 {
+        return false;
+    }
+    let current = state.selected().unwrap_or(0);
+    let next = current.saturating_add_signed(step).min(length - 1);
+    state.select(Some(next));
+    next != current
+}
+
+fn push_diff_lines(lines: &mut Vec<String>, field: &str, diff: &str) {
+    // A patch without a hunk means the field did not change.
+    if !diff.contains("@@") {
+        return;
+    }
+    lines.push(format!("  {}:", field));
+    for line in diff
+        .lines()
+        .filter(|line| !line.starts_with("---") && !line.starts_with("+++"))
+    {
+        lines.push(format!("  {}", line));
+    }
+}
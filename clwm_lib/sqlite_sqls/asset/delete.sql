@@ -0,0 +1,2 @@
+DELETE FROM asset
+WHERE asset_hash = ?1;
@@ -0,0 +1,6 @@
+SELECT asset_hash,
+    mime_type,
+    size,
+    change_set.change_date
+FROM asset
+    JOIN change_set ON change_set.change_set_id = asset.change_set_id;
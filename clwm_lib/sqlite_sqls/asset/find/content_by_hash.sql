@@ -0,0 +1,3 @@
+SELECT content
+FROM asset
+WHERE asset.asset_hash = ?1;
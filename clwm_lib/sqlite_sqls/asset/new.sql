@@ -0,0 +1,2 @@
+INSERT INTO asset (asset_hash, mime_type, size, content, change_set_id)
+VALUES (?1, ?2, ?3, ?4, ?5);
@@ -0,0 +1,2 @@
+DELETE FROM attribute
+WHERE attribute_id = ?1;
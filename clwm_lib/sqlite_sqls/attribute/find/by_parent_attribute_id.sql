@@ -5,6 +5,7 @@ SELECT attribute_id,
     data,
     data_type_version,
     metadata,
+    visibility,
     change_date
 FROM attribute
     JOIN change_set ON change_set.change_set_id = attribute.last_change_set_id
@@ -0,0 +1,11 @@
+SELECT attribute_id,
+    attribute_history.change_set_id,
+    change_set.change_date,
+    diff_data,
+    diff_data_type_version,
+    diff_metadata,
+    diff_visibility
+FROM attribute_history
+    JOIN change_set ON change_set.change_set_id = attribute_history.change_set_id
+WHERE attribute_history.attribute_id = ?1
+ORDER BY attribute_history.change_set_id;
@@ -3,6 +3,7 @@ INSERT INTO attribute_history (
         change_set_id,
         diff_data,
         diff_data_type_version,
-        diff_metadata
+        diff_metadata,
+        diff_visibility
     )
-VALUES (?, ?, ?, ?, ?);
\ No newline at end of file
+VALUES (?, ?, ?, ?, ?, ?);
\ No newline at end of file
@@ -5,6 +5,7 @@ INSERT INTO attribute (
         data,
         data_type_version,
         metadata,
-        last_change_set_id
+        last_change_set_id,
+        visibility
     )
-VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7);
\ No newline at end of file
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8);
\ No newline at end of file
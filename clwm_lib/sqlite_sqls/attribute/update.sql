@@ -5,5 +5,6 @@ SET attribute_type_id = ?1,
     data = ?4,
     data_type_version = ?5,
     metadata = ?6,
-    last_change_set_id = ?7
-WHERE attribute_id = ?8;
\ No newline at end of file
+    last_change_set_id = ?7,
+    visibility = ?8
+WHERE attribute_id = ?9;
\ No newline at end of file
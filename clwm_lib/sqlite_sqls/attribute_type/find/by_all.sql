@@ -2,6 +2,7 @@ SELECT attribute_type.attribute_type_id,
     attribute_name,
     data_type_name,
     multiple_allowed,
+    formula,
     metadata,
     change_set.change_date
 from attribute_type
@@ -3,6 +3,7 @@ INSERT INTO attribute_type_history (
         change_set_id,
         diff_attribute_name,
         diff_multiple_allowed,
+        diff_formula,
         diff_metadata
     )
-VALUES (?, ?, ?, ?, ?);
\ No newline at end of file
+VALUES (?, ?, ?, ?, ?, ?);
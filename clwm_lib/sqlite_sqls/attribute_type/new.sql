@@ -2,7 +2,8 @@ INSERT INTO attribute_type (
         attribute_name,
         data_type_name,
         multiple_allowed,
+        formula,
         metadata,
         last_change_set_id
     )
-VALUES (?1, ?2, ?3, ?4, ?5)
\ No newline at end of file
+VALUES (?1, ?2, ?3, ?4, ?5, ?6)
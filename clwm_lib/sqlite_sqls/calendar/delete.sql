@@ -0,0 +1,2 @@
+DELETE FROM calendar
+WHERE calendar_name = ?1;
@@ -0,0 +1,6 @@
+SELECT calendar_name,
+    definition,
+    change_set.change_date
+FROM calendar
+    JOIN change_set ON change_set.change_set_id = calendar.last_change_set_id
+ORDER BY calendar_name;
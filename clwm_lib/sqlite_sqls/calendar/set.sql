@@ -0,0 +1,5 @@
+INSERT INTO calendar (calendar_name, definition, last_change_set_id)
+VALUES (?1, ?2, ?3) ON CONFLICT(calendar_name) DO
+UPDATE
+SET definition = ?2,
+    last_change_set_id = ?3;
@@ -0,0 +1,5 @@
+SELECT change_set_id,
+    change_date,
+    change_source
+FROM change_set
+ORDER BY change_set_id;
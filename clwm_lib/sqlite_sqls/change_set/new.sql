@@ -0,0 +1,2 @@
+INSERT INTO change_set (change_date, change_source)
+VALUES (?1, ?2);
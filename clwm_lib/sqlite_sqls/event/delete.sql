@@ -0,0 +1,2 @@
+DELETE FROM event
+WHERE noun_id = ?1;
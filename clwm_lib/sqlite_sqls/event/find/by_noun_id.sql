@@ -0,0 +1,7 @@
+SELECT event.noun_id,
+    start_day,
+    end_day,
+    change_set.change_date
+FROM event
+    JOIN change_set ON change_set.change_set_id = event.last_change_set_id
+WHERE event.noun_id = ?1;
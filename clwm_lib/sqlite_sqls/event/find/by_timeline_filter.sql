@@ -0,0 +1,27 @@
+SELECT event.noun_id,
+    start_day,
+    end_day,
+    change_set.change_date
+FROM event
+    JOIN noun ON noun.noun_id = event.noun_id
+    JOIN change_set ON change_set.change_set_id = event.last_change_set_id
+WHERE (
+        ?1 IS NULL
+        OR COALESCE(end_day, start_day) >= ?1
+    )
+    AND (
+        ?2 IS NULL
+        OR start_day <= ?2
+    )
+    AND (
+        ?3 IS NULL
+        OR EXISTS (
+            SELECT 1
+            FROM event_participant
+            WHERE event_participant.event_noun_id = event.noun_id
+                AND event_participant.noun_id = ?3
+        )
+    )
+ORDER BY start_day,
+    end_day,
+    noun.name;
@@ -0,0 +1,8 @@
+SELECT noun_id,
+    event_history.change_set_id,
+    change_set.change_date,
+    diff_start_day,
+    diff_end_day
+FROM event_history
+    JOIN change_set on change_set.change_set_id = event_history.change_set_id
+where event_history.ROWID = ?1;
@@ -0,0 +1,7 @@
+INSERT INTO event_history (
+        noun_id,
+        change_set_id,
+        diff_start_day,
+        diff_end_day
+    )
+VALUES (?1, ?2, ?3, ?4);
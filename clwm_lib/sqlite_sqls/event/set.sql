@@ -0,0 +1,11 @@
+INSERT INTO event (
+        noun_id,
+        start_day,
+        end_day,
+        last_change_set_id
+    )
+VALUES (?1, ?2, ?3, ?4) ON CONFLICT(noun_id) DO
+UPDATE
+SET start_day = ?2,
+    end_day = ?3,
+    last_change_set_id = ?4;
@@ -0,0 +1,4 @@
+DELETE FROM event_participant
+WHERE event_noun_id = ?1
+    AND noun_id = ?2
+    AND role = ?3;
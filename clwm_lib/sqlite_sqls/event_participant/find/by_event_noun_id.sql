@@ -0,0 +1,7 @@
+SELECT event_noun_id,
+    noun_id,
+    role
+FROM event_participant
+WHERE event_noun_id = ?1
+ORDER BY role,
+    noun_id;
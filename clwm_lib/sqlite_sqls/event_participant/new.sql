@@ -0,0 +1,3 @@
+INSERT
+    OR IGNORE INTO event_participant (event_noun_id, noun_id, role)
+VALUES (?1, ?2, ?3);
@@ -5,12 +5,17 @@ CREATE TABLE "change_set" (
 	PRIMARY KEY("change_set_id" AUTOINCREMENT)
 );
 CREATE TABLE "data_type" (
+	"data_type_name" TEXT NOT NULL UNIQUE,
+	PRIMARY KEY("data_type_name")
+);
+CREATE TABLE "data_type_version" (
 	"data_type_name" TEXT NOT NULL,
 	"system_defined" INTEGER NOT NULL,
 	"definition" BLOB NOT NULL,
 	"version" INTEGER NOT NULL,
 	"change_set_id" INTEGER NOT NULL,
 	PRIMARY KEY("version", "data_type_name"),
+	FOREIGN KEY("data_type_name") REFERENCES "data_type"("data_type_name"),
 	FOREIGN KEY("change_set_id") REFERENCES "change_set"("change_set_id")
 );
 CREATE TABLE "noun" (
@@ -18,9 +23,12 @@ CREATE TABLE "noun" (
 	"name" TEXT NOT NULL,
 	"last_change_set_id" INTEGER NOT NULL,
 	"noun_type_id" INTEGER NOT NULL,
+	"parent_noun_id" INTEGER,
 	"metadata" TEXT NOT NULL,
+	"visibility" TEXT NOT NULL DEFAULT 'Public',
 	PRIMARY KEY("noun_id" AUTOINCREMENT),
 	FOREIGN KEY("noun_type_id") REFERENCES "noun_type"("noun_type_id"),
+	FOREIGN KEY("parent_noun_id") REFERENCES "noun"("noun_id"),
 	FOREIGN KEY("last_change_set_id") REFERENCES "change_set"("change_set_id")
 );
 CREATE TABLE "noun_history" (
@@ -28,7 +36,9 @@ CREATE TABLE "noun_history" (
 	"change_set_id" INTEGER NOT NULL,
 	"diff_name" TEXT NOT NULL,
 	"diff_noun_type" TEXT NOT NULL,
+	"diff_parent_noun_id" TEXT NOT NULL,
 	"diff_metadata" TEXT NOT NULL,
+	"diff_visibility" TEXT NOT NULL,
 	FOREIGN KEY("change_set_id") REFERENCES "change_set"("change_set_id"),
 	FOREIGN KEY("noun_id") REFERENCES "noun"("noun_id"),
 	PRIMARY KEY("noun_id", "change_set_id")
@@ -37,14 +47,17 @@ CREATE TABLE "noun_type" (
 	"noun_type_id" INTEGER NOT NULL UNIQUE,
 	"last_change_set_id" INTEGER NOT NULL,
 	"noun_type" TEXT NOT NULL UNIQUE,
+	"parent_noun_type_id" INTEGER,
 	"metadata" TEXT NOT NULL,
 	FOREIGN KEY("last_change_set_id") REFERENCES "change_set"("change_set_id"),
+	FOREIGN KEY("parent_noun_type_id") REFERENCES "noun_type"("noun_type_id"),
 	PRIMARY KEY("noun_type_id" AUTOINCREMENT)
 );
 CREATE TABLE "noun_type_history" (
 	"noun_type_id" INTEGER NOT NULL,
 	"change_set_id" INTEGER NOT NULL,
 	"diff_noun_type" TEXT NOT NULL,
+	"diff_parent_noun_type_id" TEXT NOT NULL,
 	"diff_metadata" TEXT NOT NULL,
 	FOREIGN KEY("noun_type_id") REFERENCES "noun_type"("noun_type_id"),
 	FOREIGN KEY("change_set_id") REFERENCES "change_set"("change_set_id"),
@@ -55,6 +68,7 @@ CREATE TABLE "attribute_type" (
 	"attribute_name" TEXT NOT NULL,
 	"data_type_name" TEXT NOT NULL,
 	"multiple_allowed" INTEGER NOT NULL,
+	"formula" TEXT,
 	"metadata" TEXT NOT NULL,
 	"last_change_set_id" INTEGER NOT NULL,
 	PRIMARY KEY("attribute_type_id" AUTOINCREMENT),
@@ -66,8 +80,154 @@ CREATE TABLE "attribute_type_history" (
 	"change_set_id" INTEGER NOT NULL,
 	"diff_attribute_name" TEXT NOT NULL,
 	"diff_multiple_allowed" TEXT NOT NULL,
+	"diff_formula" TEXT NOT NULL,
 	"diff_metadata" TEXT NOT NULL,
 	FOREIGN KEY("attribute_type_id") REFERENCES "attribute_type"("attribute_type_id"),
 	FOREIGN KEY("change_set_id") REFERENCES "change_set"("change_set_id"),
 	PRIMARY KEY("change_set_id", "attribute_type_id")
-);
\ No newline at end of file
+);
+CREATE TABLE "attribute" (
+	"attribute_id" INTEGER NOT NULL UNIQUE,
+	"attribute_type_id" INTEGER NOT NULL,
+	"parent_noun_id" INTEGER,
+	"parent_attribute_id" INTEGER,
+	"data" BLOB NOT NULL,
+	"data_type_version" INTEGER NOT NULL,
+	"metadata" TEXT NOT NULL,
+	"last_change_set_id" INTEGER NOT NULL,
+	"visibility" TEXT NOT NULL DEFAULT 'Public',
+	FOREIGN KEY("attribute_type_id") REFERENCES "attribute_type"("attribute_type_id"),
+	FOREIGN KEY("last_change_set_id") REFERENCES "change_set"("change_set_id"),
+	PRIMARY KEY("attribute_id" AUTOINCREMENT)
+);
+CREATE TABLE "attribute_history" (
+	"attribute_id" INTEGER NOT NULL,
+	"change_set_id" INTEGER NOT NULL,
+	"diff_data" TEXT NOT NULL,
+	"diff_data_type_version" TEXT NOT NULL,
+	"diff_metadata" TEXT NOT NULL,
+	"diff_visibility" TEXT NOT NULL,
+	FOREIGN KEY("change_set_id") REFERENCES "change_set"("change_set_id"),
+	PRIMARY KEY("change_set_id", "attribute_id")
+);
+CREATE TABLE "asset" (
+	"asset_hash" TEXT NOT NULL UNIQUE,
+	"mime_type" TEXT NOT NULL,
+	"size" INTEGER NOT NULL,
+	"content" BLOB NOT NULL,
+	"change_set_id" INTEGER NOT NULL,
+	FOREIGN KEY("change_set_id") REFERENCES "change_set"("change_set_id"),
+	PRIMARY KEY("asset_hash")
+);
+CREATE TABLE "noun_type_attribute_rule" (
+	"noun_type_id" INTEGER NOT NULL,
+	"attribute_type_id" INTEGER NOT NULL,
+	"requirement" TEXT NOT NULL,
+	"min_count" INTEGER NOT NULL,
+	"max_count" INTEGER,
+	"default_data" BLOB,
+	"last_change_set_id" INTEGER NOT NULL,
+	FOREIGN KEY("noun_type_id") REFERENCES "noun_type"("noun_type_id"),
+	FOREIGN KEY("attribute_type_id") REFERENCES "attribute_type"("attribute_type_id"),
+	FOREIGN KEY("last_change_set_id") REFERENCES "change_set"("change_set_id"),
+	PRIMARY KEY("noun_type_id", "attribute_type_id")
+);
+CREATE TABLE "template" (
+	"template_id" INTEGER NOT NULL UNIQUE,
+	"template_name" TEXT NOT NULL UNIQUE,
+	"noun_type_id" INTEGER NOT NULL,
+	"attributes" BLOB NOT NULL,
+	"last_change_set_id" INTEGER NOT NULL,
+	"metadata" TEXT NOT NULL,
+	FOREIGN KEY("noun_type_id") REFERENCES "noun_type"("noun_type_id"),
+	FOREIGN KEY("last_change_set_id") REFERENCES "change_set"("change_set_id"),
+	PRIMARY KEY("template_id" AUTOINCREMENT)
+);
+CREATE TABLE "relationship_type" (
+	"relationship_type_id" INTEGER NOT NULL UNIQUE,
+	"relationship_name" TEXT NOT NULL UNIQUE,
+	"inverse_name" TEXT NOT NULL UNIQUE,
+	"cardinality" TEXT NOT NULL,
+	"last_change_set_id" INTEGER NOT NULL,
+	"metadata" TEXT NOT NULL,
+	FOREIGN KEY("last_change_set_id") REFERENCES "change_set"("change_set_id"),
+	PRIMARY KEY("relationship_type_id" AUTOINCREMENT)
+);
+CREATE TABLE "relationship" (
+	"relationship_id" INTEGER NOT NULL UNIQUE,
+	"relationship_type_id" INTEGER NOT NULL,
+	"source_noun_id" INTEGER NOT NULL,
+	"target_noun_id" INTEGER NOT NULL,
+	"last_change_set_id" INTEGER NOT NULL,
+	"metadata" TEXT NOT NULL,
+	FOREIGN KEY("relationship_type_id") REFERENCES "relationship_type"("relationship_type_id"),
+	FOREIGN KEY("source_noun_id") REFERENCES "noun"("noun_id"),
+	FOREIGN KEY("target_noun_id") REFERENCES "noun"("noun_id"),
+	FOREIGN KEY("last_change_set_id") REFERENCES "change_set"("change_set_id"),
+	UNIQUE("relationship_type_id", "source_noun_id", "target_noun_id"),
+	PRIMARY KEY("relationship_id" AUTOINCREMENT)
+);
+CREATE TABLE "relationship_history" (
+	"relationship_id" INTEGER NOT NULL,
+	"change_set_id" INTEGER NOT NULL,
+	"diff_relationship_type" TEXT NOT NULL,
+	"diff_source_noun_id" TEXT NOT NULL,
+	"diff_target_noun_id" TEXT NOT NULL,
+	"diff_metadata" TEXT NOT NULL,
+	FOREIGN KEY("change_set_id") REFERENCES "change_set"("change_set_id"),
+	PRIMARY KEY("relationship_id", "change_set_id")
+);
+CREATE TABLE "noun_reference" (
+	"attribute_id" INTEGER NOT NULL,
+	"attribute_type_id" INTEGER NOT NULL,
+	"source_noun_id" INTEGER NOT NULL,
+	"target_noun_id" INTEGER NOT NULL,
+	FOREIGN KEY("attribute_id") REFERENCES "attribute"("attribute_id"),
+	FOREIGN KEY("attribute_type_id") REFERENCES "attribute_type"("attribute_type_id"),
+	FOREIGN KEY("source_noun_id") REFERENCES "noun"("noun_id"),
+	FOREIGN KEY("target_noun_id") REFERENCES "noun"("noun_id"),
+	PRIMARY KEY("attribute_id", "target_noun_id")
+);
+CREATE TABLE "event" (
+	"noun_id" INTEGER NOT NULL UNIQUE,
+	"start_day" INTEGER NOT NULL,
+	"end_day" INTEGER,
+	"last_change_set_id" INTEGER NOT NULL,
+	FOREIGN KEY("noun_id") REFERENCES "noun"("noun_id"),
+	FOREIGN KEY("last_change_set_id") REFERENCES "change_set"("change_set_id"),
+	PRIMARY KEY("noun_id")
+);
+CREATE TABLE "event_history" (
+	"noun_id" INTEGER NOT NULL,
+	"change_set_id" INTEGER NOT NULL,
+	"diff_start_day" TEXT NOT NULL,
+	"diff_end_day" TEXT NOT NULL,
+	FOREIGN KEY("noun_id") REFERENCES "event"("noun_id"),
+	FOREIGN KEY("change_set_id") REFERENCES "change_set"("change_set_id"),
+	PRIMARY KEY("noun_id", "change_set_id")
+);
+CREATE TABLE "event_participant" (
+	"event_noun_id" INTEGER NOT NULL,
+	"noun_id" INTEGER NOT NULL,
+	"role" TEXT NOT NULL,
+	FOREIGN KEY("event_noun_id") REFERENCES "event"("noun_id"),
+	FOREIGN KEY("noun_id") REFERENCES "noun"("noun_id"),
+	PRIMARY KEY("event_noun_id", "noun_id", "role")
+);
+CREATE TABLE "calendar" (
+	"calendar_name" TEXT NOT NULL UNIQUE,
+	"definition" BLOB NOT NULL,
+	"last_change_set_id" INTEGER NOT NULL,
+	FOREIGN KEY("last_change_set_id") REFERENCES "change_set"("change_set_id"),
+	PRIMARY KEY("calendar_name")
+);
+CREATE TABLE "roll_table" (
+	"roll_table_id" INTEGER NOT NULL UNIQUE,
+	"roll_table_name" TEXT NOT NULL UNIQUE,
+	"entries" BLOB NOT NULL,
+	"last_change_set_id" INTEGER NOT NULL,
+	"metadata" TEXT NOT NULL,
+	FOREIGN KEY("last_change_set_id") REFERENCES "change_set"("change_set_id"),
+	PRIMARY KEY("roll_table_id" AUTOINCREMENT)
+);
+PRAGMA user_version = 1;
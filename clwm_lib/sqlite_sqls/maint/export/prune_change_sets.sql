@@ -0,0 +1,16 @@
+DELETE FROM change_set
+WHERE change_set_id NOT IN (
+        SELECT last_change_set_id FROM noun
+        UNION SELECT last_change_set_id FROM noun_type
+        UNION SELECT last_change_set_id FROM attribute_type
+        UNION SELECT last_change_set_id FROM attribute
+        UNION SELECT last_change_set_id FROM noun_type_attribute_rule
+        UNION SELECT last_change_set_id FROM template
+        UNION SELECT last_change_set_id FROM relationship_type
+        UNION SELECT last_change_set_id FROM relationship
+        UNION SELECT last_change_set_id FROM event
+        UNION SELECT last_change_set_id FROM calendar
+        UNION SELECT last_change_set_id FROM roll_table
+        UNION SELECT change_set_id FROM data_type_version
+        UNION SELECT change_set_id FROM asset
+    );
@@ -0,0 +1,6 @@
+DELETE FROM noun_history;
+DELETE FROM noun_type_history;
+DELETE FROM attribute_type_history;
+DELETE FROM attribute_history;
+DELETE FROM relationship_history;
+DELETE FROM event_history;
@@ -0,0 +1,2 @@
+DELETE FROM noun
+WHERE noun_id = ?1;
@@ -0,0 +1,25 @@
+WITH RECURSIVE ancestor(noun_id, depth) AS (
+    SELECT parent_noun_id,
+        1
+    FROM noun
+    WHERE noun_id = ?1
+        AND parent_noun_id IS NOT NULL
+    UNION
+    SELECT noun.parent_noun_id,
+        ancestor.depth + 1
+    FROM noun
+        JOIN ancestor ON noun.noun_id = ancestor.noun_id
+    WHERE noun.parent_noun_id IS NOT NULL
+)
+SELECT noun.noun_id,
+    name,
+    change_set.change_date,
+    noun_type,
+    noun.parent_noun_id,
+    noun.visibility,
+    noun.metadata
+FROM noun
+    JOIN ancestor ON ancestor.noun_id = noun.noun_id
+    JOIN noun_type ON noun_type.noun_type_id = noun.noun_type_id
+    JOIN change_set ON change_set.change_set_id = noun.last_change_set_id
+ORDER BY ancestor.depth;
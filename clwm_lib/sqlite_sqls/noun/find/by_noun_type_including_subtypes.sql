@@ -0,0 +1,23 @@
+WITH RECURSIVE subtype(noun_type_id) AS (
+    SELECT noun_type_id
+    FROM noun_type
+    WHERE noun_type = ?1
+    UNION
+    SELECT noun_type.noun_type_id
+    FROM noun_type
+        JOIN subtype ON noun_type.parent_noun_type_id = subtype.noun_type_id
+)
+SELECT noun_id,
+    name,
+    change_set.change_date,
+    noun_type,
+    noun.parent_noun_id,
+    noun.visibility,
+    noun.metadata
+FROM noun
+    JOIN noun_type ON noun_type.noun_type_id = noun.noun_type_id
+    JOIN change_set ON change_set.change_set_id = noun.last_change_set_id
+WHERE noun.noun_type_id IN (
+        SELECT noun_type_id
+        FROM subtype
+    );
@@ -0,0 +1,24 @@
+WITH RECURSIVE descendant(noun_id, depth) AS (
+    SELECT noun_id,
+        1
+    FROM noun
+    WHERE parent_noun_id = ?1
+    UNION
+    SELECT noun.noun_id,
+        descendant.depth + 1
+    FROM noun
+        JOIN descendant ON noun.parent_noun_id = descendant.noun_id
+)
+SELECT noun.noun_id,
+    name,
+    change_set.change_date,
+    noun_type,
+    noun.parent_noun_id,
+    noun.visibility,
+    noun.metadata
+FROM noun
+    JOIN descendant ON descendant.noun_id = noun.noun_id
+    JOIN noun_type ON noun_type.noun_type_id = noun.noun_type_id
+    JOIN change_set ON change_set.change_set_id = noun.last_change_set_id
+ORDER BY descendant.depth,
+    noun.name;
@@ -0,0 +1,11 @@
+SELECT noun_id,
+    noun_history.change_set_id,
+    change_set.change_date,
+    diff_name,
+    diff_noun_type,
+    diff_parent_noun_id,
+    diff_metadata,
+    diff_visibility
+FROM noun_history
+    JOIN change_set on change_set.change_set_id = noun_history.change_set_id
+ORDER BY noun_history.change_set_id;
@@ -3,6 +3,8 @@ INSERT INTO noun_history (
         change_set_id,
         diff_name,
         diff_noun_type,
-        diff_metadata
+        diff_parent_noun_id,
+        diff_metadata,
+        diff_visibility
     )
-VALUES (?1, ?2, ?3, ?4, ?5);
\ No newline at end of file
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7);
\ No newline at end of file
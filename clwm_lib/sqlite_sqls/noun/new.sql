@@ -1,4 +1,11 @@
-INSERT INTO noun (name, last_change_set_id, noun_type_id, metadata)
+INSERT INTO noun (
+        name,
+        last_change_set_id,
+        noun_type_id,
+        parent_noun_id,
+        metadata,
+        visibility
+    )
 VALUES (
         ?1,
         ?2,
@@ -7,5 +14,7 @@ VALUES (
             FROM noun_type
             where noun_type = ?3
         ),
-        ?4
+        ?4,
+        ?5,
+        ?6
     );
\ No newline at end of file
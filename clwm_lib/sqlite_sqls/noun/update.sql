@@ -6,5 +6,7 @@ SET name = ?1,
         FROM noun_type
         where noun_type = ?3
     ),
-    metadata = ?4
-WHERE noun_id = ?5;
\ No newline at end of file
+    parent_noun_id = ?4,
+    metadata = ?5,
+    visibility = ?6
+WHERE noun_id = ?7;
\ No newline at end of file
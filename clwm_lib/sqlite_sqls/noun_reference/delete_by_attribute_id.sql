@@ -0,0 +1,2 @@
+DELETE FROM noun_reference
+WHERE attribute_id = ?1;
@@ -0,0 +1,5 @@
+SELECT attribute_id,
+    attribute_type_id,
+    source_noun_id,
+    target_noun_id
+FROM noun_reference;
@@ -0,0 +1,8 @@
+INSERT
+    OR IGNORE INTO noun_reference (
+        attribute_id,
+        attribute_type_id,
+        source_noun_id,
+        target_noun_id
+    )
+VALUES (?1, ?2, ?3, ?4);
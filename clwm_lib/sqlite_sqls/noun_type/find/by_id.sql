@@ -1,5 +1,6 @@
 SELECT noun_type_id,
     noun_type,
+    parent_noun_type_id,
     change_set.change_date,
     metadata
 FROM noun_type
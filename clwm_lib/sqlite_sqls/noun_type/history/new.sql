@@ -2,6 +2,7 @@ INSERT INTO noun_type_history (
         noun_type_id,
         change_set_id,
         diff_noun_type,
+        diff_parent_noun_type_id,
         diff_metadata
     )
-VALUES (?1, ?2, ?3, ?4);
\ No newline at end of file
+VALUES (?1, ?2, ?3, ?4, ?5);
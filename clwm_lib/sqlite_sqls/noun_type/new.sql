@@ -1,2 +1,7 @@
-INSERT INTO noun_type (noun_type, last_change_set_id, metadata)
-VALUES (?1, ?2, ?3)
\ No newline at end of file
+INSERT INTO noun_type (
+        noun_type,
+        parent_noun_type_id,
+        last_change_set_id,
+        metadata
+    )
+VALUES (?1, ?2, ?3, ?4)
@@ -1,5 +1,6 @@
 UPDATE noun_type
 SET noun_type = ?1,
-    last_change_set_id = ?2,
-    metadata = ?3
-WHERE noun_type_id = ?4;
\ No newline at end of file
+    parent_noun_type_id = ?2,
+    last_change_set_id = ?3,
+    metadata = ?4
+WHERE noun_type_id = ?5;
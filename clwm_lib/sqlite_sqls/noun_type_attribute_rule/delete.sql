@@ -0,0 +1,3 @@
+DELETE FROM noun_type_attribute_rule
+WHERE noun_type_id = ?1
+    AND attribute_type_id = ?2;
@@ -0,0 +1,10 @@
+SELECT noun_type_id,
+    attribute_type_id,
+    requirement,
+    min_count,
+    max_count,
+    default_data,
+    change_set.change_date
+FROM noun_type_attribute_rule
+    JOIN change_set ON change_set.change_set_id = noun_type_attribute_rule.last_change_set_id
+WHERE noun_type_attribute_rule.noun_type_id = ?1;
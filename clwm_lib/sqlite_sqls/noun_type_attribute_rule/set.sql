@@ -0,0 +1,16 @@
+INSERT INTO noun_type_attribute_rule (
+        noun_type_id,
+        attribute_type_id,
+        requirement,
+        min_count,
+        max_count,
+        default_data,
+        last_change_set_id
+    )
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7) ON CONFLICT(noun_type_id, attribute_type_id) DO
+UPDATE
+SET requirement = ?3,
+    min_count = ?4,
+    max_count = ?5,
+    default_data = ?6,
+    last_change_set_id = ?7;
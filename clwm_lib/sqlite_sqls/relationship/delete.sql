@@ -0,0 +1,2 @@
+DELETE FROM relationship
+WHERE relationship_id = ?1;
@@ -0,0 +1,10 @@
+SELECT relationship_id,
+    relationship_type.relationship_name,
+    source_noun_id,
+    target_noun_id,
+    change_set.change_date,
+    relationship.metadata
+FROM relationship
+    JOIN relationship_type ON relationship_type.relationship_type_id = relationship.relationship_type_id
+    JOIN change_set ON change_set.change_set_id = relationship.last_change_set_id
+WHERE relationship.ROWID = ?1;
@@ -0,0 +1,10 @@
+SELECT relationship_id,
+    relationship_history.change_set_id,
+    change_set.change_date,
+    diff_relationship_type,
+    diff_source_noun_id,
+    diff_target_noun_id,
+    diff_metadata
+FROM relationship_history
+    JOIN change_set on change_set.change_set_id = relationship_history.change_set_id
+where relationship_history.ROWID = ?1;
@@ -0,0 +1,9 @@
+INSERT INTO relationship_history (
+        relationship_id,
+        change_set_id,
+        diff_relationship_type,
+        diff_source_noun_id,
+        diff_target_noun_id,
+        diff_metadata
+    )
+VALUES (?1, ?2, ?3, ?4, ?5, ?6);
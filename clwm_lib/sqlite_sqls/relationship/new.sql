@@ -0,0 +1,18 @@
+INSERT INTO relationship (
+        relationship_type_id,
+        source_noun_id,
+        target_noun_id,
+        last_change_set_id,
+        metadata
+    )
+VALUES (
+        (
+            SELECT relationship_type_id
+            FROM relationship_type
+            where relationship_name = ?1
+        ),
+        ?2,
+        ?3,
+        ?4,
+        ?5
+    );
@@ -0,0 +1,10 @@
+SELECT relationship_type_id,
+    relationship_name,
+    inverse_name,
+    cardinality,
+    change_set.change_date,
+    metadata
+FROM relationship_type
+    JOIN change_set ON change_set.change_set_id = relationship_type.last_change_set_id
+WHERE relationship_type.relationship_name = ?1
+    OR relationship_type.inverse_name = ?1;
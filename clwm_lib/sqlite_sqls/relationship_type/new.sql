@@ -0,0 +1,8 @@
+INSERT INTO relationship_type (
+        relationship_name,
+        inverse_name,
+        cardinality,
+        last_change_set_id,
+        metadata
+    )
+VALUES (?1, ?2, ?3, ?4, ?5);
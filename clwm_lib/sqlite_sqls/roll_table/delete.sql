@@ -0,0 +1,2 @@
+DELETE FROM roll_table
+WHERE roll_table_name = ?1;
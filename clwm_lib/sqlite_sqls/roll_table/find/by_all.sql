@@ -0,0 +1,8 @@
+SELECT roll_table_id,
+    roll_table_name,
+    entries,
+    change_set.change_date,
+    roll_table.metadata
+FROM roll_table
+    JOIN change_set ON change_set.change_set_id = roll_table.last_change_set_id
+ORDER BY roll_table_name;
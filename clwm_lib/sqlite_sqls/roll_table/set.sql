@@ -0,0 +1,11 @@
+INSERT INTO roll_table (
+        roll_table_name,
+        entries,
+        last_change_set_id,
+        metadata
+    )
+VALUES (?1, ?2, ?3, ?4) ON CONFLICT(roll_table_name) DO
+UPDATE
+SET entries = ?2,
+    last_change_set_id = ?3,
+    metadata = ?4;
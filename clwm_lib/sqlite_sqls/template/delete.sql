@@ -0,0 +1,2 @@
+DELETE FROM template
+WHERE template_name = ?1;
@@ -0,0 +1,9 @@
+SELECT template_id,
+    template_name,
+    noun_type,
+    attributes,
+    change_set.change_date,
+    template.metadata
+FROM template
+    JOIN noun_type ON noun_type.noun_type_id = template.noun_type_id
+    JOIN change_set ON change_set.change_set_id = template.last_change_set_id;
@@ -0,0 +1,27 @@
+INSERT INTO template (
+        template_name,
+        noun_type_id,
+        attributes,
+        last_change_set_id,
+        metadata
+    )
+VALUES (
+        ?1,
+        (
+            SELECT noun_type_id
+            FROM noun_type
+            where noun_type = ?2
+        ),
+        ?3,
+        ?4,
+        ?5
+    ) ON CONFLICT(template_name) DO
+UPDATE
+SET noun_type_id = (
+        SELECT noun_type_id
+        FROM noun_type
+        where noun_type = ?2
+    ),
+    attributes = ?3,
+    last_change_set_id = ?4,
+    metadata = ?5;
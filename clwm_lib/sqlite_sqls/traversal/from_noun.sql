@@ -0,0 +1,84 @@
+WITH RECURSIVE edge(
+    from_noun_id,
+    to_noun_id,
+    attribute_type_id,
+    relationship_name
+) AS (
+    SELECT source_noun_id,
+        target_noun_id,
+        attribute_type_id,
+        NULL
+    FROM noun_reference
+    WHERE attribute_id NOT IN (
+            SELECT value
+            FROM json_each(?6)
+        )
+    UNION ALL
+    SELECT source_noun_id,
+        target_noun_id,
+        NULL,
+        relationship_type.relationship_name
+    FROM relationship
+        JOIN relationship_type ON relationship_type.relationship_type_id = relationship.relationship_type_id
+),
+filtered_edge(from_noun_id, to_noun_id) AS (
+    SELECT from_noun_id,
+        to_noun_id
+    FROM edge
+    WHERE (
+            (
+                ?4 IS NULL
+                AND ?5 IS NULL
+            )
+            OR attribute_type_id IN (
+                SELECT value
+                FROM json_each(?4)
+            )
+            OR relationship_name IN (
+                SELECT value
+                FROM json_each(?5)
+            )
+        )
+        AND from_noun_id NOT IN (
+            SELECT value
+            FROM json_each(?7)
+        )
+        AND to_noun_id NOT IN (
+            SELECT value
+            FROM json_each(?7)
+        )
+),
+directed_edge(from_noun_id, to_noun_id) AS (
+    SELECT from_noun_id,
+        to_noun_id
+    FROM filtered_edge
+    WHERE ?3 IN ('Outgoing', 'Both')
+    UNION
+    SELECT to_noun_id,
+        from_noun_id
+    FROM filtered_edge
+    WHERE ?3 IN ('Incoming', 'Both')
+),
+traversal(noun_id, depth, path) AS (
+    SELECT ?1,
+        0,
+        ',' || ?1 || ','
+    UNION ALL
+    SELECT directed_edge.to_noun_id,
+        traversal.depth + 1,
+        traversal.path || directed_edge.to_noun_id || ','
+    FROM traversal
+        JOIN directed_edge ON directed_edge.from_noun_id = traversal.noun_id
+    WHERE traversal.depth < ?2
+        AND instr(
+            traversal.path,
+            ',' || directed_edge.to_noun_id || ','
+        ) = 0
+)
+SELECT noun_id AS "noun_id!: i64",
+    MIN(depth) AS "depth!: i64",
+    path AS "path!: String"
+FROM traversal
+GROUP BY noun_id
+ORDER BY 2,
+    1;
@@ -0,0 +1,351 @@
+use crate::{
+    clwm_error::ClwmError,
+    model::{CalendarDefinition, CalendarEra},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarDate {
+    pub year: i64,
+    pub month: usize,
+    pub day: i64,
+}
+
+const MOON_PHASES: [&str; 8] = [
+    "New",
+    "Waxing Crescent",
+    "First Quarter",
+    "Waxing Gibbous",
+    "Full",
+    "Waning Gibbous",
+    "Last Quarter",
+    "Waning Crescent",
+];
+
+pub fn check_calendar(calendar: &CalendarDefinition) -> anyhow::Result<()> {
+    if calendar.months.is_empty() {
+        anyhow::bail!(invalid_calendar("a calendar needs at least one month"))
+    }
+    if calendar
+        .months
+        .iter()
+        .any(|month| month.days < 1 || month.leap_days < 0)
+    {
+        anyhow::bail!(invalid_calendar(
+            "every month needs at least one day and no negative leap days"
+        ))
+    }
+    if let Some(leap_rule) = &calendar.leap_rule {
+        if leap_rule.every < 1 {
+            anyhow::bail!(invalid_calendar(
+                "leap years must repeat every year or more"
+            ))
+        }
+        if let Some(except_every) = leap_rule.except_every {
+            if except_every < 1 || except_every % leap_rule.every != 0 {
+                anyhow::bail!(invalid_calendar("except_every must be a multiple of every"))
+            }
+        }
+        if let Some(unless_every) = leap_rule.unless_every {
+            match leap_rule.except_every {
+                Some(except_every) if unless_every > 0 && unless_every % except_every == 0 => {}
+                _ => anyhow::bail!(invalid_calendar(
+                    "unless_every must be a multiple of except_every"
+                )),
+            }
+        }
+    }
+    if calendar.moons.iter().any(|moon| moon.cycle_days < 1) {
+        anyhow::bail!(invalid_calendar(
+            "every moon needs a cycle of at least one day"
+        ))
+    }
+    Ok(())
+}
+
+pub fn is_leap_year(calendar: &CalendarDefinition, year: i64) -> bool {
+    match &calendar.leap_rule {
+        Some(leap_rule) => {
+            let divisible =
+                |every: Option<i64>| every.is_some_and(|every| year.rem_euclid(every) == 0);
+            if divisible(leap_rule.unless_every) {
+                true
+            } else if divisible(leap_rule.except_every) {
+                false
+            } else {
+                divisible(Some(leap_rule.every))
+            }
+        }
+        None => false,
+    }
+}
+
+pub fn month_length(calendar: &CalendarDefinition, year: i64, month: usize) -> i64 {
+    let calendar_month = &calendar.months[month - 1];
+    if is_leap_year(calendar, year) {
+        calendar_month.days + calendar_month.leap_days
+    } else {
+        calendar_month.days
+    }
+}
+
+pub fn year_length(calendar: &CalendarDefinition, year: i64) -> i64 {
+    (1..=calendar.months.len())
+        .map(|month| month_length(calendar, year, month))
+        .sum()
+}
+
+fn leap_years_up_to(calendar: &CalendarDefinition, year: i64) -> i64 {
+    match &calendar.leap_rule {
+        Some(leap_rule) => {
+            year.div_euclid(leap_rule.every)
+                - leap_rule
+                    .except_every
+                    .map_or(0, |except_every| year.div_euclid(except_every))
+                + leap_rule
+                    .unless_every
+                    .map_or(0, |unless_every| year.div_euclid(unless_every))
+        }
+        None => 0,
+    }
+}
+
+fn days_before_year(calendar: &CalendarDefinition, year: i64) -> i64 {
+    let common_year: i64 = calendar.months.iter().map(|month| month.days).sum();
+    let leap_extra: i64 = calendar.months.iter().map(|month| month.leap_days).sum();
+    (year - 1) * common_year + leap_years_up_to(calendar, year - 1) * leap_extra
+}
+
+pub fn date_to_day(calendar: &CalendarDefinition, date: CalendarDate) -> anyhow::Result<i64> {
+    if date.month < 1 || date.month > calendar.months.len() {
+        anyhow::bail!(ClwmError::InvalidWorldDate {
+            date: format!("{}-{}-{}", date.year, date.month, date.day)
+        })
+    }
+    if date.day < 1 || date.day > month_length(calendar, date.year, date.month) {
+        anyhow::bail!(ClwmError::InvalidWorldDate {
+            date: format!("{}-{}-{}", date.year, date.month, date.day)
+        })
+    }
+    let days_before_month: i64 = (1..date.month)
+        .map(|month| month_length(calendar, date.year, month))
+        .sum();
+    Ok(days_before_year(calendar, date.year) + days_before_month + date.day - 1)
+}
+
+pub fn day_to_date(calendar: &CalendarDefinition, day: i64) -> CalendarDate {
+    let common_year: i64 = calendar.months.iter().map(|month| month.days).sum();
+    let mut year = day.div_euclid(common_year) + 1;
+    while days_before_year(calendar, year) > day {
+        year -= 1;
+    }
+    while days_before_year(calendar, year + 1) <= day {
+        year += 1;
+    }
+
+    let mut remaining = day - days_before_year(calendar, year);
+    let mut month = 1;
+    while remaining >= month_length(calendar, year, month) {
+        remaining -= month_length(calendar, year, month);
+        month += 1;
+    }
+    CalendarDate {
+        year,
+        month,
+        day: remaining + 1,
+    }
+}
+
+pub fn weekday_index(calendar: &CalendarDefinition, day: i64) -> Option<usize> {
+    if calendar.weekdays.is_empty() {
+        return None;
+    }
+    Some((day + calendar.epoch_weekday).rem_euclid(calendar.weekdays.len() as i64) as usize)
+}
+
+pub fn weekday(calendar: &CalendarDefinition, day: i64) -> Option<&str> {
+    weekday_index(calendar, day).map(|index| calendar.weekdays[index].as_str())
+}
+
+pub fn moon_phases(calendar: &CalendarDefinition, day: i64) -> Vec<(&str, &'static str)> {
+    calendar
+        .moons
+        .iter()
+        .map(|moon| {
+            let position = (day - moon.offset_days).rem_euclid(moon.cycle_days);
+            let phase = (position * 8 + moon.cycle_days / 2) / moon.cycle_days % 8;
+            (moon.name.as_str(), MOON_PHASES[phase as usize])
+        })
+        .collect()
+}
+
+pub fn era_of_year(calendar: &CalendarDefinition, year: i64) -> Option<&CalendarEra> {
+    calendar
+        .eras
+        .iter()
+        .filter(|era| era.start_year <= year)
+        .max_by_key(|era| era.start_year)
+}
+
+pub fn format_date(calendar: &CalendarDefinition, day: i64) -> String {
+    let date = day_to_date(calendar, day);
+    let month_name = &calendar.months[date.month - 1].name;
+    match era_of_year(calendar, date.year) {
+        Some(era) => format!(
+            "{} {} {} {}",
+            date.day,
+            month_name,
+            date.year - era.start_year + 1,
+            era.abbreviation
+        ),
+        None => format!("{} {} {}", date.day, month_name, date.year),
+    }
+}
+
+pub fn parse_date(calendar: &CalendarDefinition, text: &str) -> anyhow::Result<i64> {
+    let invalid = || ClwmError::InvalidWorldDate {
+        date: text.to_owned(),
+    };
+    let text = text.trim();
+
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => (-1, unsigned),
+        None => (1, text),
+    };
+    let numbers: Vec<&str> = unsigned.split('-').collect();
+    if numbers.len() == 3 {
+        let date = CalendarDate {
+            year: sign * numbers[0].parse::<i64>().map_err(|_| invalid())?,
+            month: numbers[1].parse::<usize>().map_err(|_| invalid())?,
+            day: numbers[2].parse::<i64>().map_err(|_| invalid())?,
+        };
+        return date_to_day(calendar, date);
+    }
+
+    let parts: Vec<&str> = text.split_whitespace().collect();
+    if parts.len() < 3 {
+        anyhow::bail!(invalid())
+    }
+    let day = parts[0].parse::<i64>().map_err(|_| invalid())?;
+    let year = parts[parts.len() - 1]
+        .parse::<i64>()
+        .ok()
+        .map(|year| (year, None))
+        .or_else(|| {
+            let era_name = parts[parts.len() - 1];
+            let era = calendar.eras.iter().find(|era| {
+                era.abbreviation.eq_ignore_ascii_case(era_name)
+                    || era.name.eq_ignore_ascii_case(era_name)
+            })?;
+            let year = parts.get(parts.len() - 2)?.parse::<i64>().ok()?;
+            Some((year, Some(era)))
+        });
+    let (year, month_parts) = match year {
+        Some((year, None)) => (year, &parts[1..parts.len() - 1]),
+        Some((year, Some(era))) => (era.start_year + year - 1, &parts[1..parts.len() - 2]),
+        None => anyhow::bail!(invalid()),
+    };
+    let month_name = month_parts.join(" ");
+    let month = match calendar
+        .months
+        .iter()
+        .position(|month| month.name.eq_ignore_ascii_case(&month_name))
+    {
+        Some(index) => index + 1,
+        None => anyhow::bail!(invalid()),
+    };
+    date_to_day(calendar, CalendarDate { year, month, day })
+}
+
+fn invalid_calendar(message: &str) -> ClwmError {
+    ClwmError::InvalidCalendar {
+        message: message.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{CalendarLeapRule, CalendarMonth};
+
+    fn gregorian() -> CalendarDefinition {
+        let days = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+        CalendarDefinition {
+            months: days
+                .iter()
+                .enumerate()
+                .map(|(index, days)| CalendarMonth {
+                    name: format!("Month {}", index + 1),
+                    days: *days,
+                    leap_days: if index == 1 { 1 } else { 0 },
+                })
+                .collect(),
+            weekdays: Vec::new(),
+            epoch_weekday: 0,
+            leap_rule: Some(CalendarLeapRule {
+                every: 4,
+                except_every: Some(100),
+                unless_every: Some(400),
+            }),
+            eras: Vec::new(),
+            moons: Vec::new(),
+        }
+    }
+
+    fn date(year: i64, month: usize, day: i64) -> CalendarDate {
+        CalendarDate { year, month, day }
+    }
+
+    #[test]
+    fn leap_years_up_to_counts_the_leap_years_from_year_one() {
+        let calendar = gregorian();
+        assert_eq!(leap_years_up_to(&calendar, 0), 0);
+        assert_eq!(leap_years_up_to(&calendar, 3), 0);
+        assert_eq!(leap_years_up_to(&calendar, 4), 1);
+        assert_eq!(leap_years_up_to(&calendar, 1900), 460);
+        assert_eq!(leap_years_up_to(&calendar, 2000), 485);
+        let mut counted = 0;
+        for year in 1..=2400 {
+            if is_leap_year(&calendar, year) {
+                counted += 1;
+            }
+            assert_eq!(leap_years_up_to(&calendar, year), counted, "year {}", year);
+        }
+    }
+
+    #[test]
+    fn leap_years_up_to_counts_back_before_year_one() {
+        let calendar = gregorian();
+        // Year 0 is a leap year, so the count steps down past it.
+        assert_eq!(leap_years_up_to(&calendar, -1), -1);
+        assert_eq!(leap_years_up_to(&calendar, -4), -1);
+        assert_eq!(leap_years_up_to(&calendar, -5), -2);
+    }
+
+    #[test]
+    fn day_to_date_starts_at_the_first_day_of_year_one() {
+        let calendar = gregorian();
+        assert_eq!(day_to_date(&calendar, 0), date(1, 1, 1));
+        assert_eq!(day_to_date(&calendar, 364), date(1, 12, 31));
+        assert_eq!(day_to_date(&calendar, 365), date(2, 1, 1));
+        assert_eq!(day_to_date(&calendar, -1), date(0, 12, 31));
+        assert_eq!(day_to_date(&calendar, -366), date(0, 1, 1));
+    }
+
+    #[test]
+    fn day_to_date_lands_on_leap_days() {
+        let calendar = gregorian();
+        let leap_day = date_to_day(&calendar, date(2000, 2, 29)).unwrap();
+        assert_eq!(day_to_date(&calendar, leap_day), date(2000, 2, 29));
+        assert_eq!(day_to_date(&calendar, leap_day + 1), date(2000, 3, 1));
+        assert!(date_to_day(&calendar, date(1900, 2, 29)).is_err());
+    }
+
+    #[test]
+    fn day_to_date_reverses_date_to_day() {
+        let calendar = gregorian();
+        for day in (-800_000..800_000).step_by(997) {
+            let date = day_to_date(&calendar, day);
+            assert_eq!(date_to_day(&calendar, date).unwrap(), day, "{:?}", date);
+        }
+    }
+}
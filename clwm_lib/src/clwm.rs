@@ -1,37 +1,119 @@
+use std::collections::HashMap;
+
 use anyhow::Ok;
 use async_recursion::async_recursion;
 use diffy::create_patch;
 use futures::future;
+use sha2::{Digest, Sha256};
 
 use crate::{
+    calendar::{check_calendar, format_date, parse_date},
     clwm_error::ClwmError,
     clwm_file::ClwmFile,
     data_interface::{DataInterface, DataInterfaceAccessTransaction, DataInterfaceType},
     data_interfaces::data_interface_sqlite::DataInterfaceSQLite,
+    dice::{AttributeDiceRoll, DiceExpression},
+    formula::{evaluate_formula, formula_references, parse_formula, FormulaReference, FormulaStep},
     model::{
-        Attribute, AttributeHistory, AttributeType, AttributeTypeHistory, DataObject, DataType,
-        DataTypeDefinition, Noun, NounHistory, NounType, NounTypeHistory,
+        Asset, AssetReference, Attribute, AttributeHistory, AttributeRequirement, AttributeType,
+        AttributeTypeHistory, BundleAsset, Calendar, CalendarDefinition, CustomDataObject,
+        DataObject, DataType, DataTypeDefinition, Event, EventHistory, EventParticipant, EventRole,
+        GraphEdge, GraphFilter, MapDataObject, Noun, NounGraph, NounHistory, NounReferenceEdge,
+        NounRelation, NounType, NounTypeAttributeRule, NounTypeHistory, NounValidationIssue,
+        RedactionRules, RedactionSummary, RelationDirection, Relationship, RelationshipCardinality,
+        RelationshipHistory, RelationshipType, RollNounFilter, RollResult, RollTable, Template,
+        TemplateAttribute, TimelineEntry, TimelineFilter, TraversalOptions, TraversalResult,
+        VaultImportPlan, VaultMapping, Viewer, Visibility, WorldBundle, WorldHistory,
+        WORLD_BUNDLE_VERSION,
+    },
+    roll::{check_roll_table, parse_roll_text, RollGenerator, RollTextPart, MAX_ROLL_DEPTH},
+    site::{render_site, SiteFile},
+    table::{
+        attribute_columns, column_value, decode_cell, encode_cell, set_column_value, NounTable,
+        TableColumn, TableImportSummary, TableRowError, ID_COLUMN, NAME_COLUMN,
     },
+    vault::{plan_vault, VaultNote},
+    wiki::{render_wiki, WikiPage},
 };
 
 pub struct Clwm {
     pub data_interface: Box<dyn DataInterface>,
     pub clwm_file: ClwmFile,
+    pub viewer: Viewer,
 }
 
 impl Clwm {
     pub async fn new(file_name: String) -> anyhow::Result<Clwm> {
-        let clwm_file = ClwmFile::load_file(file_name.into())?;
+        Self::from_clwm_file(ClwmFile::load_file(file_name.into())?).await
+    }
+
+    async fn from_clwm_file(clwm_file: ClwmFile) -> anyhow::Result<Clwm> {
         let mut data_interface: Box<dyn DataInterface> = match &clwm_file.data_interface {
             DataInterfaceType::Sqlite => Box::new(DataInterfaceSQLite::new(clwm_file.url.clone())),
         };
 
         data_interface.init().await?;
 
-        Ok(Clwm {
+        let clwm = Clwm {
             data_interface,
             clwm_file,
-        })
+            viewer: Viewer::default(),
+        };
+        clwm.upgrade_schema().await?;
+        Ok(clwm)
+    }
+
+    /// Upgrades a world created by an older version. The references existing
+    /// attributes hold are indexed again, as older worlds never recorded them.
+    async fn upgrade_schema(&self) -> anyhow::Result<()> {
+        let transaction = self
+            .data_interface
+            .create_transaction("Upgrade".to_owned())
+            .await?;
+        if !transaction.upgrade_schema().await? {
+            transaction.rollback().await?;
+            return Ok(());
+        }
+        for attribute in transaction.find_attribute_by_all().await? {
+            Self::index_noun_references(&attribute, &transaction).await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    pub fn set_viewer(&mut self, viewer: Viewer) {
+        self.viewer = viewer;
+    }
+
+    /// Opens a unit of work. Until it is committed or rolled back every change
+    /// is made in one transaction and recorded in one change set.
+    pub async fn begin_unit_of_work(&mut self) -> anyhow::Result<()> {
+        self.data_interface
+            .begin_unit_of_work("CLWM".to_string())
+            .await
+    }
+
+    pub async fn commit_unit_of_work(&mut self) -> anyhow::Result<()> {
+        self.data_interface.commit_unit_of_work().await
+    }
+
+    pub async fn rollback_unit_of_work(&mut self) -> anyhow::Result<()> {
+        self.data_interface.rollback_unit_of_work().await
+    }
+
+    pub fn in_unit_of_work(&self) -> bool {
+        self.data_interface.in_unit_of_work()
+    }
+
+    /// Marks a point in the open unit of work that can be rolled back to
+    /// without discarding the rest of the unit.
+    pub async fn savepoint(&self) -> anyhow::Result<Box<dyn DataInterfaceAccessTransaction>> {
+        if !self.in_unit_of_work() {
+            anyhow::bail!(ClwmError::NoUnitOfWorkOpen);
+        }
+        self.data_interface
+            .create_transaction("CLWM".to_string())
+            .await
     }
 
     pub async fn create(
@@ -57,7 +139,18 @@ impl Clwm {
             .data_interface
             .create_transaction("CLWM".to_owned())
             .await?;
+        let created_noun = Self::create_noun(name, noun_type, metadata, &[], &transaction).await?;
+        transaction.commit().await?;
+        Ok(created_noun)
+    }
 
+    async fn create_noun(
+        name: String,
+        noun_type: String,
+        metadata: String,
+        templated_attribute_type_ids: &[i64],
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<Noun> {
         let found_noun_types = transaction
             .find_noun_type_by_noun_type(noun_type.clone())
             .await?;
@@ -77,36 +170,293 @@ impl Clwm {
             last_changed: None,
             name,
             noun_type,
+            parent_noun_id: None,
             metadata,
+            visibility: Visibility::Public,
             attributes: None,
         };
 
         let created_noun = transaction.new_noun(new_noun).await?;
         let noun_history = NounHistory {
             noun_id: created_noun.noun_id.unwrap(),
+            change_set_id: None,
             change_date: None,
             diff_name: create_patch("", &created_noun.name).to_string(),
             diff_noun_type: create_patch("", &created_noun.noun_type).to_string(),
+            diff_parent_noun_id: create_patch("", &optional_id_to_string(None)).to_string(),
             diff_metadata: create_patch("", &created_noun.metadata).to_string(),
+            diff_visibility: create_patch("", &created_noun.visibility.to_string()).to_string(),
         };
 
         transaction.new_noun_history(noun_history).await?;
 
+        for rule in Self::find_noun_type_rules(created_noun.noun_type.clone(), transaction).await? {
+            if templated_attribute_type_ids.contains(&rule.attribute_type_id) {
+                continue;
+            }
+            if let Some(default_data) = rule.default_data {
+                Self::create_attribute(
+                    rule.attribute_type_id,
+                    created_noun.noun_id,
+                    None,
+                    default_data,
+                    None,
+                    String::new(),
+                    transaction,
+                )
+                .await?;
+            }
+        }
+
+        Ok(created_noun)
+    }
+
+    pub async fn set_template(&mut self, template: Template) -> anyhow::Result<Template> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let rules = Self::find_noun_type_rules(template.noun_type.clone(), &transaction).await?;
+        Self::check_template_attributes(
+            &template.attributes,
+            Some((&template.noun_type, &rules)),
+            &transaction,
+        )
+        .await?;
+        let saved_template = transaction.set_template(template).await?;
+        transaction.commit().await?;
+        Ok(saved_template)
+    }
+
+    pub async fn remove_template(&mut self, template_name: String) -> anyhow::Result<()> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        if transaction
+            .find_template_by_name(template_name.clone())
+            .await?
+            .is_none()
+        {
+            anyhow::bail!(ClwmError::TemplateNotFound)
+        }
+        transaction.delete_template(template_name).await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    pub async fn get_template_by_name(
+        &mut self,
+        template_name: String,
+    ) -> anyhow::Result<Option<Template>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction.find_template_by_name(template_name).await?)
+    }
+
+    pub async fn get_all_templates(&mut self) -> anyhow::Result<Vec<Template>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction.find_template_by_all().await?)
+    }
+
+    pub async fn instantiate_template(
+        &mut self,
+        template_name: String,
+        name: String,
+        noun_type: Option<String>,
+        mut placeholders: HashMap<String, String>,
+        metadata: String,
+    ) -> anyhow::Result<Noun> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let template = match transaction.find_template_by_name(template_name).await? {
+            Some(template) => template,
+            None => anyhow::bail!(ClwmError::TemplateNotFound),
+        };
+        // A template for a noun type also fits any of its subtypes.
+        let noun_type = match noun_type {
+            Some(noun_type) if noun_type != template.noun_type => {
+                let noun_type_id = match transaction
+                    .find_noun_type_by_noun_type(noun_type.clone())
+                    .await?
+                    .into_iter()
+                    .find(|x| x.noun_type == noun_type)
+                {
+                    Some(found_noun_type) => found_noun_type.noun_type_id.unwrap(),
+                    None => anyhow::bail!(ClwmError::NounTypeNotFound),
+                };
+                if !Self::find_noun_type_ancestry(noun_type_id, &transaction)
+                    .await?
+                    .iter()
+                    .any(|ancestor| ancestor.noun_type == template.noun_type)
+                {
+                    anyhow::bail!(ClwmError::TemplateNounTypeMismatch {
+                        template: template.template_name,
+                        template_noun_type: template.noun_type,
+                        noun_type
+                    })
+                }
+                noun_type
+            }
+            _ => template.noun_type.clone(),
+        };
+        placeholders
+            .entry("name".to_owned())
+            .or_insert_with(|| name.clone());
+
+        let templated_attribute_type_ids: Vec<i64> = template
+            .attributes
+            .iter()
+            .map(|template_attribute| template_attribute.attribute_type_id)
+            .collect();
+        let created_noun = Self::create_noun(
+            name,
+            noun_type,
+            metadata,
+            &templated_attribute_type_ids,
+            &transaction,
+        )
+        .await?;
+        Self::create_template_attributes(
+            &template.attributes,
+            created_noun.noun_id,
+            None,
+            &placeholders,
+            &transaction,
+        )
+        .await?;
         transaction.commit().await?;
         Ok(created_noun)
     }
 
+    #[async_recursion(?Send)]
+    async fn check_template_attributes(
+        template_attributes: &[TemplateAttribute],
+        noun_type_rules: Option<(&String, &[NounTypeAttributeRule])>,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<()> {
+        for template_attribute in template_attributes.iter() {
+            let attribute_type = match transaction
+                .find_attribute_type_by_id(template_attribute.attribute_type_id)
+                .await?
+            {
+                Some(attribute_type) => attribute_type,
+                None => anyhow::bail!(ClwmError::AttributeTypeNotFound),
+            };
+            if attribute_type.formula.is_some() && template_attribute.data != DataObject::Null {
+                anyhow::bail!(ClwmError::ComputedAttributeCannotHaveData)
+            }
+            match transaction
+                .find_data_type_latest_by_name(attribute_type.data_type.clone())
+                .await?
+            {
+                Some(data_type) => {
+                    if !is_data_of_data_def(&template_attribute.data, &data_type.definition, true) {
+                        anyhow::bail!(ClwmError::DataDoesNotMatchDataTypeDefinition)
+                    }
+                }
+                None => anyhow::bail!(ClwmError::DataTypeNotFound),
+            }
+            Self::check_asset_references(&template_attribute.data, transaction).await?;
+
+            let sibling_count = template_attributes
+                .iter()
+                .filter(|x| x.attribute_type_id == template_attribute.attribute_type_id)
+                .count() as i64;
+            if !attribute_type.multiple_allowed && sibling_count > 1 {
+                anyhow::bail!(ClwmError::AttributeTypeDoesNotAllowMultipleAttributes {
+                    attribute_type: attribute_type.attribute_name
+                })
+            }
+            if let Some((noun_type, rules)) = noun_type_rules {
+                if let Some(rule) = rules
+                    .iter()
+                    .find(|rule| rule.attribute_type_id == template_attribute.attribute_type_id)
+                {
+                    if rule.requirement == AttributeRequirement::Forbidden {
+                        anyhow::bail!(ClwmError::AttributeTypeForbiddenForNounType {
+                            attribute_type: attribute_type.attribute_name,
+                            noun_type: noun_type.clone()
+                        })
+                    }
+                    if let Some(max_count) = rule.max_count {
+                        if sibling_count > max_count {
+                            anyhow::bail!(ClwmError::AttributeTypeCardinalityExceeded {
+                                attribute_type: attribute_type.attribute_name,
+                                max_count
+                            })
+                        }
+                    }
+                }
+            }
+
+            Self::check_template_attributes(&template_attribute.children, None, transaction)
+                .await?;
+        }
+        Ok(())
+    }
+
+    #[async_recursion(?Send)]
+    async fn create_template_attributes(
+        template_attributes: &[TemplateAttribute],
+        parent_noun_id: Option<i64>,
+        parent_attribute_id: Option<i64>,
+        placeholders: &HashMap<String, String>,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<()> {
+        for template_attribute in template_attributes.iter() {
+            let data = fill_placeholders(&template_attribute.data, placeholders)?;
+            Self::check_asset_references(&data, transaction).await?;
+            let created_attribute = Self::create_attribute(
+                template_attribute.attribute_type_id,
+                parent_noun_id,
+                parent_attribute_id,
+                data,
+                None,
+                fill_placeholder_text(&template_attribute.metadata, placeholders)?,
+                transaction,
+            )
+            .await?;
+            Self::create_template_attributes(
+                &template_attribute.children,
+                None,
+                created_attribute.attribute_id,
+                placeholders,
+                transaction,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
     pub async fn get_all_nouns(&mut self) -> anyhow::Result<Vec<Noun>> {
         let transaction = self
             .data_interface
             .create_transaction("CLWM".to_owned())
             .await?;
-        Ok(transaction.find_noun_by_all().await?)
+        Ok(self.visible_nouns(transaction.find_noun_by_all().await?))
+    }
+
+    /// Nouns whose name contains `name`.
+    pub async fn find_nouns_by_name(&mut self, name: String) -> anyhow::Result<Vec<Noun>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(self.visible_nouns(transaction.find_noun_by_name(name).await?))
     }
 
     pub async fn new_noun_type(
         &mut self,
         noun_type: String,
+        parent_noun_type: Option<String>,
         metadata: String,
     ) -> anyhow::Result<NounType> {
         let transaction = self
@@ -124,22 +474,45 @@ impl Clwm {
                 noun_type: noun_type.clone()
             })
         };
+        let parent_noun_type_id = match parent_noun_type {
+            Some(parent_noun_type) => {
+                Some(Self::find_noun_type_id(parent_noun_type, &transaction).await?)
+            }
+            None => None,
+        };
+        let created_noun_type =
+            Self::create_noun_type(noun_type, parent_noun_type_id, metadata, &transaction).await?;
+        transaction.commit().await?;
+        Ok(created_noun_type)
+    }
+
+    async fn create_noun_type(
+        noun_type: String,
+        parent_noun_type_id: Option<i64>,
+        metadata: String,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<NounType> {
         let new_noun_type = NounType {
             noun_type_id: None,
             last_changed: None,
             noun_type,
+            parent_noun_type_id,
             metadata: metadata,
         };
         let created_noun_type = transaction.new_noun_type(new_noun_type).await?;
         let noun_type_history = NounTypeHistory {
             noun_type_id: created_noun_type.noun_type_id.unwrap(),
+            change_set_id: None,
             change_date: None,
             diff_noun_type: create_patch("", &created_noun_type.noun_type).to_string(),
+            diff_parent_noun_type_id: create_patch(
+                "",
+                &optional_id_to_string(created_noun_type.parent_noun_type_id),
+            )
+            .to_string(),
             diff_metadata: create_patch("", &created_noun_type.metadata).to_string(),
         };
         transaction.new_noun_type_history(noun_type_history).await?;
-
-        transaction.commit().await?;
         Ok(created_noun_type)
     }
 
@@ -167,499 +540,3651 @@ impl Clwm {
             possible_old_noun.unwrap()
         };
 
+        if let Some(parent_noun_id) = noun.parent_noun_id {
+            if transaction.find_noun_by_id(parent_noun_id).await?.is_none() {
+                anyhow::bail!(ClwmError::NounNotFound);
+            }
+            if parent_noun_id == old_noun.noun_id.unwrap()
+                || transaction
+                    .find_noun_ancestors_by_id(parent_noun_id)
+                    .await?
+                    .iter()
+                    .any(|ancestor| ancestor.noun_id == old_noun.noun_id)
+            {
+                anyhow::bail!(ClwmError::NounContainmentCycle {
+                    noun: old_noun.name
+                });
+            }
+        }
+
+        let new_noun = Self::save_noun(&old_noun, noun, &transaction).await?;
+        transaction.commit().await?;
+        Ok(new_noun)
+    }
+
+    async fn save_noun(
+        old_noun: &Noun,
+        noun: Noun,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<Noun> {
         let new_noun = transaction.update_noun(noun).await?;
 
         let noun_history = NounHistory {
             noun_id: new_noun.noun_id.unwrap(),
+            change_set_id: None,
             change_date: None,
             diff_name: create_patch(&old_noun.name, &new_noun.name).to_string(),
             diff_noun_type: create_patch(&old_noun.noun_type, &new_noun.noun_type).to_string(),
+            diff_parent_noun_id: create_patch(
+                &optional_id_to_string(old_noun.parent_noun_id),
+                &optional_id_to_string(new_noun.parent_noun_id),
+            )
+            .to_string(),
             diff_metadata: create_patch(&old_noun.metadata, &new_noun.metadata).to_string(),
+            diff_visibility: create_patch(
+                &old_noun.visibility.to_string(),
+                &new_noun.visibility.to_string(),
+            )
+            .to_string(),
         };
 
         transaction.new_noun_history(noun_history).await?;
-        transaction.commit().await?;
         Ok(new_noun)
     }
 
-    pub async fn update_noun_type(&mut self, noun_type: NounType) -> anyhow::Result<NounType> {
-        let transaction = self
-            .data_interface
-            .create_transaction("CLWM".to_owned())
-            .await?;
-        if noun_type.noun_type_id.is_none() {
-            anyhow::bail!(ClwmError::NounTypeHasNoId);
-        }
-
-        let possible_old_noun_type = transaction
-            .find_noun_type_by_id(noun_type.noun_type_id.unwrap())
-            .await?;
-
-        let old_noun_type = if possible_old_noun_type.is_none() {
-            anyhow::bail!(ClwmError::NounTypeNotFound);
-        } else {
-            possible_old_noun_type.unwrap()
-        };
-
-        let new_noun_type = transaction.update_noun_type(noun_type).await?;
-
-        let noun_type_history = NounTypeHistory {
-            noun_type_id: new_noun_type.noun_type_id.unwrap(),
-            change_date: None,
-            diff_noun_type: create_patch(&old_noun_type.noun_type, &new_noun_type.noun_type)
-                .to_string(),
-            diff_metadata: create_patch(&old_noun_type.metadata, &new_noun_type.metadata)
-                .to_string(),
+    pub async fn move_noun(
+        &mut self,
+        noun_id: i64,
+        parent_noun_id: Option<i64>,
+    ) -> anyhow::Result<Noun> {
+        let mut noun = match self.get_noun_by_id(noun_id).await? {
+            Some(noun) => noun,
+            None => anyhow::bail!(ClwmError::NounNotFound),
         };
-        transaction.new_noun_type_history(noun_type_history).await?;
-        transaction.commit().await?;
-        Ok(new_noun_type)
+        noun.parent_noun_id = parent_noun_id;
+        self.update_noun(noun).await
     }
 
-    pub async fn get_noun_by_id(&mut self, id: i64) -> anyhow::Result<Option<Noun>> {
+    pub async fn get_noun_ancestors(&mut self, noun_id: i64) -> anyhow::Result<Vec<Noun>> {
         let transaction = self
             .data_interface
             .create_transaction("CLWM".to_owned())
             .await?;
-        Ok(transaction.find_noun_by_id(id).await?)
+        if !Self::is_noun_visible(&self.viewer, noun_id, &transaction).await? {
+            anyhow::bail!(ClwmError::NounNotFound);
+        }
+        // Ancestors come nearest first, so the chain stops at the first
+        // hidden noun rather than skipping over it.
+        Ok(transaction
+            .find_noun_ancestors_by_id(noun_id)
+            .await?
+            .into_iter()
+            .take_while(|noun| self.viewer.can_see(&noun.visibility))
+            .collect())
     }
 
-    pub async fn get_noun_type_by_id(&mut self, id: i64) -> anyhow::Result<Option<NounType>> {
+    pub async fn get_noun_descendants(&mut self, noun_id: i64) -> anyhow::Result<Vec<Noun>> {
         let transaction = self
             .data_interface
             .create_transaction("CLWM".to_owned())
             .await?;
-        Ok(transaction.find_noun_type_by_id(id).await?)
+        if !Self::is_noun_visible(&self.viewer, noun_id, &transaction).await? {
+            anyhow::bail!(ClwmError::NounNotFound);
+        }
+        // Nouns below a hidden noun are left out along with it.
+        let descendants =
+            self.visible_nouns(transaction.find_noun_descendants_by_id(noun_id).await?);
+        let mut reachable = vec![noun_id];
+        loop {
+            let found: Vec<i64> = descendants
+                .iter()
+                .filter(|noun| {
+                    noun.parent_noun_id
+                        .is_some_and(|parent_noun_id| reachable.contains(&parent_noun_id))
+                })
+                .filter_map(|noun| noun.noun_id)
+                .filter(|noun_id| !reachable.contains(noun_id))
+                .collect();
+            if found.is_empty() {
+                break;
+            }
+            reachable.extend(found);
+        }
+        Ok(descendants
+            .into_iter()
+            .filter(|noun| {
+                noun.noun_id
+                    .is_some_and(|noun_id| reachable.contains(&noun_id))
+            })
+            .collect())
     }
 
-    pub async fn new_data_type(
+    pub async fn set_event(
         &mut self,
-        name: String,
-        defintion: DataTypeDefinition,
-    ) -> anyhow::Result<DataType> {
+        noun_id: i64,
+        start_day: i64,
+        end_day: Option<i64>,
+    ) -> anyhow::Result<Event> {
         let transaction = self
             .data_interface
             .create_transaction("CLWM".to_owned())
             .await?;
-
-        let possible_data_type = transaction
-            .find_data_type_latest_by_name(name.clone())
-            .await?;
-
-        if let Some(data_type) = possible_data_type {
-            if data_type.name == name {
-                anyhow::bail!(ClwmError::DataTypeAlreadyExists { data_type: name })
-            }
+        if transaction.find_noun_by_id(noun_id).await?.is_none() {
+            anyhow::bail!(ClwmError::NounNotFound);
+        }
+        if end_day.is_some_and(|end_day| end_day < start_day) {
+            anyhow::bail!(ClwmError::EventEndsBeforeStart);
         }
 
-        let created_data_type = transaction
-            .new_data_type(DataType {
-                name,
-                system_defined: false,
-                definition: defintion,
-                version: Some(1),
-                change_date: None,
+        let (old_start_day, old_end_day) = match transaction.find_event_by_noun_id(noun_id).await? {
+            Some(old_event) => (
+                old_event.start_day.to_string(),
+                optional_id_to_string(old_event.end_day),
+            ),
+            None => (String::new(), String::new()),
+        };
+        let event = transaction
+            .set_event(Event {
+                noun_id,
+                last_changed: None,
+                start_day,
+                end_day,
+                participants: None,
             })
             .await?;
+        let event_history = EventHistory {
+            noun_id,
+            change_set_id: None,
+            change_date: None,
+            diff_start_day: create_patch(&old_start_day, &event.start_day.to_string()).to_string(),
+            diff_end_day: create_patch(&old_end_day, &optional_id_to_string(event.end_day))
+                .to_string(),
+        };
+        transaction.new_event_history(event_history).await?;
         transaction.commit().await?;
-        Ok(created_data_type)
+        Ok(event)
     }
 
-    pub async fn get_all_data_types(&mut self) -> anyhow::Result<Vec<DataType>> {
+    pub async fn add_event_participant(
+        &mut self,
+        event_noun_id: i64,
+        noun_id: i64,
+        role: EventRole,
+    ) -> anyhow::Result<EventParticipant> {
         let transaction = self
             .data_interface
             .create_transaction("CLWM".to_owned())
             .await?;
-        Ok(transaction.find_data_type_all_by_all().await?)
+        if transaction
+            .find_event_by_noun_id(event_noun_id)
+            .await?
+            .is_none()
+        {
+            anyhow::bail!(ClwmError::EventNotFound);
+        }
+        if transaction.find_noun_by_id(noun_id).await?.is_none() {
+            anyhow::bail!(ClwmError::NounNotFound);
+        }
+        let participant = transaction
+            .new_event_participant(event_noun_id, EventParticipant { noun_id, role })
+            .await?;
+        transaction.commit().await?;
+        Ok(participant)
     }
 
-    pub async fn get_latest_data_type_by_name(
+    pub async fn remove_event_participant(
         &mut self,
-        name: String,
-    ) -> anyhow::Result<Option<DataType>> {
+        event_noun_id: i64,
+        noun_id: i64,
+        role: EventRole,
+    ) -> anyhow::Result<()> {
         let transaction = self
             .data_interface
             .create_transaction("CLWM".to_owned())
             .await?;
-        Ok(transaction.find_data_type_latest_by_name(name).await?)
+        if !transaction
+            .find_event_participant_by_event_noun_id(event_noun_id)
+            .await?
+            .iter()
+            .any(|x| x.noun_id == noun_id && x.role == role)
+        {
+            anyhow::bail!(ClwmError::EventParticipantNotFound);
+        }
+        transaction
+            .delete_event_participant(event_noun_id, EventParticipant { noun_id, role })
+            .await?;
+        transaction.commit().await?;
+        Ok(())
     }
 
-    pub async fn get_all_data_type_by_name(
-        &mut self,
-        name: String,
-    ) -> anyhow::Result<Vec<DataType>> {
+    pub async fn get_event(&mut self, noun_id: i64) -> anyhow::Result<Option<Event>> {
         let transaction = self
             .data_interface
             .create_transaction("CLWM".to_owned())
             .await?;
-        Ok(transaction.find_data_type_all_by_name(name).await?)
+        if !Self::is_noun_visible(&self.viewer, noun_id, &transaction).await? {
+            return Ok(None);
+        }
+        match transaction.find_event_by_noun_id(noun_id).await? {
+            Some(mut event) => {
+                event.participants = Some(
+                    Self::visible_participants(
+                        &self.viewer,
+                        transaction
+                            .find_event_participant_by_event_noun_id(noun_id)
+                            .await?,
+                        &transaction,
+                    )
+                    .await?,
+                );
+                Ok(Some(event))
+            }
+            None => Ok(None),
+        }
     }
 
-    pub async fn update_data_type(&mut self, data_type: DataType) -> anyhow::Result<DataType> {
+    pub async fn timeline(&mut self, filter: TimelineFilter) -> anyhow::Result<Vec<TimelineEntry>> {
         let transaction = self
             .data_interface
             .create_transaction("CLWM".to_owned())
             .await?;
-
-        let possible_data_type = transaction
-            .find_data_type_latest_by_name(data_type.name.clone())
-            .await?;
-        if possible_data_type.is_none() {
-            anyhow::bail!(ClwmError::DataTypeNotFound)
-        } else {
-            let old_data_type = possible_data_type.unwrap();
-            if old_data_type.name != data_type.name {
-                anyhow::bail!(ClwmError::DataTypeNotFound)
-            }
-            let created_data_type = DataType {
-                name: data_type.name.clone(),
-                system_defined: false,
-                definition: data_type.definition.clone(),
-                version: Some(old_data_type.version.unwrap() + 1),
-                change_date: None,
+        let mut timeline: Vec<TimelineEntry> = Vec::new();
+        for mut event in transaction.find_event_by_timeline_filter(filter).await? {
+            let noun = match transaction.find_noun_by_id(event.noun_id).await? {
+                Some(noun) => noun,
+                None => anyhow::bail!(ClwmError::NounNotFound),
             };
-            let new_data_type = transaction.new_data_type(created_data_type).await?;
-            transaction.commit().await?;
-            Ok(new_data_type)
+            if !self.viewer.can_see(&noun.visibility) {
+                continue;
+            }
+            event.participants = Some(
+                Self::visible_participants(
+                    &self.viewer,
+                    transaction
+                        .find_event_participant_by_event_noun_id(event.noun_id)
+                        .await?,
+                    &transaction,
+                )
+                .await?,
+            );
+            timeline.push(TimelineEntry { noun, event });
         }
+        Ok(timeline)
     }
 
-    pub async fn new_attribute_type(
+    pub async fn set_calendar(
         &mut self,
-        attribute_name: String,
-        multiple_allowed: bool,
-        data_type_name: String,
-        metadata: String,
-    ) -> anyhow::Result<AttributeType> {
+        calendar_name: String,
+        definition: CalendarDefinition,
+    ) -> anyhow::Result<Calendar> {
+        check_calendar(&definition)?;
         let transaction = self
             .data_interface
             .create_transaction("CLWM".to_owned())
             .await?;
-        let found_attribute_type = transaction
-            .find_attribute_type_by_name(attribute_name.clone())
-            .await?;
-        if found_attribute_type
-            .iter()
-            .any(|at| at.attribute_name == attribute_name)
-        {
-            anyhow::bail!(ClwmError::AttributeTypeAlreadyExists {
-                attribute_type: attribute_name
-            })
-        }
-
-        let found_data_type = transaction
-            .find_data_type_latest_by_name(data_type_name.clone())
-            .await?;
-        if found_data_type.is_none() {
-            anyhow::bail!(ClwmError::DataTypeNotFound)
-        }
-
-        let created_attribute_type = transaction
-            .new_attribute_type(AttributeType {
-                attribute_type_id: None,
-                attribute_name,
-                multiple_allowed,
-                data_type: data_type_name,
-                metadata,
+        let calendar = transaction
+            .set_calendar(Calendar {
+                calendar_name,
                 last_changed: None,
+                definition,
             })
             .await?;
+        transaction.commit().await?;
+        Ok(calendar)
+    }
 
-        let attribute_type_history = AttributeTypeHistory {
-            attribute_type_id: created_attribute_type.attribute_type_id.unwrap(),
-            change_date: None,
-            diff_attribute_name: create_patch("", &created_attribute_type.attribute_name)
-                .to_string(),
-            diff_metadata: create_patch("", &created_attribute_type.metadata).to_string(),
-            diff_multiple_allowed: create_patch(
-                "",
-                &created_attribute_type.multiple_allowed.to_string(),
-            )
-            .to_string(),
-        };
-        transaction
-            .new_attribute_type_history(attribute_type_history)
+    pub async fn remove_calendar(&mut self, calendar_name: String) -> anyhow::Result<()> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
             .await?;
-
+        if transaction
+            .find_calendar_by_name(calendar_name.clone())
+            .await?
+            .is_none()
+        {
+            anyhow::bail!(ClwmError::CalendarNotFound)
+        }
+        transaction.delete_calendar(calendar_name).await?;
         transaction.commit().await?;
+        Ok(())
+    }
 
-        Ok(created_attribute_type)
+    pub async fn get_all_calendars(&mut self) -> anyhow::Result<Vec<Calendar>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction.find_calendar_by_all().await?)
     }
 
-    pub async fn update_attribute_type(
+    pub async fn get_calendar(
         &mut self,
-        attribute_type: AttributeType,
-    ) -> anyhow::Result<AttributeType> {
+        calendar_name: Option<String>,
+    ) -> anyhow::Result<Option<Calendar>> {
         let transaction = self
             .data_interface
             .create_transaction("CLWM".to_owned())
             .await?;
-        if attribute_type.attribute_type_id.is_none() {
-            anyhow::bail!(ClwmError::AttributeTypeHasNoId)
+        match calendar_name {
+            Some(calendar_name) => Ok(transaction.find_calendar_by_name(calendar_name).await?),
+            None => {
+                let mut calendars = transaction.find_calendar_by_all().await?;
+                if calendars.len() == 1 {
+                    Ok(calendars.pop())
+                } else {
+                    Ok(None)
+                }
+            }
         }
-        let possible_attribute_type = transaction
-            .find_attribute_type_by_id(attribute_type.attribute_type_id.unwrap())
+    }
+
+    pub async fn parse_world_date(
+        &mut self,
+        calendar_name: Option<String>,
+        date: &str,
+    ) -> anyhow::Result<i64> {
+        if let std::result::Result::Ok(day) = date.trim().parse::<i64>() {
+            return Ok(day);
+        }
+        match self.get_calendar(calendar_name).await? {
+            Some(calendar) => parse_date(&calendar.definition, date),
+            None => anyhow::bail!(ClwmError::CalendarNotFound),
+        }
+    }
+
+    pub async fn format_world_date(
+        &mut self,
+        calendar_name: Option<String>,
+        day: i64,
+    ) -> anyhow::Result<String> {
+        match self.get_calendar(calendar_name).await? {
+            Some(calendar) => Ok(format_date(&calendar.definition, day)),
+            None => Ok(day.to_string()),
+        }
+    }
+
+    pub async fn set_roll_table(&mut self, roll_table: RollTable) -> anyhow::Result<RollTable> {
+        check_roll_table(&roll_table)?;
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
             .await?;
-        let old_attribute_type = if possible_attribute_type.is_none() {
-            anyhow::bail!(ClwmError::AttributeTypeNotFound)
-        } else {
-            possible_attribute_type.unwrap()
-        };
+        let saved_roll_table = transaction.set_roll_table(roll_table).await?;
+        transaction.commit().await?;
+        Ok(saved_roll_table)
+    }
 
-        let new_attribute_type = transaction.new_attribute_type(attribute_type).await?;
-        let attribute_type_history = AttributeTypeHistory {
-            attribute_type_id: new_attribute_type.attribute_type_id.unwrap(),
-            change_date: None,
-            diff_attribute_name: create_patch(
-                &old_attribute_type.attribute_name,
-                &new_attribute_type.attribute_name,
-            )
-            .to_string(),
-            diff_metadata: create_patch(&old_attribute_type.metadata, &new_attribute_type.metadata)
-                .to_string(),
-            diff_multiple_allowed: create_patch(
-                &old_attribute_type.multiple_allowed.to_string(),
-                &new_attribute_type.multiple_allowed.to_string(),
-            )
-            .to_string(),
-        };
-        transaction
-            .new_attribute_type_history(attribute_type_history)
+    pub async fn remove_roll_table(&mut self, roll_table_name: String) -> anyhow::Result<()> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
             .await?;
+        if transaction
+            .find_roll_table_by_name(roll_table_name.clone())
+            .await?
+            .is_none()
+        {
+            anyhow::bail!(ClwmError::RollTableNotFound)
+        }
+        transaction.delete_roll_table(roll_table_name).await?;
         transaction.commit().await?;
-        Ok(new_attribute_type)
+        Ok(())
     }
 
-    pub async fn get_attribute_type_by_id(
+    pub async fn get_roll_table_by_name(
         &mut self,
-        attribute_type_id: i64,
-    ) -> anyhow::Result<Option<AttributeType>> {
+        roll_table_name: String,
+    ) -> anyhow::Result<Option<RollTable>> {
         let transaction = self
             .data_interface
             .create_transaction("CLWM".to_owned())
             .await?;
-        Ok(transaction
-            .find_attribute_type_by_id(attribute_type_id)
-            .await?)
+        Ok(transaction.find_roll_table_by_name(roll_table_name).await?)
     }
 
-    pub async fn get_all_attribute_types(&mut self) -> anyhow::Result<Vec<AttributeType>> {
+    pub async fn get_all_roll_tables(&mut self) -> anyhow::Result<Vec<RollTable>> {
         let transaction = self
             .data_interface
             .create_transaction("CLWM".to_owned())
             .await?;
-        Ok(transaction.find_attribute_type_by_all().await?)
+        Ok(transaction.find_roll_table_by_all().await?)
     }
 
-    pub async fn new_attribute(
+    pub async fn roll(
         &mut self,
-        attribute_type_id: i64,
-        parent_noun_id: Option<i64>,
-        parent_attribute_id: Option<i64>,
-        data: DataObject,
-        data_type_version: i64,
-        metadata: String,
-    ) -> anyhow::Result<Attribute> {
+        roll_table_name: String,
+        generator: &mut RollGenerator,
+    ) -> anyhow::Result<RollResult> {
         let transaction = self
             .data_interface
             .create_transaction("CLWM".to_owned())
             .await?;
-        let found_attribute_type = transaction
-            .find_attribute_type_by_id(attribute_type_id)
+        Self::roll_on_table(&roll_table_name, generator, 0, &self.viewer, &transaction).await
+    }
+
+    pub async fn roll_noun(
+        &mut self,
+        filter: RollNounFilter,
+        generator: &mut RollGenerator,
+    ) -> anyhow::Result<Noun> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
             .await?;
-        if found_attribute_type.is_none() {
-            anyhow::bail!(ClwmError::AttributeTypeNotFound)
-        }
+        Self::pick_noun(&filter, generator, &self.viewer, &transaction).await
+    }
 
-        if parent_noun_id.is_none() && parent_attribute_id.is_none() {
-            anyhow::bail!(ClwmError::ParentNounOrParentAttributeIdMustBeSet)
+    #[async_recursion(?Send)]
+    async fn roll_on_table(
+        roll_table_name: &str,
+        generator: &mut RollGenerator,
+        depth: usize,
+        viewer: &Viewer,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<RollResult> {
+        if depth >= MAX_ROLL_DEPTH {
+            anyhow::bail!(ClwmError::RollTableTooDeep {
+                max_depth: MAX_ROLL_DEPTH
+            })
         }
+        let roll_table = match transaction
+            .find_roll_table_by_name(roll_table_name.to_owned())
+            .await?
+        {
+            Some(roll_table) => roll_table,
+            None => anyhow::bail!(ClwmError::RollTableNotFound),
+        };
+        let entry = match generator.pick_entry(&roll_table.entries) {
+            Some(entry) => entry,
+            None => anyhow::bail!(ClwmError::InvalidRollTable {
+                message: "the roll table has no entries".to_owned()
+            }),
+        };
 
-        if parent_noun_id.is_some() && parent_attribute_id.is_some() {
-            anyhow::bail!(ClwmError::ParentNounAndParentAttributeIdMustNotBeSet)
+        if let Some(table) = &entry.table {
+            return Self::roll_on_table(table, generator, depth + 1, viewer, transaction).await;
+        }
+        if let Some(filter) = &entry.nouns {
+            let noun = Self::pick_noun(filter, generator, viewer, transaction).await?;
+            return Ok(RollResult {
+                text: noun.name,
+                noun_id: noun.noun_id,
+            });
         }
 
-        if parent_noun_id.is_some() {
-            let found_noun = transaction.find_noun_by_id(parent_noun_id.unwrap()).await?;
-            if found_noun.is_none() {
-                anyhow::bail!(ClwmError::NounNotFound)
-            }
-
-            if found_attribute_type.as_ref().unwrap().multiple_allowed == false {
-                let found_attribute = transaction
-                    .find_attribute_by_parent_noun_id_and_attribute_type_id(
-                        parent_noun_id.unwrap(),
-                        attribute_type_id,
-                    )
-                    .await?;
-                if found_attribute.len() > 0 {
-                    anyhow::bail!(ClwmError::AttributeTypeDoesNotAllowMultipleAttributes {
-                        attribute_type: found_attribute_type.unwrap().attribute_name
-                    })
+        let mut text = String::new();
+        for part in parse_roll_text(entry.text.as_deref().unwrap_or_default())? {
+            match part {
+                RollTextPart::Literal(literal) => text.push_str(&literal),
+                RollTextPart::Table(table) => {
+                    let nested_result =
+                        Self::roll_on_table(&table, generator, depth + 1, viewer, transaction)
+                            .await?;
+                    text.push_str(&nested_result.text);
+                }
+                RollTextPart::Dice(dice) => {
+                    text.push_str(&generator.roll_dice(&dice).total.to_string())
                 }
             }
         }
+        Ok(RollResult {
+            text,
+            noun_id: None,
+        })
+    }
 
-        if parent_attribute_id.is_some() {
-            let found_attribute = transaction
-                .find_attribute_by_id(parent_attribute_id.unwrap())
-                .await?;
-            if found_attribute.is_none() {
-                anyhow::bail!(ClwmError::AttributeNotFound)
+    async fn pick_noun(
+        filter: &RollNounFilter,
+        generator: &mut RollGenerator,
+        viewer: &Viewer,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<Noun> {
+        let mut nouns = match &filter.noun_type {
+            Some(noun_type) if filter.include_subtypes => {
+                transaction
+                    .find_noun_by_noun_type_including_subtypes(noun_type.clone())
+                    .await?
             }
-
-            if found_attribute_type.as_ref().unwrap().multiple_allowed == false {
-                let found_attribute = transaction
-                    .find_attribute_by_parent_attribute_id_and_attribute_type_id(
-                        parent_attribute_id.unwrap(),
-                        attribute_type_id,
-                    )
-                    .await?;
-                if found_attribute.len() > 0 {
-                    anyhow::bail!(ClwmError::AttributeTypeDoesNotAllowMultipleAttributes {
-                        attribute_type: found_attribute_type.unwrap().attribute_name
-                    })
-                }
+            Some(noun_type) => {
+                transaction
+                    .find_noun_by_noun_type(noun_type.clone())
+                    .await?
             }
+            None => transaction.find_noun_by_all().await?,
+        };
+        if let Some(attribute_type_id) = filter.attribute_type_id {
+            let noun_ids: Vec<i64> = transaction
+                .find_attribute_by_attribute_type_id(attribute_type_id)
+                .await?
+                .iter()
+                .filter_map(|attribute| attribute.parent_noun_id)
+                .collect();
+            nouns.retain(|noun| noun_ids.contains(&noun.noun_id.unwrap()));
         }
-
-        let found_data_type = transaction
-            .find_data_type_all_by_name(found_attribute_type.unwrap().data_type)
-            .await?;
-
-        let found_data_type_version = found_data_type
-            .iter()
-            .find(|&x| x.version == Some(data_type_version));
-        if found_data_type_version.is_none() {
-            anyhow::bail!(ClwmError::DataTypeVersionNotFound)
+        nouns.retain(|noun| viewer.can_see(&noun.visibility));
+        nouns.sort_by_key(|noun| noun.noun_id);
+        match generator.choose(&nouns) {
+            Some(noun) => Ok(noun.clone()),
+            None => anyhow::bail!(ClwmError::RollNoMatchingNouns),
         }
+    }
 
-        if !is_data_of_data_def(&data, &found_data_type_version.unwrap().definition, true) {
-            anyhow::bail!(ClwmError::DataDoesNotMatchDataTypeDefinition)
+    pub async fn update_noun_type(&mut self, noun_type: NounType) -> anyhow::Result<NounType> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        if noun_type.noun_type_id.is_none() {
+            anyhow::bail!(ClwmError::NounTypeHasNoId);
         }
 
-        let created_attribute = transaction
-            .new_attribute(Attribute {
-                attribute_id: None,
-                attribute_type_id,
-                parent_noun_id,
-                parent_attribute_id,
-                data,
-                data_type_version,
-                metadata,
-                last_changed: None,
-                children: None,
-            })
+        let possible_old_noun_type = transaction
+            .find_noun_type_by_id(noun_type.noun_type_id.unwrap())
             .await?;
 
-        let toml_data = toml::to_string(&created_attribute.data)?;
+        let old_noun_type = if possible_old_noun_type.is_none() {
+            anyhow::bail!(ClwmError::NounTypeNotFound);
+        } else {
+            possible_old_noun_type.unwrap()
+        };
 
-        let attribute_history = AttributeHistory {
-            attribute_id: created_attribute.attribute_id.unwrap(),
-            diff_data: create_patch("", &toml_data).to_string(),
+        if let Some(parent_noun_type_id) = noun_type.parent_noun_type_id {
+            if transaction
+                .find_noun_type_by_id(parent_noun_type_id)
+                .await?
+                .is_none()
+            {
+                anyhow::bail!(ClwmError::NounTypeNotFound);
+            }
+            let ancestry = Self::find_noun_type_ancestry(parent_noun_type_id, &transaction).await?;
+            if ancestry
+                .iter()
+                .any(|ancestor| ancestor.noun_type_id == noun_type.noun_type_id)
+            {
+                anyhow::bail!(ClwmError::NounTypeInheritanceCycle {
+                    noun_type: noun_type.noun_type
+                });
+            }
+        }
+
+        let new_noun_type = transaction.update_noun_type(noun_type).await?;
+
+        let noun_type_history = NounTypeHistory {
+            noun_type_id: new_noun_type.noun_type_id.unwrap(),
+            change_set_id: None,
+            change_date: None,
+            diff_noun_type: create_patch(&old_noun_type.noun_type, &new_noun_type.noun_type)
+                .to_string(),
+            diff_parent_noun_type_id: create_patch(
+                &optional_id_to_string(old_noun_type.parent_noun_type_id),
+                &optional_id_to_string(new_noun_type.parent_noun_type_id),
+            )
+            .to_string(),
+            diff_metadata: create_patch(&old_noun_type.metadata, &new_noun_type.metadata)
+                .to_string(),
+        };
+        transaction.new_noun_type_history(noun_type_history).await?;
+        transaction.commit().await?;
+        Ok(new_noun_type)
+    }
+
+    pub async fn set_noun_type_attribute_rule(
+        &mut self,
+        noun_type: String,
+        attribute_type_id: i64,
+        requirement: AttributeRequirement,
+        min_count: Option<i64>,
+        max_count: Option<i64>,
+        default_data: Option<DataObject>,
+    ) -> anyhow::Result<NounTypeAttributeRule> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let noun_type_id = Self::find_noun_type_id(noun_type, &transaction).await?;
+        let attribute_type = match transaction
+            .find_attribute_type_by_id(attribute_type_id)
+            .await?
+        {
+            Some(attribute_type) => attribute_type,
+            None => anyhow::bail!(ClwmError::AttributeTypeNotFound),
+        };
+
+        if let Some(default_data) = &default_data {
+            if requirement == AttributeRequirement::Forbidden || attribute_type.formula.is_some() {
+                anyhow::bail!(ClwmError::AttributeTypeCannotHaveDefault {
+                    attribute_type: attribute_type.attribute_name
+                })
+            }
+            match transaction
+                .find_data_type_latest_by_name(attribute_type.data_type.clone())
+                .await?
+            {
+                Some(data_type) => {
+                    if !is_data_of_data_def(default_data, &data_type.definition, true) {
+                        anyhow::bail!(ClwmError::DataDoesNotMatchDataTypeDefinition)
+                    }
+                    Self::check_asset_references(default_data, &transaction).await?;
+                }
+                None => anyhow::bail!(ClwmError::DataTypeNotFound),
+            }
+        }
+
+        let (min_count, max_count) = match requirement {
+            AttributeRequirement::Required => (min_count.unwrap_or(1), max_count),
+            AttributeRequirement::Optional => (min_count.unwrap_or(0), max_count),
+            AttributeRequirement::Forbidden => (0, Some(0)),
+        };
+        if (requirement == AttributeRequirement::Required && min_count < 1)
+            || (requirement == AttributeRequirement::Optional && min_count != 0)
+            || max_count.is_some_and(|max_count| max_count < min_count)
+        {
+            anyhow::bail!(ClwmError::InvalidAttributeCardinality)
+        }
+
+        let rule = transaction
+            .set_noun_type_attribute_rule(NounTypeAttributeRule {
+                noun_type_id,
+                attribute_type_id,
+                last_changed: None,
+                requirement,
+                min_count,
+                max_count,
+                default_data,
+            })
+            .await?;
+        transaction.commit().await?;
+        Ok(rule)
+    }
+
+    pub async fn remove_noun_type_attribute_rule(
+        &mut self,
+        noun_type: String,
+        attribute_type_id: i64,
+    ) -> anyhow::Result<()> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let noun_type_id = Self::find_noun_type_id(noun_type, &transaction).await?;
+        transaction
+            .delete_noun_type_attribute_rule(noun_type_id, attribute_type_id)
+            .await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    pub async fn get_noun_type_attribute_rules(
+        &mut self,
+        noun_type: String,
+    ) -> anyhow::Result<Vec<NounTypeAttributeRule>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Self::find_noun_type_rules(noun_type, &transaction).await
+    }
+
+    pub async fn validate_noun(
+        &mut self,
+        noun_id: i64,
+    ) -> anyhow::Result<Vec<NounValidationIssue>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let noun = match Self::find_visible_noun_by_id(&self.viewer, noun_id, &transaction).await? {
+            Some(noun) => noun,
+            None => anyhow::bail!(ClwmError::NounNotFound),
+        };
+        let rules = Self::find_noun_type_rules(noun.noun_type, &transaction).await?;
+        let attributes = transaction
+            .find_attribute_by_parent_noun_id(noun_id)
+            .await?;
+
+        let mut issues = Vec::new();
+        for rule in rules.iter() {
+            let attribute_type = match transaction
+                .find_attribute_type_by_id(rule.attribute_type_id)
+                .await?
+            {
+                Some(attribute_type) => attribute_type.attribute_name,
+                None => anyhow::bail!(ClwmError::AttributeTypeNotFound),
+            };
+            let found = attributes
+                .iter()
+                .filter(|attribute| attribute.attribute_type_id == rule.attribute_type_id)
+                .count() as i64;
+
+            if rule.requirement == AttributeRequirement::Forbidden {
+                if found > 0 {
+                    issues.push(NounValidationIssue::ForbiddenAttribute {
+                        attribute_type,
+                        found,
+                    });
+                }
+                continue;
+            }
+            if found < rule.min_count {
+                issues.push(NounValidationIssue::MissingRequiredAttribute {
+                    attribute_type: attribute_type.clone(),
+                    min_count: rule.min_count,
+                    found,
+                });
+            }
+            if let Some(max_count) = rule.max_count {
+                if found > max_count {
+                    issues.push(NounValidationIssue::TooManyAttributes {
+                        attribute_type,
+                        max_count,
+                        found,
+                    });
+                }
+            }
+        }
+        Ok(issues)
+    }
+
+    /// Creates an attribute holding data of the given data type version, or of
+    /// the latest version of its attribute type's data type when none is given.
+    async fn create_attribute(
+        attribute_type_id: i64,
+        parent_noun_id: Option<i64>,
+        parent_attribute_id: Option<i64>,
+        data: DataObject,
+        data_type_version: Option<i64>,
+        metadata: String,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<Attribute> {
+        let data_type_version = match data_type_version {
+            Some(data_type_version) => data_type_version,
+            None => {
+                let attribute_type = match transaction
+                    .find_attribute_type_by_id(attribute_type_id)
+                    .await?
+                {
+                    Some(attribute_type) => attribute_type,
+                    None => anyhow::bail!(ClwmError::AttributeTypeNotFound),
+                };
+                match transaction
+                    .find_data_type_latest_by_name(attribute_type.data_type)
+                    .await?
+                {
+                    Some(data_type) => data_type.version.unwrap(),
+                    None => anyhow::bail!(ClwmError::DataTypeNotFound),
+                }
+            }
+        };
+        Self::check_new_attribute(
+            attribute_type_id,
+            parent_noun_id,
+            parent_attribute_id,
+            &data,
+            data_type_version,
+            transaction,
+        )
+        .await?;
+
+        let created_attribute = transaction
+            .new_attribute(Attribute {
+                attribute_id: None,
+                attribute_type_id,
+                parent_noun_id,
+                parent_attribute_id,
+                data,
+                data_type_version,
+                metadata,
+                last_changed: None,
+                visibility: Visibility::Public,
+                children: None,
+            })
+            .await?;
+
+        let toml_data = history_data(&created_attribute.data)?;
+        let attribute_history = AttributeHistory {
+            attribute_id: created_attribute.attribute_id.unwrap(),
+            change_set_id: None,
+            diff_data: create_patch("", &toml_data).to_string(),
             diff_data_type_version: create_patch(
                 "",
                 created_attribute.data_type_version.to_string().as_str(),
             )
             .to_string(),
             diff_metadata: create_patch("", &created_attribute.metadata).to_string(),
+            diff_visibility: create_patch("", &created_attribute.visibility.to_string())
+                .to_string(),
+            change_date: None,
+        };
+        transaction.new_attribute_history(attribute_history).await?;
+        Self::index_noun_references(&created_attribute, transaction).await?;
+        Ok(created_attribute)
+    }
+
+    pub async fn new_relationship_type(
+        &mut self,
+        relationship_name: String,
+        inverse_name: String,
+        cardinality: RelationshipCardinality,
+        metadata: String,
+    ) -> anyhow::Result<RelationshipType> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        for name in [&relationship_name, &inverse_name] {
+            if !transaction
+                .find_relationship_type_by_name(name.clone())
+                .await?
+                .is_empty()
+            {
+                anyhow::bail!(ClwmError::RelationshipTypeNameInUse { name: name.clone() })
+            }
+        }
+        let created_relationship_type = transaction
+            .new_relationship_type(RelationshipType {
+                relationship_type_id: None,
+                last_changed: None,
+                relationship_name,
+                inverse_name,
+                cardinality,
+                metadata,
+            })
+            .await?;
+        transaction.commit().await?;
+        Ok(created_relationship_type)
+    }
+
+    pub async fn get_all_relationship_types(&mut self) -> anyhow::Result<Vec<RelationshipType>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction.find_relationship_type_by_all().await?)
+    }
+
+    pub async fn link_nouns(
+        &mut self,
+        source_noun_id: i64,
+        relationship: String,
+        target_noun_id: i64,
+        metadata: String,
+    ) -> anyhow::Result<Relationship> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let (relationship_type, source_noun_id, target_noun_id) =
+            Self::resolve_relationship(relationship, source_noun_id, target_noun_id, &transaction)
+                .await?;
+        for noun_id in [source_noun_id, target_noun_id] {
+            if transaction.find_noun_by_id(noun_id).await?.is_none() {
+                anyhow::bail!(ClwmError::NounNotFound)
+            }
+        }
+
+        let source_relationships = transaction
+            .find_relationship_by_noun_id(source_noun_id)
+            .await?;
+        let target_relationships = transaction
+            .find_relationship_by_noun_id(target_noun_id)
+            .await?;
+        let of_type =
+            |x: &&Relationship| x.relationship_type == relationship_type.relationship_name;
+        let symmetric = relationship_type.relationship_name == relationship_type.inverse_name;
+        if source_relationships.iter().filter(of_type).any(|x| {
+            (x.source_noun_id == source_noun_id && x.target_noun_id == target_noun_id)
+                || (symmetric
+                    && x.source_noun_id == target_noun_id
+                    && x.target_noun_id == source_noun_id)
+        }) {
+            anyhow::bail!(ClwmError::RelationshipAlreadyExists)
+        }
+        let single_target = matches!(
+            relationship_type.cardinality,
+            RelationshipCardinality::OneToOne | RelationshipCardinality::ManyToOne
+        );
+        if single_target
+            && source_relationships.iter().filter(of_type).any(|x| {
+                x.source_noun_id == source_noun_id
+                    || (symmetric && x.target_noun_id == source_noun_id)
+            })
+        {
+            anyhow::bail!(ClwmError::RelationshipCardinalityExceeded {
+                relationship_type: relationship_type.relationship_name,
+                noun_id: source_noun_id
+            })
+        }
+        let single_source = matches!(
+            relationship_type.cardinality,
+            RelationshipCardinality::OneToOne | RelationshipCardinality::OneToMany
+        );
+        if single_source
+            && target_relationships.iter().filter(of_type).any(|x| {
+                x.target_noun_id == target_noun_id
+                    || (symmetric && x.source_noun_id == target_noun_id)
+            })
+        {
+            anyhow::bail!(ClwmError::RelationshipCardinalityExceeded {
+                relationship_type: relationship_type.inverse_name,
+                noun_id: target_noun_id
+            })
+        }
+
+        let created_relationship = transaction
+            .new_relationship(Relationship {
+                relationship_id: None,
+                last_changed: None,
+                relationship_type: relationship_type.relationship_name,
+                source_noun_id,
+                target_noun_id,
+                metadata,
+            })
+            .await?;
+        let relationship_history = RelationshipHistory {
+            relationship_id: created_relationship.relationship_id.unwrap(),
+            change_set_id: None,
+            change_date: None,
+            diff_relationship_type: create_patch("", &created_relationship.relationship_type)
+                .to_string(),
+            diff_source_noun_id: create_patch("", &created_relationship.source_noun_id.to_string())
+                .to_string(),
+            diff_target_noun_id: create_patch("", &created_relationship.target_noun_id.to_string())
+                .to_string(),
+            diff_metadata: create_patch("", &created_relationship.metadata).to_string(),
+        };
+        transaction
+            .new_relationship_history(relationship_history)
+            .await?;
+        transaction.commit().await?;
+        Ok(created_relationship)
+    }
+
+    pub async fn unlink_nouns(
+        &mut self,
+        source_noun_id: i64,
+        relationship: String,
+        target_noun_id: i64,
+    ) -> anyhow::Result<Relationship> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let (relationship_type, source_noun_id, target_noun_id) =
+            Self::resolve_relationship(relationship, source_noun_id, target_noun_id, &transaction)
+                .await?;
+        let old_relationship = match transaction
+            .find_relationship_by_noun_id(source_noun_id)
+            .await?
+            .into_iter()
+            .find(|x| {
+                x.relationship_type == relationship_type.relationship_name
+                    && ((x.source_noun_id == source_noun_id && x.target_noun_id == target_noun_id)
+                        || (relationship_type.relationship_name == relationship_type.inverse_name
+                            && x.source_noun_id == target_noun_id
+                            && x.target_noun_id == source_noun_id))
+            }) {
+            Some(old_relationship) => old_relationship,
+            None => anyhow::bail!(ClwmError::RelationshipNotFound),
+        };
+
+        transaction
+            .delete_relationship(old_relationship.relationship_id.unwrap())
+            .await?;
+        let relationship_history = RelationshipHistory {
+            relationship_id: old_relationship.relationship_id.unwrap(),
+            change_set_id: None,
             change_date: None,
+            diff_relationship_type: create_patch(&old_relationship.relationship_type, "")
+                .to_string(),
+            diff_source_noun_id: create_patch(&old_relationship.source_noun_id.to_string(), "")
+                .to_string(),
+            diff_target_noun_id: create_patch(&old_relationship.target_noun_id.to_string(), "")
+                .to_string(),
+            diff_metadata: create_patch(&old_relationship.metadata, "").to_string(),
+        };
+        transaction
+            .new_relationship_history(relationship_history)
+            .await?;
+        transaction.commit().await?;
+        Ok(old_relationship)
+    }
+
+    pub async fn get_noun_relations(&mut self, noun_id: i64) -> anyhow::Result<Vec<NounRelation>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        if !Self::is_noun_visible(&self.viewer, noun_id, &transaction).await? {
+            anyhow::bail!(ClwmError::NounNotFound)
+        }
+        let relationship_types = transaction.find_relationship_type_by_all().await?;
+        let mut relations: Vec<NounRelation> = Vec::new();
+        for relationship in transaction.find_relationship_by_noun_id(noun_id).await? {
+            let relationship_type = match relationship_types
+                .iter()
+                .find(|x| x.relationship_name == relationship.relationship_type)
+            {
+                Some(relationship_type) => relationship_type,
+                None => anyhow::bail!(ClwmError::RelationshipTypeNotFound),
+            };
+            if relationship.source_noun_id == noun_id {
+                relations.push(NounRelation {
+                    relation_name: relationship_type.relationship_name.clone(),
+                    direction: RelationDirection::Outgoing,
+                    other_noun_id: relationship.target_noun_id,
+                    relationship: relationship.clone(),
+                });
+            }
+            if relationship.target_noun_id == noun_id {
+                relations.push(NounRelation {
+                    relation_name: relationship_type.inverse_name.clone(),
+                    direction: RelationDirection::Incoming,
+                    other_noun_id: relationship.source_noun_id,
+                    relationship: relationship.clone(),
+                });
+            }
+        }
+        let mut visible_relations = Vec::new();
+        for relation in relations {
+            if Self::is_noun_visible(&self.viewer, relation.other_noun_id, &transaction).await? {
+                visible_relations.push(relation);
+            }
+        }
+        Ok(visible_relations)
+    }
+
+    async fn resolve_relationship(
+        relationship: String,
+        source_noun_id: i64,
+        target_noun_id: i64,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<(RelationshipType, i64, i64)> {
+        let relationship_types = transaction
+            .find_relationship_type_by_name(relationship.clone())
+            .await?;
+        if let Some(relationship_type) = relationship_types
+            .iter()
+            .find(|x| x.relationship_name == relationship)
+        {
+            // A symmetric relationship reads the same both ways, so its
+            // endpoints are stored in a fixed order.
+            if relationship_type.inverse_name == relationship && target_noun_id < source_noun_id {
+                return Ok((relationship_type.clone(), target_noun_id, source_noun_id));
+            }
+            return Ok((relationship_type.clone(), source_noun_id, target_noun_id));
+        }
+        match relationship_types
+            .into_iter()
+            .find(|x| x.inverse_name == relationship)
+        {
+            Some(relationship_type) => Ok((relationship_type, target_noun_id, source_noun_id)),
+            None => anyhow::bail!(ClwmError::RelationshipTypeNotFound),
+        }
+    }
+
+    async fn find_noun_type_id(
+        noun_type: String,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<i64> {
+        match transaction
+            .find_noun_type_by_noun_type(noun_type.clone())
+            .await?
+            .iter()
+            .find(|noun_type_record| noun_type_record.noun_type == noun_type)
+            .and_then(|noun_type_record| noun_type_record.noun_type_id)
+        {
+            Some(noun_type_id) => Ok(noun_type_id),
+            None => anyhow::bail!(ClwmError::NounTypeNotFound),
+        }
+    }
+
+    async fn find_noun_type_rules(
+        noun_type: String,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<Vec<NounTypeAttributeRule>> {
+        let noun_type_id = Self::find_noun_type_id(noun_type, transaction).await?;
+        let mut rules: Vec<NounTypeAttributeRule> = Vec::new();
+        for ancestor in Self::find_noun_type_ancestry(noun_type_id, transaction).await? {
+            for rule in transaction
+                .find_noun_type_attribute_rule_by_noun_type_id(ancestor.noun_type_id.unwrap())
+                .await?
+            {
+                if !rules
+                    .iter()
+                    .any(|x| x.attribute_type_id == rule.attribute_type_id)
+                {
+                    rules.push(rule);
+                }
+            }
+        }
+        Ok(rules)
+    }
+
+    async fn find_noun_type_ancestry(
+        noun_type_id: i64,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<Vec<NounType>> {
+        let mut ancestry: Vec<NounType> = Vec::new();
+        let mut next_noun_type_id = Some(noun_type_id);
+        while let Some(current_noun_type_id) = next_noun_type_id {
+            if ancestry
+                .iter()
+                .any(|x| x.noun_type_id == Some(current_noun_type_id))
+            {
+                break;
+            }
+            let noun_type = match transaction
+                .find_noun_type_by_id(current_noun_type_id)
+                .await?
+            {
+                Some(noun_type) => noun_type,
+                None => anyhow::bail!(ClwmError::NounTypeNotFound),
+            };
+            next_noun_type_id = noun_type.parent_noun_type_id;
+            ancestry.push(noun_type);
+        }
+        Ok(ancestry)
+    }
+
+    pub async fn find_nouns_by_noun_type(
+        &mut self,
+        noun_type: String,
+        include_subtypes: bool,
+    ) -> anyhow::Result<Vec<Noun>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let nouns = if include_subtypes {
+            transaction
+                .find_noun_by_noun_type_including_subtypes(noun_type)
+                .await?
+        } else {
+            transaction.find_noun_by_noun_type(noun_type).await?
         };
+        Ok(self.visible_nouns(nouns))
+    }
+
+    pub async fn get_noun_by_id(&mut self, id: i64) -> anyhow::Result<Option<Noun>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction
+            .find_noun_by_id(id)
+            .await?
+            .filter(|noun| self.viewer.can_see(&noun.visibility)))
+    }
+
+    pub async fn get_noun_type_by_name(
+        &mut self,
+        noun_type: String,
+    ) -> anyhow::Result<Option<NounType>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction
+            .find_noun_type_by_noun_type(noun_type.clone())
+            .await?
+            .into_iter()
+            .find(|noun_type_record| noun_type_record.noun_type == noun_type))
+    }
+
+    pub async fn get_noun_type_by_id(&mut self, id: i64) -> anyhow::Result<Option<NounType>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction.find_noun_type_by_id(id).await?)
+    }
+
+    pub async fn new_data_type(
+        &mut self,
+        name: String,
+        defintion: DataTypeDefinition,
+    ) -> anyhow::Result<DataType> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+
+        let possible_data_type = transaction
+            .find_data_type_latest_by_name(name.clone())
+            .await?;
+
+        if let Some(data_type) = possible_data_type {
+            if data_type.name == name {
+                anyhow::bail!(ClwmError::DataTypeAlreadyExists { data_type: name })
+            }
+        }
+
+        if !is_valid_data_def(&defintion) {
+            anyhow::bail!(ClwmError::InvalidMapKeyType)
+        }
+
+        let created_data_type = transaction
+            .new_data_type(DataType {
+                name,
+                system_defined: false,
+                definition: defintion,
+                version: Some(1),
+                change_date: None,
+            })
+            .await?;
+        transaction.commit().await?;
+        Ok(created_data_type)
+    }
+
+    pub async fn get_all_data_types(&mut self) -> anyhow::Result<Vec<DataType>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction.find_data_type_all_by_all().await?)
+    }
+
+    pub async fn get_latest_data_type_by_name(
+        &mut self,
+        name: String,
+    ) -> anyhow::Result<Option<DataType>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction.find_data_type_latest_by_name(name).await?)
+    }
+
+    pub async fn get_all_data_type_by_name(
+        &mut self,
+        name: String,
+    ) -> anyhow::Result<Vec<DataType>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction.find_data_type_all_by_name(name).await?)
+    }
+
+    pub async fn update_data_type(&mut self, data_type: DataType) -> anyhow::Result<DataType> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+
+        let possible_data_type = transaction
+            .find_data_type_latest_by_name(data_type.name.clone())
+            .await?;
+        if possible_data_type.is_none() {
+            anyhow::bail!(ClwmError::DataTypeNotFound)
+        } else {
+            let old_data_type = possible_data_type.unwrap();
+            if old_data_type.name != data_type.name {
+                anyhow::bail!(ClwmError::DataTypeNotFound)
+            }
+            if !is_valid_data_def(&data_type.definition) {
+                anyhow::bail!(ClwmError::InvalidMapKeyType)
+            }
+            let created_data_type = DataType {
+                name: data_type.name.clone(),
+                system_defined: false,
+                definition: data_type.definition.clone(),
+                version: Some(old_data_type.version.unwrap() + 1),
+                change_date: None,
+            };
+            let new_data_type = transaction.new_data_type(created_data_type).await?;
+            transaction.commit().await?;
+            Ok(new_data_type)
+        }
+    }
+
+    pub async fn new_attribute_type(
+        &mut self,
+        attribute_name: String,
+        multiple_allowed: bool,
+        data_type_name: String,
+        formula: Option<String>,
+        metadata: String,
+    ) -> anyhow::Result<AttributeType> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let found_attribute_type = transaction
+            .find_attribute_type_by_name(attribute_name.clone())
+            .await?;
+        if found_attribute_type
+            .iter()
+            .any(|at| at.attribute_name == attribute_name)
+        {
+            anyhow::bail!(ClwmError::AttributeTypeAlreadyExists {
+                attribute_type: attribute_name
+            })
+        }
+
+        let found_data_type = transaction
+            .find_data_type_latest_by_name(data_type_name.clone())
+            .await?;
+        if found_data_type.is_none() {
+            anyhow::bail!(ClwmError::DataTypeNotFound)
+        }
+        check_formula(&formula, &found_data_type.unwrap().definition)?;
+
+        let created_attribute_type = Self::create_attribute_type(
+            AttributeType {
+                attribute_type_id: None,
+                attribute_name,
+                multiple_allowed,
+                data_type: data_type_name,
+                formula,
+                metadata,
+                last_changed: None,
+            },
+            &transaction,
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(created_attribute_type)
+    }
+
+    async fn create_attribute_type(
+        attribute_type: AttributeType,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<AttributeType> {
+        let created_attribute_type = transaction.new_attribute_type(attribute_type).await?;
+
+        let attribute_type_history = AttributeTypeHistory {
+            attribute_type_id: created_attribute_type.attribute_type_id.unwrap(),
+            change_set_id: None,
+            change_date: None,
+            diff_attribute_name: create_patch("", &created_attribute_type.attribute_name)
+                .to_string(),
+            diff_metadata: create_patch("", &created_attribute_type.metadata).to_string(),
+            diff_multiple_allowed: create_patch(
+                "",
+                &created_attribute_type.multiple_allowed.to_string(),
+            )
+            .to_string(),
+            diff_formula: create_patch(
+                "",
+                created_attribute_type
+                    .formula
+                    .as_deref()
+                    .unwrap_or_default(),
+            )
+            .to_string(),
+        };
+        transaction
+            .new_attribute_type_history(attribute_type_history)
+            .await?;
+        Ok(created_attribute_type)
+    }
+
+    pub async fn update_attribute_type(
+        &mut self,
+        attribute_type: AttributeType,
+    ) -> anyhow::Result<AttributeType> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        if attribute_type.attribute_type_id.is_none() {
+            anyhow::bail!(ClwmError::AttributeTypeHasNoId)
+        }
+        let possible_attribute_type = transaction
+            .find_attribute_type_by_id(attribute_type.attribute_type_id.unwrap())
+            .await?;
+        let old_attribute_type = if possible_attribute_type.is_none() {
+            anyhow::bail!(ClwmError::AttributeTypeNotFound)
+        } else {
+            possible_attribute_type.unwrap()
+        };
+
+        if attribute_type.formula.is_some() {
+            match transaction
+                .find_data_type_latest_by_name(old_attribute_type.data_type.clone())
+                .await?
+            {
+                Some(data_type) => check_formula(&attribute_type.formula, &data_type.definition)?,
+                None => anyhow::bail!(ClwmError::DataTypeNotFound),
+            }
+        }
+
+        let new_attribute_type = transaction.update_attribute_type(attribute_type).await?;
+        let attribute_type_history = AttributeTypeHistory {
+            attribute_type_id: new_attribute_type.attribute_type_id.unwrap(),
+            change_set_id: None,
+            change_date: None,
+            diff_attribute_name: create_patch(
+                &old_attribute_type.attribute_name,
+                &new_attribute_type.attribute_name,
+            )
+            .to_string(),
+            diff_metadata: create_patch(&old_attribute_type.metadata, &new_attribute_type.metadata)
+                .to_string(),
+            diff_multiple_allowed: create_patch(
+                &old_attribute_type.multiple_allowed.to_string(),
+                &new_attribute_type.multiple_allowed.to_string(),
+            )
+            .to_string(),
+            diff_formula: create_patch(
+                old_attribute_type.formula.as_deref().unwrap_or_default(),
+                new_attribute_type.formula.as_deref().unwrap_or_default(),
+            )
+            .to_string(),
+        };
+        transaction
+            .new_attribute_type_history(attribute_type_history)
+            .await?;
+        transaction.commit().await?;
+        Ok(new_attribute_type)
+    }
+
+    pub async fn get_attribute_type_by_id(
+        &mut self,
+        attribute_type_id: i64,
+    ) -> anyhow::Result<Option<AttributeType>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction
+            .find_attribute_type_by_id(attribute_type_id)
+            .await?)
+    }
+
+    pub async fn get_all_attribute_types(&mut self) -> anyhow::Result<Vec<AttributeType>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction.find_attribute_type_by_all().await?)
+    }
+
+    pub async fn get_attribute_type_by_name(
+        &mut self,
+        attribute_name: String,
+    ) -> anyhow::Result<Option<AttributeType>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction
+            .find_attribute_type_by_name(attribute_name.clone())
+            .await?
+            .into_iter()
+            .find(|attribute_type| attribute_type.attribute_name == attribute_name))
+    }
+
+    /// Checks a new attribute against its attribute type, its parent and
+    /// the rules of the noun type it is added to.
+    async fn check_new_attribute(
+        attribute_type_id: i64,
+        parent_noun_id: Option<i64>,
+        parent_attribute_id: Option<i64>,
+        data: &DataObject,
+        data_type_version: i64,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<()> {
+        let attribute_type = match transaction
+            .find_attribute_type_by_id(attribute_type_id)
+            .await?
+        {
+            Some(attribute_type) => attribute_type,
+            None => anyhow::bail!(ClwmError::AttributeTypeNotFound),
+        };
+
+        if attribute_type.formula.is_some() && *data != DataObject::Null {
+            anyhow::bail!(ClwmError::ComputedAttributeCannotHaveData)
+        }
+
+        if parent_noun_id.is_none() && parent_attribute_id.is_none() {
+            anyhow::bail!(ClwmError::ParentNounOrParentAttributeIdMustBeSet)
+        }
+
+        if parent_noun_id.is_some() && parent_attribute_id.is_some() {
+            anyhow::bail!(ClwmError::ParentNounAndParentAttributeIdMustNotBeSet)
+        }
+
+        if let Some(parent_noun_id) = parent_noun_id {
+            let noun_type = match transaction.find_noun_by_id(parent_noun_id).await? {
+                Some(noun) => noun.noun_type,
+                None => anyhow::bail!(ClwmError::NounNotFound),
+            };
+            let found_count = transaction
+                .find_attribute_by_parent_noun_id_and_attribute_type_id(
+                    parent_noun_id,
+                    attribute_type_id,
+                )
+                .await?
+                .len() as i64;
+
+            if !attribute_type.multiple_allowed && found_count > 0 {
+                anyhow::bail!(ClwmError::AttributeTypeDoesNotAllowMultipleAttributes {
+                    attribute_type: attribute_type.attribute_name
+                })
+            }
+
+            let rules = Self::find_noun_type_rules(noun_type.clone(), transaction).await?;
+            if let Some(rule) = rules
+                .iter()
+                .find(|rule| rule.attribute_type_id == attribute_type_id)
+            {
+                if rule.requirement == AttributeRequirement::Forbidden {
+                    anyhow::bail!(ClwmError::AttributeTypeForbiddenForNounType {
+                        attribute_type: attribute_type.attribute_name,
+                        noun_type
+                    })
+                }
+                if let Some(max_count) = rule.max_count {
+                    if found_count >= max_count {
+                        anyhow::bail!(ClwmError::AttributeTypeCardinalityExceeded {
+                            attribute_type: attribute_type.attribute_name,
+                            max_count
+                        })
+                    }
+                }
+            }
+        }
+
+        if let Some(parent_attribute_id) = parent_attribute_id {
+            if transaction
+                .find_attribute_by_id(parent_attribute_id)
+                .await?
+                .is_none()
+            {
+                anyhow::bail!(ClwmError::AttributeNotFound)
+            }
+
+            if !attribute_type.multiple_allowed
+                && !transaction
+                    .find_attribute_by_parent_attribute_id_and_attribute_type_id(
+                        parent_attribute_id,
+                        attribute_type_id,
+                    )
+                    .await?
+                    .is_empty()
+            {
+                anyhow::bail!(ClwmError::AttributeTypeDoesNotAllowMultipleAttributes {
+                    attribute_type: attribute_type.attribute_name
+                })
+            }
+        }
+
+        let found_data_type = transaction
+            .find_data_type_all_by_name(attribute_type.data_type)
+            .await?;
+        let data_type = match found_data_type
+            .iter()
+            .find(|x| x.version == Some(data_type_version))
+        {
+            Some(data_type) => data_type,
+            None => anyhow::bail!(ClwmError::DataTypeVersionNotFound),
+        };
+
+        if !is_data_of_data_def(data, &data_type.definition, true) {
+            anyhow::bail!(ClwmError::DataDoesNotMatchDataTypeDefinition)
+        }
+
+        Self::check_asset_references(data, transaction).await?;
+        Ok(())
+    }
+
+    pub async fn new_attribute(
+        &mut self,
+        attribute_type_id: i64,
+        parent_noun_id: Option<i64>,
+        parent_attribute_id: Option<i64>,
+        data: DataObject,
+        data_type_version: i64,
+        metadata: String,
+    ) -> anyhow::Result<Attribute> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let created_attribute = Self::create_attribute(
+            attribute_type_id,
+            parent_noun_id,
+            parent_attribute_id,
+            data,
+            Some(data_type_version),
+            metadata,
+            &transaction,
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(created_attribute)
+    }
+
+    pub async fn update_attribute(&mut self, attribute: Attribute) -> anyhow::Result<Attribute> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        if attribute.attribute_id.is_none() {
+            anyhow::bail!(ClwmError::AttributeHasNoId)
+        }
+        let possible_attribute = transaction
+            .find_attribute_by_id(attribute.attribute_id.unwrap())
+            .await?;
+        if possible_attribute.is_none() {
+            anyhow::bail!(ClwmError::AttributeNotFound)
+        }
+        let old_attribute = possible_attribute.unwrap();
+        if attribute.attribute_type_id != old_attribute.attribute_type_id {
+            anyhow::bail!(ClwmError::AttributeTypeIdDoesNotMatch)
+        }
+        if attribute.parent_noun_id != old_attribute.parent_noun_id {
+            anyhow::bail!(ClwmError::ParentNounIdDoesNotMatch)
+        }
+        if attribute.parent_attribute_id != old_attribute.parent_attribute_id {
+            anyhow::bail!(ClwmError::ParentAttributeIdDoesNotMatch)
+        }
+
+        let found_attribute_type = transaction
+            .find_attribute_type_by_id(attribute.attribute_type_id)
+            .await?;
+
+        if found_attribute_type.as_ref().unwrap().formula.is_some()
+            && attribute.data != DataObject::Null
+        {
+            anyhow::bail!(ClwmError::ComputedAttributeCannotHaveData)
+        }
+
+        let found_data_type = transaction
+            .find_data_type_all_by_name(found_attribute_type.unwrap().data_type)
+            .await?;
+
+        let found_data_type_version = found_data_type
+            .iter()
+            .find(|&x| x.version == Some(attribute.data_type_version));
+        if found_data_type_version.is_none() {
+            anyhow::bail!(ClwmError::DataTypeVersionNotFound)
+        }
+
+        if !is_data_of_data_def(
+            &attribute.data,
+            &found_data_type_version.unwrap().definition,
+            true,
+        ) {
+            anyhow::bail!(ClwmError::DataDoesNotMatchDataTypeDefinition)
+        }
+
+        Self::check_asset_references(&attribute.data, &transaction).await?;
+
+        let new_attribute = Self::save_attribute(&old_attribute, attribute, &transaction).await?;
+        transaction.commit().await?;
+        Ok(new_attribute)
+    }
+
+    async fn save_attribute(
+        old_attribute: &Attribute,
+        attribute: Attribute,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<Attribute> {
+        let new_attribute = transaction.update_attribute(attribute).await?;
+
+        let toml_data_new = history_data(&new_attribute.data)?;
+        let toml_data_old = history_data(&old_attribute.data)?;
+        let attribute_history = AttributeHistory {
+            attribute_id: new_attribute.attribute_id.unwrap(),
+            change_set_id: None,
+            diff_data: create_patch(&toml_data_old, &toml_data_new).to_string(),
+            diff_data_type_version: create_patch(
+                old_attribute.data_type_version.to_string().as_str(),
+                new_attribute.data_type_version.to_string().as_str(),
+            )
+            .to_string(),
+            diff_metadata: create_patch(&old_attribute.metadata, &new_attribute.metadata)
+                .to_string(),
+            diff_visibility: create_patch(
+                &old_attribute.visibility.to_string(),
+                &new_attribute.visibility.to_string(),
+            )
+            .to_string(),
+            change_date: None,
+        };
+
+        transaction.new_attribute_history(attribute_history).await?;
+        Self::index_noun_references(&new_attribute, transaction).await?;
+        Ok(new_attribute)
+    }
+
+    pub async fn traverse(
+        &mut self,
+        noun_id: i64,
+        options: TraversalOptions,
+    ) -> anyhow::Result<Vec<TraversalResult>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        if !Self::is_noun_visible(&self.viewer, noun_id, &transaction).await? {
+            anyhow::bail!(ClwmError::NounNotFound)
+        }
+        // Players must not learn about hidden nouns or hidden references, so
+        // the walk is kept from passing through either.
+        let mut hidden_noun_ids = Vec::new();
+        let mut hidden_attribute_ids = Vec::new();
+        if self.viewer != Viewer::GameMaster {
+            for noun in transaction.find_noun_by_all().await? {
+                let noun_id = noun.noun_id.unwrap();
+                if !Self::is_noun_visible(&self.viewer, noun_id, &transaction).await? {
+                    hidden_noun_ids.push(noun_id);
+                }
+            }
+            for reference in transaction.find_noun_reference_by_all().await? {
+                let visible = match transaction
+                    .find_attribute_by_id(reference.attribute_id)
+                    .await?
+                {
+                    Some(attribute) => {
+                        Self::is_attribute_visible(&self.viewer, &attribute, &transaction).await?
+                    }
+                    None => false,
+                };
+                if !visible {
+                    hidden_attribute_ids.push(reference.attribute_id);
+                }
+            }
+        }
+        transaction
+            .traverse_from_noun(noun_id, options, hidden_noun_ids, hidden_attribute_ids)
+            .await
+    }
+
+    pub async fn noun_graph(&mut self, filter: GraphFilter) -> anyhow::Result<NounGraph> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let mut nouns = self.visible_nouns(transaction.find_noun_by_all().await?);
+        if !filter.noun_types.is_empty() {
+            nouns.retain(|noun| filter.noun_types.contains(&noun.noun_type));
+        }
+        let noun_ids: Vec<i64> = nouns.iter().filter_map(|noun| noun.noun_id).collect();
+        if let Some(root_noun_id) = filter.root_noun_id {
+            if !noun_ids.contains(&root_noun_id) {
+                anyhow::bail!(ClwmError::NounNotFound)
+            }
+        }
+
+        let attribute_names: HashMap<i64, String> = transaction
+            .find_attribute_type_by_all()
+            .await?
+            .into_iter()
+            .filter_map(|x| x.attribute_type_id.map(|id| (id, x.attribute_name)))
+            .collect();
+        let mut edges: Vec<GraphEdge> = Vec::new();
+        for reference in transaction.find_noun_reference_by_all().await? {
+            if !noun_ids.contains(&reference.source_noun_id)
+                || !noun_ids.contains(&reference.target_noun_id)
+            {
+                continue;
+            }
+            if self.viewer != Viewer::GameMaster {
+                let visible = match transaction
+                    .find_attribute_by_id(reference.attribute_id)
+                    .await?
+                {
+                    Some(attribute) => {
+                        Self::is_attribute_visible(&self.viewer, &attribute, &transaction).await?
+                    }
+                    None => false,
+                };
+                if !visible {
+                    continue;
+                }
+            }
+            let edge = GraphEdge {
+                source_noun_id: reference.source_noun_id,
+                target_noun_id: reference.target_noun_id,
+                label: attribute_names
+                    .get(&reference.attribute_type_id)
+                    .cloned()
+                    .unwrap_or_else(|| format!("#{}", reference.attribute_type_id)),
+            };
+            if !edges.contains(&edge) {
+                edges.push(edge);
+            }
+        }
+
+        if let Some(root_noun_id) = filter.root_noun_id {
+            let mut reached: Vec<i64> = vec![root_noun_id];
+            let mut frontier: Vec<i64> = vec![root_noun_id];
+            let mut depth = 0;
+            while !frontier.is_empty() && filter.max_depth.is_none_or(|max_depth| depth < max_depth)
+            {
+                depth += 1;
+                let mut next_frontier = Vec::new();
+                for edge in edges.iter() {
+                    for (from, to) in [
+                        (edge.source_noun_id, edge.target_noun_id),
+                        (edge.target_noun_id, edge.source_noun_id),
+                    ] {
+                        if frontier.contains(&from) && !reached.contains(&to) {
+                            reached.push(to);
+                            next_frontier.push(to);
+                        }
+                    }
+                }
+                frontier = next_frontier;
+            }
+            nouns.retain(|noun| reached.contains(&noun.noun_id.unwrap()));
+            edges.retain(|edge| {
+                reached.contains(&edge.source_noun_id) && reached.contains(&edge.target_noun_id)
+            });
+        }
+        Ok(NounGraph { nouns, edges })
+    }
+
+    pub async fn find_shortest_path(
+        &mut self,
+        from_noun_id: i64,
+        to_noun_id: i64,
+        options: TraversalOptions,
+    ) -> anyhow::Result<Option<TraversalResult>> {
+        Ok(self
+            .traverse(from_noun_id, options)
+            .await?
+            .into_iter()
+            .find(|x| x.noun_id == to_noun_id))
+    }
+
+    pub async fn rebuild_noun_references(&mut self) -> anyhow::Result<usize> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        for attribute in transaction.find_attribute_by_all().await?.iter() {
+            Self::index_noun_references(attribute, &transaction).await?;
+        }
+        let count = transaction.find_noun_reference_by_all().await?.len();
+        transaction.commit().await?;
+        Ok(count)
+    }
+
+    async fn index_noun_references(
+        attribute: &Attribute,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<()> {
+        let attribute_id = attribute.attribute_id.unwrap();
+        transaction
+            .delete_noun_reference_by_attribute_id(attribute_id)
+            .await?;
+        let mut target_noun_ids: Vec<i64> = Vec::new();
+        collect_noun_references(&attribute.data, &mut target_noun_ids);
+        if target_noun_ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut parent_noun_id = attribute.parent_noun_id;
+        let mut parent_attribute_id = attribute.parent_attribute_id;
+        while let (None, Some(current_attribute_id)) = (parent_noun_id, parent_attribute_id) {
+            match transaction
+                .find_attribute_by_id(current_attribute_id)
+                .await?
+            {
+                Some(parent_attribute) => {
+                    parent_noun_id = parent_attribute.parent_noun_id;
+                    parent_attribute_id = parent_attribute.parent_attribute_id;
+                }
+                None => anyhow::bail!(ClwmError::AttributeNotFound),
+            }
+        }
+        let source_noun_id = match parent_noun_id {
+            Some(source_noun_id) => source_noun_id,
+            None => return Ok(()),
+        };
+
+        for target_noun_id in target_noun_ids {
+            transaction
+                .new_noun_reference(NounReferenceEdge {
+                    attribute_id,
+                    attribute_type_id: attribute.attribute_type_id,
+                    source_noun_id,
+                    target_noun_id,
+                })
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_attribute_by_id(
+        &mut self,
+        attribute_id: i64,
+    ) -> anyhow::Result<Option<Attribute>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        match transaction.find_attribute_by_id(attribute_id).await? {
+            Some(attribute)
+                if Self::is_attribute_visible(&self.viewer, &attribute, &transaction).await? =>
+            {
+                Ok(Some(attribute))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// The attributes reached from a noun by following attribute type names
+    /// down the attribute tree, so `["inventory", "staff"]` finds the `staff`
+    /// attributes under the noun's `inventory` attributes.
+    pub async fn find_attributes_by_path(
+        &mut self,
+        noun_id: i64,
+        attribute_names: &[String],
+    ) -> anyhow::Result<Vec<Attribute>> {
+        if self.get_noun_by_id(noun_id).await?.is_none() {
+            anyhow::bail!(ClwmError::NounNotFound);
+        }
+        let mut attribute_type_ids = Vec::new();
+        for attribute_name in attribute_names.iter() {
+            match self
+                .get_attribute_type_by_name(attribute_name.clone())
+                .await?
+            {
+                Some(attribute_type) => {
+                    attribute_type_ids.push(attribute_type.attribute_type_id.unwrap())
+                }
+                None => anyhow::bail!(ClwmError::AttributeTypeNotFound),
+            }
+        }
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+
+        let mut found_attributes: Vec<Attribute> = Vec::new();
+        for (depth, attribute_type_id) in attribute_type_ids.into_iter().enumerate() {
+            found_attributes = if depth == 0 {
+                transaction
+                    .find_attribute_by_parent_noun_id_and_attribute_type_id(
+                        noun_id,
+                        attribute_type_id,
+                    )
+                    .await?
+            } else {
+                let mut children = Vec::new();
+                for parent in found_attributes.iter() {
+                    children.extend(
+                        transaction
+                            .find_attribute_by_parent_attribute_id_and_attribute_type_id(
+                                parent.attribute_id.unwrap(),
+                                attribute_type_id,
+                            )
+                            .await?,
+                    );
+                }
+                children
+            };
+            found_attributes.retain(|x| self.viewer.can_see(&x.visibility));
+        }
+        Ok(found_attributes)
+    }
+
+    /// The recorded changes to a noun, oldest first.
+    pub async fn get_noun_history(&mut self, noun_id: i64) -> anyhow::Result<Vec<NounHistory>> {
+        if self.get_noun_by_id(noun_id).await?.is_none() {
+            anyhow::bail!(ClwmError::NounNotFound);
+        }
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let mut history = transaction.find_noun_history_by_noun_id(noun_id).await?;
+        history.sort_by_key(|history| (history.change_date, history.change_set_id));
+        Ok(history)
+    }
+
+    /// The recorded changes to an attribute, oldest first.
+    pub async fn get_attribute_history(
+        &mut self,
+        attribute_id: i64,
+    ) -> anyhow::Result<Vec<AttributeHistory>> {
+        if self.get_attribute_by_id(attribute_id).await?.is_none() {
+            anyhow::bail!(ClwmError::AttributeNotFound);
+        }
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let mut history = transaction
+            .find_attribute_history_by_attribute_id(attribute_id)
+            .await?;
+        history.sort_by_key(|history| (history.change_date, history.change_set_id));
+        Ok(history)
+    }
+
+    pub async fn roll_attribute_dice(
+        &mut self,
+        noun_id: i64,
+        attribute_type: String,
+        generator: &mut RollGenerator,
+    ) -> anyhow::Result<Vec<AttributeDiceRoll>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        if !Self::is_noun_visible(&self.viewer, noun_id, &transaction).await? {
+            anyhow::bail!(ClwmError::NounNotFound)
+        }
+        let attribute_type_ids: Vec<i64> = match attribute_type.parse::<i64>().ok() {
+            Some(attribute_type_id) => vec![attribute_type_id],
+            None => transaction
+                .find_attribute_type_by_name(attribute_type)
+                .await?
+                .iter()
+                .filter_map(|attribute_type| attribute_type.attribute_type_id)
+                .collect(),
+        };
+        if attribute_type_ids.is_empty() {
+            anyhow::bail!(ClwmError::AttributeTypeNotFound)
+        }
+
+        let mut dice_rolls = Vec::new();
+        for attribute_type_id in attribute_type_ids {
+            let attributes = transaction
+                .find_attribute_by_parent_noun_id_and_attribute_type_id(noun_id, attribute_type_id)
+                .await?;
+            for attribute in attributes.iter() {
+                if !self.viewer.can_see(&attribute.visibility) {
+                    continue;
+                }
+                let mut expressions = Vec::new();
+                collect_dice_expressions(&attribute.data, &mut expressions);
+                for expression in expressions {
+                    let expression = expression.parse::<DiceExpression>()?;
+                    dice_rolls.push(AttributeDiceRoll {
+                        attribute_id: attribute.attribute_id.unwrap(),
+                        roll: generator.roll_dice(&expression),
+                        expression,
+                    });
+                }
+            }
+        }
+        if dice_rolls.is_empty() {
+            anyhow::bail!(ClwmError::AttributeNotFound)
+        }
+        Ok(dice_rolls)
+    }
+
+    pub async fn get_all_attributes(&mut self) -> anyhow::Result<Vec<Attribute>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let mut attributes = Vec::new();
+        for attribute in transaction.find_attribute_by_all().await? {
+            if Self::is_attribute_visible(&self.viewer, &attribute, &transaction).await? {
+                attributes.push(attribute);
+            }
+        }
+        Ok(attributes)
+    }
+
+    pub async fn find_attributes_by_map_key(
+        &mut self,
+        attribute_type_id: i64,
+        key: String,
+    ) -> anyhow::Result<Vec<(Attribute, DataObject)>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let attribute_type = match transaction
+            .find_attribute_type_by_id(attribute_type_id)
+            .await?
+        {
+            Some(attribute_type) => attribute_type,
+            None => anyhow::bail!(ClwmError::AttributeTypeNotFound),
+        };
+        let data_types = transaction
+            .find_data_type_all_by_name(attribute_type.data_type)
+            .await?;
+
+        let mut found = Vec::new();
+        for attribute in transaction
+            .find_attribute_by_attribute_type_id(attribute_type_id)
+            .await?
+        {
+            if !Self::is_attribute_visible(&self.viewer, &attribute, &transaction).await? {
+                continue;
+            }
+            let data_type = match data_types
+                .iter()
+                .find(|x| x.version == Some(attribute.data_type_version))
+            {
+                Some(data_type) => data_type,
+                None => anyhow::bail!(ClwmError::DataTypeVersionNotFound),
+            };
+            let key_def = match &data_type.definition {
+                DataTypeDefinition::Map(key_def, _) => key_def,
+                _ => anyhow::bail!(ClwmError::DataTypeIsNotAMap),
+            };
+            let map_key = parse_map_key(&key, key_def)?;
+            if let DataObject::Map(map) = &attribute.data {
+                if let Some(value) = map.get(&map_key) {
+                    let value = value.clone();
+                    found.push((attribute, value));
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// The hash an asset with this content is stored under.
+    pub fn asset_hash(content: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(content))
+    }
+
+    pub async fn new_asset(
+        &mut self,
+        content: Vec<u8>,
+        mime_type: String,
+    ) -> anyhow::Result<Asset> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let asset_hash = Self::asset_hash(&content);
+
+        // Assets are shared by content, so the same content can not be added
+        // again under a second mime type.
+        if let Some(found_asset) = transaction.find_asset_by_hash(asset_hash.clone()).await? {
+            if found_asset.mime_type != mime_type {
+                anyhow::bail!(ClwmError::AssetMimeTypeDoesNotMatch {
+                    asset_hash,
+                    mime_type: found_asset.mime_type
+                })
+            }
+            return Ok(found_asset);
+        }
+
+        let created_asset = transaction
+            .new_asset(
+                Asset {
+                    asset_hash,
+                    last_changed: None,
+                    mime_type,
+                    size: content.len() as i64,
+                },
+                content,
+            )
+            .await?;
+        transaction.commit().await?;
+        Ok(created_asset)
+    }
+
+    pub async fn get_asset_by_hash(&mut self, asset_hash: String) -> anyhow::Result<Option<Asset>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction.find_asset_by_hash(asset_hash).await?)
+    }
+
+    pub async fn get_asset_content(
+        &mut self,
+        asset_hash: String,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction.find_asset_content_by_hash(asset_hash).await?)
+    }
+
+    pub async fn get_all_assets(&mut self) -> anyhow::Result<Vec<Asset>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        Ok(transaction.find_asset_by_all().await?)
+    }
+
+    pub async fn export_world(&mut self, include_history: bool) -> anyhow::Result<WorldBundle> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+
+        let noun_types = transaction.find_noun_type_by_all().await?;
+        let mut noun_type_attribute_rules = Vec::new();
+        for noun_type in noun_types.iter() {
+            noun_type_attribute_rules.extend(
+                transaction
+                    .find_noun_type_attribute_rule_by_noun_type_id(noun_type.noun_type_id.unwrap())
+                    .await?,
+            );
+        }
+
+        let mut nouns = self.visible_nouns(transaction.find_noun_by_all().await?);
+        for noun in nouns.iter_mut() {
+            self.populate_noun_recursive(noun, &transaction).await?;
+        }
+        let noun_ids: Vec<i64> = nouns.iter().filter_map(|noun| noun.noun_id).collect();
+        let mut attribute_ids = Vec::new();
+        for noun in nouns.iter() {
+            collect_attribute_ids(
+                noun.attributes.as_deref().unwrap_or_default(),
+                &mut attribute_ids,
+            );
+        }
+
+        let relationships: Vec<Relationship> = transaction
+            .find_relationship_by_all()
+            .await?
+            .into_iter()
+            .filter(|relationship| {
+                noun_ids.contains(&relationship.source_noun_id)
+                    && noun_ids.contains(&relationship.target_noun_id)
+            })
+            .collect();
+        let mut events = Vec::new();
+        for noun_id in noun_ids.iter() {
+            if let Some(mut event) = transaction.find_event_by_noun_id(*noun_id).await? {
+                event.participants = Some(
+                    transaction
+                        .find_event_participant_by_event_noun_id(*noun_id)
+                        .await?
+                        .into_iter()
+                        .filter(|participant| noun_ids.contains(&participant.noun_id))
+                        .collect(),
+                );
+                events.push(event);
+            }
+        }
+
+        let attributes: Vec<Attribute> = transaction
+            .find_attribute_by_all()
+            .await?
+            .into_iter()
+            .filter(|attribute| attribute_ids.contains(&attribute.attribute_id.unwrap()))
+            .collect();
+        let mut asset_references = Vec::new();
+        for attribute in attributes.iter() {
+            collect_asset_references(&attribute.data, &mut asset_references);
+        }
+        let mut assets = Vec::new();
+        for asset in transaction.find_asset_by_all().await? {
+            if !asset_references
+                .iter()
+                .any(|reference| reference.asset_hash == asset.asset_hash)
+            {
+                continue;
+            }
+            let content = match transaction
+                .find_asset_content_by_hash(asset.asset_hash.clone())
+                .await?
+            {
+                Some(content) => content,
+                None => anyhow::bail!(ClwmError::AssetNotFound),
+            };
+            assets.push(BundleAsset { asset, content });
+        }
+
+        let history = if include_history {
+            let relationship_ids: Vec<i64> = relationships
+                .iter()
+                .filter_map(|relationship| relationship.relationship_id)
+                .collect();
+            let event_noun_ids: Vec<i64> = events.iter().map(|event| event.noun_id).collect();
+            Some(WorldHistory {
+                change_sets: transaction.find_change_set_by_all().await?,
+                noun_types: transaction.find_noun_type_history_by_all().await?,
+                attribute_types: transaction.find_attribute_type_history_by_all().await?,
+                nouns: transaction
+                    .find_noun_history_by_all()
+                    .await?
+                    .into_iter()
+                    .filter(|history| noun_ids.contains(&history.noun_id))
+                    .collect(),
+                attributes: transaction
+                    .find_attribute_history_by_all()
+                    .await?
+                    .into_iter()
+                    .filter(|history| attribute_ids.contains(&history.attribute_id))
+                    .collect(),
+                relationships: transaction
+                    .find_relationship_history_by_all()
+                    .await?
+                    .into_iter()
+                    .filter(|history| relationship_ids.contains(&history.relationship_id))
+                    .collect(),
+                events: transaction
+                    .find_event_history_by_all()
+                    .await?
+                    .into_iter()
+                    .filter(|history| event_noun_ids.contains(&history.noun_id))
+                    .collect(),
+            })
+        } else {
+            None
+        };
+
+        let bundle = WorldBundle {
+            format_version: WORLD_BUNDLE_VERSION,
+            noun_types,
+            noun_type_attribute_rules,
+            data_types: transaction.find_data_type_all_by_all().await?,
+            attribute_types: transaction.find_attribute_type_by_all().await?,
+            nouns,
+            relationship_types: transaction.find_relationship_type_by_all().await?,
+            relationships,
+            events,
+            calendars: transaction.find_calendar_by_all().await?,
+            roll_tables: transaction.find_roll_table_by_all().await?,
+            templates: transaction.find_template_by_all().await?,
+            assets,
+            history,
+        };
+        transaction.commit().await?;
+        Ok(bundle)
+    }
+
+    pub async fn import_world(&mut self, bundle: WorldBundle) -> anyhow::Result<()> {
+        if bundle.format_version > WORLD_BUNDLE_VERSION {
+            anyhow::bail!(ClwmError::UnsupportedBundleVersion {
+                version: bundle.format_version
+            })
+        }
+        let transaction = self
+            .data_interface
+            .create_transaction("Import".to_owned())
+            .await?;
+
+        let mut change_set_ids: HashMap<i64, i64> = HashMap::new();
+        if let Some(history) = &bundle.history {
+            for change_set in history.change_sets.iter() {
+                let created_change_set = transaction.new_change_set(change_set.clone()).await?;
+                change_set_ids.insert(
+                    change_set.change_set_id.unwrap(),
+                    created_change_set.change_set_id.unwrap(),
+                );
+            }
+        }
+
+        let mut noun_type_ids: HashMap<i64, i64> = HashMap::new();
+        for noun_type in bundle.noun_types.iter() {
+            if !transaction
+                .find_noun_type_by_noun_type(noun_type.noun_type.clone())
+                .await?
+                .is_empty()
+            {
+                anyhow::bail!(ClwmError::NounTypeAlreadyExists {
+                    noun_type: noun_type.noun_type.clone()
+                })
+            }
+            let created_noun_type = transaction
+                .new_noun_type(NounType {
+                    noun_type_id: None,
+                    last_changed: None,
+                    noun_type: noun_type.noun_type.clone(),
+                    parent_noun_type_id: None,
+                    metadata: noun_type.metadata.clone(),
+                })
+                .await?;
+            noun_type_ids.insert(
+                noun_type.noun_type_id.unwrap(),
+                created_noun_type.noun_type_id.unwrap(),
+            );
+        }
+        for noun_type in bundle.noun_types.iter() {
+            if let Some(parent_noun_type_id) = noun_type.parent_noun_type_id {
+                let mut updated_noun_type = noun_type.clone();
+                updated_noun_type.noun_type_id = Some(mapped_id(
+                    &noun_type_ids,
+                    noun_type.noun_type_id.unwrap(),
+                    ClwmError::NounTypeNotFound,
+                )?);
+                updated_noun_type.parent_noun_type_id =
+                    noun_type_ids.get(&parent_noun_type_id).copied();
+                transaction.update_noun_type(updated_noun_type).await?;
+            }
+        }
+
+        let mut data_types = bundle.data_types.clone();
+        data_types.sort_by_key(|data_type| data_type.version);
+        let mut data_type_names: Vec<String> = Vec::new();
+        for data_type in data_types.iter() {
+            if !data_type_names.contains(&data_type.name) {
+                if transaction
+                    .find_data_type_latest_by_name(data_type.name.clone())
+                    .await?
+                    .is_some()
+                {
+                    anyhow::bail!(ClwmError::DataTypeAlreadyExists {
+                        data_type: data_type.name.clone()
+                    })
+                }
+                data_type_names.push(data_type.name.clone());
+            }
+            transaction.new_data_type(data_type.clone()).await?;
+        }
+
+        let mut attribute_type_ids: HashMap<i64, i64> = HashMap::new();
+        for attribute_type in bundle.attribute_types.iter() {
+            if !transaction
+                .find_attribute_type_by_name(attribute_type.attribute_name.clone())
+                .await?
+                .is_empty()
+            {
+                anyhow::bail!(ClwmError::AttributeTypeAlreadyExists {
+                    attribute_type: attribute_type.attribute_name.clone()
+                })
+            }
+            let mut created_attribute_type = attribute_type.clone();
+            created_attribute_type.attribute_type_id = None;
+            let created_attribute_type = transaction
+                .new_attribute_type(created_attribute_type)
+                .await?;
+            attribute_type_ids.insert(
+                attribute_type.attribute_type_id.unwrap(),
+                created_attribute_type.attribute_type_id.unwrap(),
+            );
+        }
+        let mut noun_ids: HashMap<i64, i64> = HashMap::new();
+        for noun in bundle.nouns.iter() {
+            let created_noun = transaction
+                .new_noun(Noun {
+                    noun_id: None,
+                    last_changed: None,
+                    name: noun.name.clone(),
+                    noun_type: noun.noun_type.clone(),
+                    parent_noun_id: None,
+                    metadata: noun.metadata.clone(),
+                    visibility: noun.visibility.clone(),
+                    attributes: None,
+                })
+                .await?;
+            noun_ids.insert(noun.noun_id.unwrap(), created_noun.noun_id.unwrap());
+        }
+        let mut attribute_ids: HashMap<i64, i64> = HashMap::new();
+        for noun in bundle.nouns.iter() {
+            let noun_id = mapped_id(&noun_ids, noun.noun_id.unwrap(), ClwmError::NounNotFound)?;
+            if let Some(parent_noun_id) = noun.parent_noun_id {
+                let mut updated_noun = transaction.find_noun_by_id(noun_id).await?.unwrap();
+                updated_noun.parent_noun_id = noun_ids.get(&parent_noun_id).copied();
+                transaction.update_noun(updated_noun).await?;
+            }
+            for attribute in noun.attributes.iter().flatten() {
+                Self::import_attribute(
+                    attribute,
+                    Some(noun_id),
+                    None,
+                    &attribute_type_ids,
+                    &noun_ids,
+                    &mut attribute_ids,
+                    &transaction,
+                )
+                .await?;
+            }
+        }
+
+        for rule in bundle.noun_type_attribute_rules.iter() {
+            let mut created_rule = rule.clone();
+            created_rule.noun_type_id = mapped_id(
+                &noun_type_ids,
+                rule.noun_type_id,
+                ClwmError::NounTypeNotFound,
+            )?;
+            created_rule.attribute_type_id = mapped_id(
+                &attribute_type_ids,
+                rule.attribute_type_id,
+                ClwmError::AttributeTypeNotFound,
+            )?;
+            if let Some(default_data) = created_rule.default_data.as_mut() {
+                remap_noun_references(default_data, &noun_ids);
+            }
+            transaction
+                .set_noun_type_attribute_rule(created_rule)
+                .await?;
+        }
+
+        for relationship_type in bundle.relationship_types.iter() {
+            if !transaction
+                .find_relationship_type_by_name(relationship_type.relationship_name.clone())
+                .await?
+                .is_empty()
+            {
+                anyhow::bail!(ClwmError::RelationshipTypeNameInUse {
+                    name: relationship_type.relationship_name.clone()
+                })
+            }
+            let mut created_relationship_type = relationship_type.clone();
+            created_relationship_type.relationship_type_id = None;
+            transaction
+                .new_relationship_type(created_relationship_type)
+                .await?;
+        }
+        let mut relationship_ids: HashMap<i64, i64> = HashMap::new();
+        for relationship in bundle.relationships.iter() {
+            let created_relationship = transaction
+                .new_relationship(Relationship {
+                    relationship_id: None,
+                    last_changed: None,
+                    relationship_type: relationship.relationship_type.clone(),
+                    source_noun_id: mapped_id(
+                        &noun_ids,
+                        relationship.source_noun_id,
+                        ClwmError::NounNotFound,
+                    )?,
+                    target_noun_id: mapped_id(
+                        &noun_ids,
+                        relationship.target_noun_id,
+                        ClwmError::NounNotFound,
+                    )?,
+                    metadata: relationship.metadata.clone(),
+                })
+                .await?;
+            relationship_ids.insert(
+                relationship.relationship_id.unwrap(),
+                created_relationship.relationship_id.unwrap(),
+            );
+        }
+        for event in bundle.events.iter() {
+            let event_noun_id = mapped_id(&noun_ids, event.noun_id, ClwmError::NounNotFound)?;
+            let mut created_event = event.clone();
+            created_event.noun_id = event_noun_id;
+            transaction.set_event(created_event).await?;
+            for participant in event.participants.iter().flatten() {
+                transaction
+                    .new_event_participant(
+                        event_noun_id,
+                        EventParticipant {
+                            noun_id: mapped_id(
+                                &noun_ids,
+                                participant.noun_id,
+                                ClwmError::NounNotFound,
+                            )?,
+                            role: participant.role,
+                        },
+                    )
+                    .await?;
+            }
+        }
+
+        for calendar in bundle.calendars.iter() {
+            transaction.set_calendar(calendar.clone()).await?;
+        }
+        for roll_table in bundle.roll_tables.iter() {
+            let mut created_roll_table = roll_table.clone();
+            created_roll_table.roll_table_id = None;
+            for filter in created_roll_table
+                .entries
+                .iter_mut()
+                .filter_map(|entry| entry.nouns.as_mut())
+            {
+                if let Some(attribute_type_id) = filter.attribute_type_id {
+                    filter.attribute_type_id = Some(mapped_id(
+                        &attribute_type_ids,
+                        attribute_type_id,
+                        ClwmError::AttributeTypeNotFound,
+                    )?);
+                }
+            }
+            transaction.set_roll_table(created_roll_table).await?;
+        }
+        for template in bundle.templates.iter() {
+            let mut created_template = template.clone();
+            created_template.template_id = None;
+            remap_template_attributes(
+                &mut created_template.attributes,
+                &attribute_type_ids,
+                &noun_ids,
+            )?;
+            transaction.set_template(created_template).await?;
+        }
+        for bundle_asset in bundle.assets.iter() {
+            if transaction
+                .find_asset_by_hash(bundle_asset.asset.asset_hash.clone())
+                .await?
+                .is_none()
+            {
+                transaction
+                    .new_asset(bundle_asset.asset.clone(), bundle_asset.content.clone())
+                    .await?;
+            }
+        }
+
+        if let Some(history) = bundle.history {
+            for mut noun_type_history in history.noun_types {
+                if let (Some(noun_type_id), Some(change_set_id)) = (
+                    noun_type_ids.get(&noun_type_history.noun_type_id),
+                    noun_type_history
+                        .change_set_id
+                        .and_then(|id| change_set_ids.get(&id)),
+                ) {
+                    noun_type_history.noun_type_id = *noun_type_id;
+                    noun_type_history.change_set_id = Some(*change_set_id);
+                    transaction.new_noun_type_history(noun_type_history).await?;
+                }
+            }
+            for mut attribute_type_history in history.attribute_types {
+                if let (Some(attribute_type_id), Some(change_set_id)) = (
+                    attribute_type_ids.get(&attribute_type_history.attribute_type_id),
+                    attribute_type_history
+                        .change_set_id
+                        .and_then(|id| change_set_ids.get(&id)),
+                ) {
+                    attribute_type_history.attribute_type_id = *attribute_type_id;
+                    attribute_type_history.change_set_id = Some(*change_set_id);
+                    transaction
+                        .new_attribute_type_history(attribute_type_history)
+                        .await?;
+                }
+            }
+            for mut noun_history in history.nouns {
+                if let (Some(noun_id), Some(change_set_id)) = (
+                    noun_ids.get(&noun_history.noun_id),
+                    noun_history
+                        .change_set_id
+                        .and_then(|id| change_set_ids.get(&id)),
+                ) {
+                    noun_history.noun_id = *noun_id;
+                    noun_history.change_set_id = Some(*change_set_id);
+                    transaction.new_noun_history(noun_history).await?;
+                }
+            }
+            for mut attribute_history in history.attributes {
+                if let (Some(attribute_id), Some(change_set_id)) = (
+                    attribute_ids.get(&attribute_history.attribute_id),
+                    attribute_history
+                        .change_set_id
+                        .and_then(|id| change_set_ids.get(&id)),
+                ) {
+                    attribute_history.attribute_id = *attribute_id;
+                    attribute_history.change_set_id = Some(*change_set_id);
+                    transaction.new_attribute_history(attribute_history).await?;
+                }
+            }
+            for mut relationship_history in history.relationships {
+                if let (Some(relationship_id), Some(change_set_id)) = (
+                    relationship_ids.get(&relationship_history.relationship_id),
+                    relationship_history
+                        .change_set_id
+                        .and_then(|id| change_set_ids.get(&id)),
+                ) {
+                    relationship_history.relationship_id = *relationship_id;
+                    relationship_history.change_set_id = Some(*change_set_id);
+                    transaction
+                        .new_relationship_history(relationship_history)
+                        .await?;
+                }
+            }
+            for mut event_history in history.events {
+                if let (Some(noun_id), Some(change_set_id)) = (
+                    noun_ids.get(&event_history.noun_id),
+                    event_history
+                        .change_set_id
+                        .and_then(|id| change_set_ids.get(&id)),
+                ) {
+                    event_history.noun_id = *noun_id;
+                    event_history.change_set_id = Some(*change_set_id);
+                    transaction.new_event_history(event_history).await?;
+                }
+            }
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    #[async_recursion(?Send)]
+    async fn import_attribute(
+        attribute: &Attribute,
+        parent_noun_id: Option<i64>,
+        parent_attribute_id: Option<i64>,
+        attribute_type_ids: &HashMap<i64, i64>,
+        noun_ids: &HashMap<i64, i64>,
+        attribute_ids: &mut HashMap<i64, i64>,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<()> {
+        let mut data = attribute.data.clone();
+        remap_noun_references(&mut data, noun_ids);
+        let created_attribute = transaction
+            .new_attribute(Attribute {
+                attribute_id: None,
+                last_changed: None,
+                attribute_type_id: mapped_id(
+                    attribute_type_ids,
+                    attribute.attribute_type_id,
+                    ClwmError::AttributeTypeNotFound,
+                )?,
+                parent_noun_id,
+                parent_attribute_id,
+                data,
+                data_type_version: attribute.data_type_version,
+                metadata: attribute.metadata.clone(),
+                visibility: attribute.visibility.clone(),
+                children: None,
+            })
+            .await?;
+        Self::index_noun_references(&created_attribute, transaction).await?;
+        let attribute_id = created_attribute.attribute_id.unwrap();
+        attribute_ids.insert(attribute.attribute_id.unwrap(), attribute_id);
+        for child in attribute.children.iter().flatten() {
+            Self::import_attribute(
+                child,
+                None,
+                Some(attribute_id),
+                attribute_type_ids,
+                noun_ids,
+                attribute_ids,
+                transaction,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn export_wiki(&mut self) -> anyhow::Result<Vec<WikiPage>> {
+        let nouns = self.get_all_populated_nouns().await?;
+        let noun_types = self.get_all_noun_types().await?;
+        let attribute_types = self.get_all_attribute_types().await?;
+        Ok(render_wiki(&nouns, &noun_types, &attribute_types))
+    }
+
+    pub async fn plan_vault_import(
+        &mut self,
+        notes: &[VaultNote],
+        mapping: &VaultMapping,
+    ) -> anyhow::Result<VaultImportPlan> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        plan_vault(
+            notes,
+            mapping,
+            &transaction.find_noun_type_by_all().await?,
+            &transaction.find_data_type_all_by_all().await?,
+            &transaction.find_attribute_type_by_all().await?,
+        )
+    }
+
+    pub async fn import_vault(&mut self, plan: VaultImportPlan) -> anyhow::Result<Vec<Noun>> {
+        let transaction = self
+            .data_interface
+            .create_transaction("Import".to_owned())
+            .await?;
+
+        for data_type in plan.data_types.iter().filter(|x| !x.exists) {
+            transaction
+                .new_data_type(DataType {
+                    name: data_type.name.clone(),
+                    system_defined: false,
+                    definition: data_type.definition.clone(),
+                    version: Some(1),
+                    change_date: None,
+                })
+                .await?;
+        }
+        for noun_type in plan.noun_types.iter().filter(|x| !x.exists) {
+            Self::create_noun_type(noun_type.name.clone(), None, String::new(), &transaction)
+                .await?;
+        }
+
+        let mut attribute_type_ids: HashMap<String, i64> = HashMap::new();
+        for planned_attribute_type in plan.attribute_types.iter() {
+            let attribute_name = planned_attribute_type.attribute_name.clone();
+            let attribute_type = if planned_attribute_type.exists {
+                match transaction
+                    .find_attribute_type_by_name(attribute_name.clone())
+                    .await?
+                    .into_iter()
+                    .find(|x| x.attribute_name == attribute_name)
+                {
+                    Some(attribute_type) => attribute_type,
+                    None => anyhow::bail!(ClwmError::AttributeTypeNotFound),
+                }
+            } else {
+                Self::create_attribute_type(
+                    AttributeType {
+                        attribute_type_id: None,
+                        last_changed: None,
+                        attribute_name: attribute_name.clone(),
+                        data_type: planned_attribute_type.data_type.clone(),
+                        multiple_allowed: false,
+                        formula: None,
+                        metadata: String::new(),
+                    },
+                    &transaction,
+                )
+                .await?
+            };
+            attribute_type_ids.insert(attribute_name, attribute_type.attribute_type_id.unwrap());
+        }
+
+        let mut noun_ids: HashMap<i64, i64> = HashMap::new();
+        let mut created_nouns = Vec::new();
+        for (index, noun) in plan.nouns.iter().enumerate() {
+            let created_noun = Self::create_noun(
+                noun.name.clone(),
+                noun.noun_type.clone(),
+                String::new(),
+                &[],
+                &transaction,
+            )
+            .await?;
+            noun_ids.insert(index as i64, created_noun.noun_id.unwrap());
+            created_nouns.push(created_noun);
+        }
+        for (noun, created_noun) in plan.nouns.iter().zip(created_nouns.iter()) {
+            for (attribute_name, data) in noun.attributes.iter() {
+                let mut data = data.clone();
+                replace_noun_references(&mut data, &noun_ids);
+                Self::create_attribute(
+                    attribute_type_ids[attribute_name],
+                    created_noun.noun_id,
+                    None,
+                    data,
+                    None,
+                    String::new(),
+                    &transaction,
+                )
+                .await?;
+            }
+        }
+        transaction.commit().await?;
+        Ok(created_nouns)
+    }
+
+    pub async fn export_noun_table(&mut self, noun_type: String) -> anyhow::Result<NounTable> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        let columns = Self::find_table_columns(&transaction).await?;
+        let rule_attribute_type_ids: Vec<i64> =
+            Self::find_noun_type_rules(noun_type.clone(), &transaction)
+                .await?
+                .iter()
+                .map(|rule| rule.attribute_type_id)
+                .collect();
+        let mut nouns = self.visible_nouns(transaction.find_noun_by_noun_type(noun_type).await?);
+        drop(transaction);
+        for noun in nouns.iter_mut() {
+            self.populate_noun(noun).await?;
+        }
+
+        let columns: Vec<TableColumn> = columns
+            .into_iter()
+            .filter(|column| {
+                rule_attribute_type_ids.contains(&column.attribute_type_id)
+                    || nouns.iter().any(|noun| {
+                        noun.attributes
+                            .iter()
+                            .flatten()
+                            .any(|x| x.attribute_type_id == column.attribute_type_id)
+                    })
+            })
+            .collect();
+        let mut table = NounTable {
+            headers: vec![ID_COLUMN.to_owned(), NAME_COLUMN.to_owned()],
+            rows: Vec::new(),
+        };
+        table
+            .headers
+            .extend(columns.iter().map(|column| column.header.clone()));
+        for noun in nouns.iter() {
+            let mut row = vec![noun.noun_id.unwrap().to_string(), noun.name.clone()];
+            for column in columns.iter() {
+                row.push(
+                    noun.attributes
+                        .iter()
+                        .flatten()
+                        .find(|x| x.attribute_type_id == column.attribute_type_id)
+                        .and_then(|x| column_value(&x.data, &column.path))
+                        .map_or(String::new(), encode_cell),
+                );
+            }
+            table.rows.push(row);
+        }
+        Ok(table)
+    }
+
+    /// Creates or updates nouns from the rows of a table. Nothing is committed
+    /// unless every row is valid.
+    pub async fn import_noun_table(
+        &mut self,
+        noun_type: String,
+        table: NounTable,
+    ) -> anyhow::Result<TableImportSummary> {
+        let transaction = self
+            .data_interface
+            .create_transaction("Import".to_owned())
+            .await?;
+        Self::find_noun_type_id(noun_type.clone(), &transaction).await?;
+        let columns = Self::find_table_columns(&transaction).await?;
+
+        let mut summary = TableImportSummary::default();
+        let mut header_columns: Vec<Option<&TableColumn>> = Vec::new();
+        for header in table.headers.iter() {
+            if header == ID_COLUMN || header == NAME_COLUMN {
+                header_columns.push(None);
+                continue;
+            }
+            match columns.iter().find(|column| column.header == *header) {
+                Some(column) => header_columns.push(Some(column)),
+                None => summary.errors.push(TableRowError {
+                    row: 1,
+                    column: Some(header.clone()),
+                    message: "no single valued attribute type matches this column".to_owned(),
+                }),
+            }
+        }
+        let id_index = table.headers.iter().position(|x| x == ID_COLUMN);
+        let name_index = table.headers.iter().position(|x| x == NAME_COLUMN);
+        if id_index.is_none() && name_index.is_none() {
+            summary.errors.push(TableRowError {
+                row: 1,
+                column: None,
+                message: format!(
+                    "the table needs an {:?} or {:?} column",
+                    ID_COLUMN, NAME_COLUMN
+                ),
+            });
+        }
+        if !summary.errors.is_empty() {
+            transaction.rollback().await?;
+            return Ok(summary);
+        }
+
+        for (index, cells) in table.rows.iter().enumerate() {
+            let row = index + 2;
+            let error_count = summary.errors.len();
+            let cell = |position: Option<usize>| {
+                position
+                    .and_then(|position| cells.get(position))
+                    .map_or("", |x| x.trim())
+            };
+
+            let noun = match cell(id_index) {
+                "" => None,
+                id => match id.parse::<i64>().ok() {
+                    Some(noun_id) => {
+                        Self::find_visible_noun_by_id(&self.viewer, noun_id, &transaction).await?
+                    }
+                    None => None,
+                },
+            };
+            let name = cell(name_index).to_owned();
+            match &noun {
+                Some(noun) if noun.noun_type != noun_type => summary.errors.push(TableRowError {
+                    row,
+                    column: Some(ID_COLUMN.to_owned()),
+                    message: format!("noun {} is a {}", cell(id_index), noun.noun_type),
+                }),
+                None if !cell(id_index).is_empty() => summary.errors.push(TableRowError {
+                    row,
+                    column: Some(ID_COLUMN.to_owned()),
+                    message: format!("noun {:?} not found", cell(id_index)),
+                }),
+                None if name.is_empty() => summary.errors.push(TableRowError {
+                    row,
+                    column: Some(NAME_COLUMN.to_owned()),
+                    message: "a new noun needs a name".to_owned(),
+                }),
+                _ => {}
+            }
+
+            let mut values: Vec<(i64, Vec<(&TableColumn, DataObject)>)> = Vec::new();
+            for (position, column) in header_columns.iter().enumerate() {
+                let column = match column {
+                    Some(column) => column,
+                    None => continue,
+                };
+                let raw_cell = cells.get(position).map_or("", |x| x.as_str());
+                if raw_cell.trim().is_empty() {
+                    continue;
+                }
+                let value = match decode_cell(raw_cell, &column.definition) {
+                    std::result::Result::Ok(value) => value,
+                    Err(message) => {
+                        summary.errors.push(TableRowError {
+                            row,
+                            column: Some(column.header.clone()),
+                            message,
+                        });
+                        continue;
+                    }
+                };
+                let mut references = Vec::new();
+                collect_noun_references(&value, &mut references);
+                for noun_id in references {
+                    if !Self::is_noun_visible(&self.viewer, noun_id, &transaction).await? {
+                        summary.errors.push(TableRowError {
+                            row,
+                            column: Some(column.header.clone()),
+                            message: format!("noun {} not found", noun_id),
+                        });
+                    }
+                }
+                match values
+                    .iter_mut()
+                    .find(|(attribute_type_id, _)| *attribute_type_id == column.attribute_type_id)
+                {
+                    Some((_, attribute_values)) => attribute_values.push((column, value)),
+                    None => values.push((column.attribute_type_id, vec![(column, value)])),
+                }
+            }
+            if summary.errors.len() > error_count {
+                continue;
+            }
+
+            let is_new = noun.is_none();
+            let result =
+                Self::apply_table_row(noun, name, noun_type.clone(), values, &transaction).await;
+            if let Err(error) = result {
+                summary.errors.push(TableRowError {
+                    row,
+                    column: None,
+                    message: error.to_string(),
+                });
+            } else if is_new {
+                summary.created += 1;
+            } else {
+                summary.updated += 1;
+            }
+        }
+
+        if summary.errors.is_empty() {
+            transaction.commit().await?;
+        } else {
+            transaction.rollback().await?;
+        }
+        Ok(summary)
+    }
+
+    async fn apply_table_row(
+        noun: Option<Noun>,
+        name: String,
+        noun_type: String,
+        values: Vec<(i64, Vec<(&TableColumn, DataObject)>)>,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<()> {
+        let noun = match noun {
+            Some(noun) if !name.is_empty() && name != noun.name => {
+                let mut renamed_noun = noun.clone();
+                renamed_noun.name = name;
+                Self::save_noun(&noun, renamed_noun, transaction).await?
+            }
+            Some(noun) => noun,
+            None => Self::create_noun(name, noun_type, String::new(), &[], transaction).await?,
+        };
+        let noun_id = noun.noun_id.unwrap();
+
+        for (attribute_type_id, attribute_values) in values {
+            let existing = transaction
+                .find_attribute_by_parent_noun_id_and_attribute_type_id(noun_id, attribute_type_id)
+                .await?
+                .into_iter()
+                .next();
+            let mut data = existing
+                .as_ref()
+                .map_or(DataObject::Null, |attribute| attribute.data.clone());
+            for (column, value) in attribute_values {
+                set_column_value(&mut data, &column.path, value);
+            }
+            match existing {
+                Some(old_attribute) if old_attribute.data != data => {
+                    let attribute_type = match transaction
+                        .find_attribute_type_by_id(attribute_type_id)
+                        .await?
+                    {
+                        Some(attribute_type) => attribute_type,
+                        None => anyhow::bail!(ClwmError::AttributeTypeNotFound),
+                    };
+                    let data_type = match transaction
+                        .find_data_type_latest_by_name(attribute_type.data_type)
+                        .await?
+                    {
+                        Some(data_type) => data_type,
+                        None => anyhow::bail!(ClwmError::DataTypeNotFound),
+                    };
+                    if !is_data_of_data_def(&data, &data_type.definition, true) {
+                        anyhow::bail!(ClwmError::DataDoesNotMatchDataTypeDefinition)
+                    }
+                    let mut attribute = old_attribute.clone();
+                    attribute.data = data;
+                    attribute.data_type_version = data_type.version.unwrap();
+                    Self::save_attribute(&old_attribute, attribute, transaction).await?;
+                }
+                Some(_) => {}
+                None => {
+                    Self::create_attribute(
+                        attribute_type_id,
+                        Some(noun_id),
+                        None,
+                        data,
+                        None,
+                        String::new(),
+                        transaction,
+                    )
+                    .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn find_table_columns(
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<Vec<TableColumn>> {
+        let mut columns = Vec::new();
+        for attribute_type in transaction.find_attribute_type_by_all().await? {
+            if attribute_type.formula.is_some() || attribute_type.multiple_allowed {
+                continue;
+            }
+            if let Some(data_type) = transaction
+                .find_data_type_latest_by_name(attribute_type.data_type.clone())
+                .await?
+            {
+                columns.extend(attribute_columns(&attribute_type, &data_type.definition));
+            }
+        }
+        Ok(columns)
+    }
+
+    pub async fn build_site(&mut self) -> anyhow::Result<Vec<SiteFile>> {
+        let nouns = self.get_all_populated_nouns().await?;
+        let noun_types = self.get_all_noun_types().await?;
+        let attribute_types = self.get_all_attribute_types().await?;
+        let pages = render_wiki(&nouns, &noun_types, &attribute_types);
+
+        let mut asset_references = Vec::new();
+        for noun in nouns.iter() {
+            collect_attribute_asset_references(
+                noun.attributes.as_deref().unwrap_or_default(),
+                &mut asset_references,
+            );
+        }
+        let mut assets: Vec<BundleAsset> = Vec::new();
+        for reference in asset_references {
+            if assets
+                .iter()
+                .any(|x| x.asset.asset_hash == reference.asset_hash)
+            {
+                continue;
+            }
+            let asset = self.get_asset_by_hash(reference.asset_hash.clone()).await?;
+            let content = self.get_asset_content(reference.asset_hash.clone()).await?;
+            match (asset, content) {
+                (Some(asset), Some(content)) => assets.push(BundleAsset { asset, content }),
+                _ => anyhow::bail!(ClwmError::AssetNotFound),
+            }
+        }
+        render_site(&pages, &assets)
+    }
+
+    async fn get_all_populated_nouns(&mut self) -> anyhow::Result<Vec<Noun>> {
+        let mut nouns = self.get_all_nouns().await?;
+        for noun in nouns.iter_mut() {
+            self.populate_noun(noun).await?;
+        }
+        Ok(nouns)
+    }
+
+    pub async fn export_redacted(
+        &mut self,
+        rules: RedactionRules,
+        file_name: String,
+        path: String,
+    ) -> anyhow::Result<RedactionSummary> {
+        if std::path::Path::new(&path).exists() {
+            anyhow::bail!("{} already exists", path);
+        }
+        // The copy is redacted under a temporary name so that a failure never
+        // leaves unredacted data where the export is expected.
+        let partial_path = format!("{}.partial", path);
+        let data_interface_type = self.clwm_file.data_interface;
+        let url = self.data_interface.copy_to(partial_path.clone()).await?;
+        let (summary, url) = match Self::redact_copy(
+            ClwmFile {
+                url,
+                data_interface: data_interface_type,
+            },
+            &rules,
+            &self.viewer,
+            path.clone(),
+        )
+        .await
+        {
+            Err(error) => {
+                let _ = std::fs::remove_file(&partial_path);
+                return Err(error);
+            }
+            result => result?,
+        };
+        if let Err(error) = Self::create(data_interface_type, url, file_name).await {
+            let _ = std::fs::remove_file(&path);
+            return Err(error);
+        }
+        Ok(summary)
+    }
+
+    async fn redact_copy(
+        clwm_file: ClwmFile,
+        rules: &RedactionRules,
+        viewer: &Viewer,
+        path: String,
+    ) -> anyhow::Result<(RedactionSummary, String)> {
+        let mut export = Clwm::from_clwm_file(clwm_file).await?;
+        let mut summary = export.redact(rules, viewer).await?;
+        summary.assets_removed = export.collect_asset_garbage().await?.len();
+        export.data_interface.compact().await?;
+        let url = export.data_interface.move_to(path).await?;
+        Ok((summary, url))
+    }
+
+    async fn redact(
+        &mut self,
+        rules: &RedactionRules,
+        viewer: &Viewer,
+    ) -> anyhow::Result<RedactionSummary> {
+        let transaction = self
+            .data_interface
+            .create_transaction("CLWM".to_owned())
+            .await?;
+        transaction.delete_all_history().await?;
+
+        let mut noun_type_noun_ids = Vec::new();
+        for noun_type in rules.noun_types.iter() {
+            if transaction
+                .find_noun_type_by_noun_type(noun_type.clone())
+                .await?
+                .is_empty()
+            {
+                anyhow::bail!(ClwmError::NounTypeNotFound)
+            }
+            for noun in transaction
+                .find_noun_by_noun_type_including_subtypes(noun_type.clone())
+                .await?
+            {
+                noun_type_noun_ids.extend(noun.noun_id);
+            }
+        }
+        let mut attribute_type_ids = Vec::new();
+        for attribute_type in rules.attribute_types.iter() {
+            let found_attribute_type = match attribute_type.parse::<i64>().ok() {
+                Some(attribute_type_id) => {
+                    transaction
+                        .find_attribute_type_by_id(attribute_type_id)
+                        .await?
+                }
+                None => transaction
+                    .find_attribute_type_by_name(attribute_type.clone())
+                    .await?
+                    .pop(),
+            };
+            match found_attribute_type.and_then(|attribute_type| attribute_type.attribute_type_id) {
+                Some(attribute_type_id) => attribute_type_ids.push(attribute_type_id),
+                None => anyhow::bail!(ClwmError::AttributeTypeNotFound),
+            }
+        }
+
+        let (kept_nouns, removed_nouns): (Vec<Noun>, Vec<Noun>) = transaction
+            .find_noun_by_all()
+            .await?
+            .into_iter()
+            .partition(|noun| {
+                let noun_id = noun.noun_id.unwrap();
+                (rules.noun_types.is_empty()
+                    || noun_type_noun_ids.contains(&noun_id)
+                    || rules.include_nouns.contains(&noun_id))
+                    && !rules.exclude_nouns.contains(&noun_id)
+                    && viewer.can_see(&noun.visibility)
+            });
+        let removed_noun_ids: Vec<i64> = removed_nouns
+            .iter()
+            .filter_map(|noun| noun.noun_id)
+            .collect();
+
+        let attributes = transaction.find_attribute_by_all().await?;
+        let mut removed_attribute_ids: Vec<i64> = attributes
+            .iter()
+            .filter(|attribute| {
+                !(attribute_type_ids.is_empty()
+                    || attribute_type_ids.contains(&attribute.attribute_type_id))
+                    || !viewer.can_see(&attribute.visibility)
+                    || attribute
+                        .parent_noun_id
+                        .is_some_and(|noun_id| removed_noun_ids.contains(&noun_id))
+            })
+            .filter_map(|attribute| attribute.attribute_id)
+            .collect();
+        loop {
+            let removed_children: Vec<i64> = attributes
+                .iter()
+                .filter(|attribute| {
+                    attribute
+                        .parent_attribute_id
+                        .is_some_and(|parent_id| removed_attribute_ids.contains(&parent_id))
+                })
+                .filter_map(|attribute| attribute.attribute_id)
+                .filter(|attribute_id| !removed_attribute_ids.contains(attribute_id))
+                .collect();
+            if removed_children.is_empty() {
+                break;
+            }
+            removed_attribute_ids.extend(removed_children);
+        }
+
+        let mut placeholders: HashMap<i64, i64> = HashMap::new();
+        for mut attribute in attributes
+            .into_iter()
+            .filter(|attribute| !removed_attribute_ids.contains(&attribute.attribute_id.unwrap()))
+        {
+            let mut references = Vec::new();
+            collect_noun_references(&attribute.data, &mut references);
+            references.retain(|noun_id| removed_noun_ids.contains(noun_id));
+            if references.is_empty() {
+                continue;
+            }
+            for noun_id in references {
+                if placeholders.contains_key(&noun_id) {
+                    continue;
+                }
+                if placeholders.is_empty()
+                    && transaction
+                        .find_noun_type_by_noun_type(rules.placeholder_noun_type.clone())
+                        .await?
+                        .iter()
+                        .all(|noun_type| noun_type.noun_type != rules.placeholder_noun_type)
+                {
+                    transaction
+                        .new_noun_type(NounType {
+                            noun_type_id: None,
+                            last_changed: None,
+                            noun_type: rules.placeholder_noun_type.clone(),
+                            parent_noun_type_id: None,
+                            metadata: String::new(),
+                        })
+                        .await?;
+                }
+                let placeholder = transaction
+                    .new_noun(Noun {
+                        noun_id: None,
+                        last_changed: None,
+                        name: rules.placeholder_name.clone(),
+                        noun_type: rules.placeholder_noun_type.clone(),
+                        parent_noun_id: None,
+                        metadata: String::new(),
+                        visibility: Visibility::Public,
+                        attributes: None,
+                    })
+                    .await?;
+                placeholders.insert(noun_id, placeholder.noun_id.unwrap());
+            }
+            replace_noun_references(&mut attribute.data, &placeholders);
+            let attribute = transaction.update_attribute(attribute).await?;
+            Self::index_noun_references(&attribute, &transaction).await?;
+        }
+
+        for attribute_id in removed_attribute_ids.iter().rev() {
+            transaction
+                .delete_noun_reference_by_attribute_id(*attribute_id)
+                .await?;
+            transaction.delete_attribute(*attribute_id).await?;
+        }
+        for relationship in transaction.find_relationship_by_all().await? {
+            if removed_noun_ids.contains(&relationship.source_noun_id)
+                || removed_noun_ids.contains(&relationship.target_noun_id)
+            {
+                transaction
+                    .delete_relationship(relationship.relationship_id.unwrap())
+                    .await?;
+            }
+        }
+        for noun in kept_nouns.iter().chain(removed_nouns.iter()) {
+            let noun_id = noun.noun_id.unwrap();
+            if transaction.find_event_by_noun_id(noun_id).await?.is_none() {
+                continue;
+            }
+            let is_removed = removed_noun_ids.contains(&noun_id);
+            for participant in transaction
+                .find_event_participant_by_event_noun_id(noun_id)
+                .await?
+            {
+                if is_removed || removed_noun_ids.contains(&participant.noun_id) {
+                    transaction
+                        .delete_event_participant(noun_id, participant)
+                        .await?;
+                }
+            }
+            if is_removed {
+                transaction.delete_event(noun_id).await?;
+            }
+        }
+        for noun in kept_nouns.iter().chain(removed_nouns.iter()) {
+            if noun
+                .parent_noun_id
+                .is_some_and(|parent_noun_id| removed_noun_ids.contains(&parent_noun_id))
+            {
+                let mut noun = noun.clone();
+                noun.parent_noun_id = None;
+                transaction.update_noun(noun).await?;
+            }
+        }
+        for noun_id in removed_noun_ids.iter() {
+            transaction.delete_noun(*noun_id).await?;
+        }
+
+        // Templates, roll tables and rule defaults are GM tools that can name
+        // hidden nouns, so none of them are handed to players.
+        for template in transaction.find_template_by_all().await? {
+            transaction.delete_template(template.template_name).await?;
+        }
+        for roll_table in transaction.find_roll_table_by_all().await? {
+            transaction
+                .delete_roll_table(roll_table.roll_table_name)
+                .await?;
+        }
+        for noun_type in transaction.find_noun_type_by_all().await? {
+            for mut rule in transaction
+                .find_noun_type_attribute_rule_by_noun_type_id(noun_type.noun_type_id.unwrap())
+                .await?
+            {
+                if rule.default_data.is_some() {
+                    rule.default_data = None;
+                    transaction.set_noun_type_attribute_rule(rule).await?;
+                }
+            }
+        }
+        if !rules.calendars.is_empty() {
+            for calendar in transaction.find_calendar_by_all().await? {
+                if !rules.calendars.contains(&calendar.calendar_name) {
+                    transaction.delete_calendar(calendar.calendar_name).await?;
+                }
+            }
+        }
+        transaction.delete_unreferenced_change_sets().await?;
 
-        transaction.new_attribute_history(attribute_history).await?;
         transaction.commit().await?;
-        Ok(created_attribute)
+        Ok(RedactionSummary {
+            nouns_removed: removed_noun_ids.len(),
+            attributes_removed: removed_attribute_ids.len(),
+            placeholders_created: placeholders.len(),
+            assets_removed: 0,
+        })
     }
 
-    pub async fn update_attribute(&mut self, attribute: Attribute) -> anyhow::Result<Attribute> {
+    pub async fn collect_asset_garbage(&mut self) -> anyhow::Result<Vec<Asset>> {
         let transaction = self
             .data_interface
             .create_transaction("CLWM".to_owned())
             .await?;
-        if attribute.attribute_id.is_none() {
-            anyhow::bail!(ClwmError::AttributeHasNoId)
-        }
-        let possible_attribute = transaction
-            .find_attribute_by_id(attribute.attribute_id.unwrap())
-            .await?;
-        if possible_attribute.is_none() {
-            anyhow::bail!(ClwmError::AttributeNotFound)
-        }
-        let old_attribute = possible_attribute.unwrap();
-        if attribute.attribute_type_id != old_attribute.attribute_type_id {
-            anyhow::bail!(ClwmError::AttributeTypeIdDoesNotMatch)
+
+        let mut referenced: Vec<DataObject> = transaction
+            .find_attribute_by_all()
+            .await?
+            .into_iter()
+            .map(|attribute| attribute.data)
+            .collect();
+
+        // Older versions of an attribute can still be restored from its
+        // history, so every version is rebuilt from the stored patches.
+        let mut versions: HashMap<i64, String> = HashMap::new();
+        for history in transaction.find_attribute_history_by_all().await? {
+            let version = versions.entry(history.attribute_id).or_default();
+            *version = diffy::apply(version, &diffy::Patch::from_str(&history.diff_data)?)?;
+            if !version.is_empty() {
+                referenced.push(toml::from_str::<DataObject>(version)?);
+            }
         }
-        if attribute.parent_noun_id != old_attribute.parent_noun_id {
-            anyhow::bail!(ClwmError::ParentNounIdDoesNotMatch)
+
+        for template in transaction.find_template_by_all().await? {
+            collect_template_data(&template.attributes, &mut referenced);
         }
-        if attribute.parent_attribute_id != old_attribute.parent_attribute_id {
-            anyhow::bail!(ClwmError::ParentAttributeIdDoesNotMatch)
+        for noun_type in transaction.find_noun_type_by_all().await? {
+            referenced.extend(
+                transaction
+                    .find_noun_type_attribute_rule_by_noun_type_id(noun_type.noun_type_id.unwrap())
+                    .await?
+                    .into_iter()
+                    .filter_map(|rule| rule.default_data),
+            );
         }
 
-        let found_attribute_type = transaction
-            .find_attribute_type_by_id(attribute.attribute_type_id)
-            .await?;
-
-        let found_data_type = transaction
-            .find_data_type_all_by_name(found_attribute_type.unwrap().data_type)
-            .await?;
+        let mut references = Vec::new();
+        for data in referenced.iter() {
+            collect_asset_references(data, &mut references);
+        }
 
-        let found_data_type_version = found_data_type
-            .iter()
-            .find(|&x| x.version == Some(attribute.data_type_version));
-        if found_data_type_version.is_none() {
-            anyhow::bail!(ClwmError::DataTypeVersionNotFound)
+        let mut removed_assets = Vec::new();
+        for asset in transaction.find_asset_by_all().await? {
+            if !references
+                .iter()
+                .any(|reference| reference.asset_hash == asset.asset_hash)
+            {
+                transaction.delete_asset(asset.asset_hash.clone()).await?;
+                removed_assets.push(asset);
+            }
         }
 
-        if !is_data_of_data_def(
-            &attribute.data,
-            &found_data_type_version.unwrap().definition,
-            true,
-        ) {
-            anyhow::bail!(ClwmError::DataDoesNotMatchDataTypeDefinition)
+        transaction.commit().await?;
+        Ok(removed_assets)
+    }
+
+    async fn check_asset_references(
+        data: &DataObject,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<()> {
+        let mut references = Vec::new();
+        collect_asset_references(data, &mut references);
+        for reference in references {
+            let asset = match transaction
+                .find_asset_by_hash(reference.asset_hash.clone())
+                .await?
+            {
+                Some(asset) => asset,
+                None => anyhow::bail!(ClwmError::AssetNotFound),
+            };
+            if asset.mime_type != reference.mime_type || asset.size != reference.size {
+                anyhow::bail!(ClwmError::AssetReferenceDoesNotMatch)
+            }
         }
+        Ok(())
+    }
 
-        let new_attribute = transaction.update_attribute(attribute).await?;
+    pub async fn set_noun_visibility(
+        &mut self,
+        noun_id: i64,
+        visibility: Visibility,
+    ) -> anyhow::Result<Noun> {
+        let mut noun = match self.get_noun_by_id(noun_id).await? {
+            Some(noun) => noun,
+            None => anyhow::bail!(ClwmError::NounNotFound),
+        };
+        noun.visibility = visibility;
+        self.update_noun(noun).await
+    }
 
-        let toml_data_new = toml::to_string(&new_attribute.data)?;
-        let toml_data_old = toml::to_string(&old_attribute.data)?;
-        let attribute_history = AttributeHistory {
-            attribute_id: new_attribute.attribute_id.unwrap(),
-            diff_data: create_patch(&toml_data_old, &toml_data_new).to_string(),
-            diff_data_type_version: create_patch(
-                old_attribute.data_type_version.to_string().as_str(),
-                new_attribute.data_type_version.to_string().as_str(),
-            )
-            .to_string(),
-            diff_metadata: create_patch(&old_attribute.metadata, &new_attribute.metadata)
-                .to_string(),
-            change_date: None,
+    pub async fn set_attribute_visibility(
+        &mut self,
+        attribute_id: i64,
+        visibility: Visibility,
+    ) -> anyhow::Result<Attribute> {
+        let mut attribute = match self.get_attribute_by_id(attribute_id).await? {
+            Some(attribute) => attribute,
+            None => anyhow::bail!(ClwmError::AttributeNotFound),
         };
+        attribute.visibility = visibility;
+        self.update_attribute(attribute).await
+    }
 
-        transaction.new_attribute_history(attribute_history).await?;
+    fn visible_nouns(&self, mut nouns: Vec<Noun>) -> Vec<Noun> {
+        nouns.retain(|noun| self.viewer.can_see(&noun.visibility));
+        nouns
+    }
 
-        transaction.commit().await?;
+    async fn is_noun_visible(
+        viewer: &Viewer,
+        noun_id: i64,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<bool> {
+        Ok(Self::find_visible_noun_by_id(viewer, noun_id, transaction)
+            .await?
+            .is_some())
+    }
 
-        Ok(new_attribute)
+    async fn find_visible_noun_by_id(
+        viewer: &Viewer,
+        noun_id: i64,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<Option<Noun>> {
+        Ok(transaction
+            .find_noun_by_id(noun_id)
+            .await?
+            .filter(|noun| viewer.can_see(&noun.visibility)))
     }
 
-    pub async fn get_attribute_by_id(
-        &mut self,
-        attribute_id: i64,
-    ) -> anyhow::Result<Option<Attribute>> {
-        let transaction = self
-            .data_interface
-            .create_transaction("CLWM".to_owned())
-            .await?;
-        Ok(transaction.find_attribute_by_id(attribute_id).await?)
+    #[async_recursion(?Send)]
+    async fn is_attribute_visible(
+        viewer: &Viewer,
+        attribute: &Attribute,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<bool> {
+        if !viewer.can_see(&attribute.visibility) {
+            return Ok(false);
+        }
+        if *viewer == Viewer::GameMaster {
+            return Ok(true);
+        }
+        if let Some(parent_noun_id) = attribute.parent_noun_id {
+            return Self::is_noun_visible(viewer, parent_noun_id, transaction).await;
+        }
+        if let Some(parent_attribute_id) = attribute.parent_attribute_id {
+            return match transaction
+                .find_attribute_by_id(parent_attribute_id)
+                .await?
+            {
+                Some(parent_attribute) => {
+                    Self::is_attribute_visible(viewer, &parent_attribute, transaction).await
+                }
+                None => Ok(false),
+            };
+        }
+        Ok(true)
     }
 
-    pub async fn get_all_attributes(&mut self) -> anyhow::Result<Vec<Attribute>> {
-        let transaction = self
-            .data_interface
-            .create_transaction("CLWM".to_owned())
-            .await?;
-        Ok(transaction.find_attribute_by_all().await?)
+    async fn visible_participants(
+        viewer: &Viewer,
+        participants: Vec<EventParticipant>,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<Vec<EventParticipant>> {
+        let mut visible_participants = Vec::new();
+        for participant in participants {
+            if Self::is_noun_visible(viewer, participant.noun_id, transaction).await? {
+                visible_participants.push(participant);
+            }
+        }
+        Ok(visible_participants)
     }
 
     pub async fn populate_noun(&mut self, noun: &mut Noun) -> anyhow::Result<()> {
@@ -669,6 +4194,7 @@ impl Clwm {
             .await?;
 
         self.populate_noun_recursive(noun, &transaction).await?;
+        self.evaluate_noun_formulas(noun, &transaction).await?;
 
         Ok(())
     }
@@ -688,6 +4214,7 @@ impl Clwm {
         let mut found_attributes = transaction
             .find_attribute_by_parent_noun_id(noun_id)
             .await?;
+        found_attributes.retain(|x| self.viewer.can_see(&x.visibility));
 
         future::join_all(
             found_attributes
@@ -703,6 +4230,207 @@ impl Clwm {
         Ok(())
     }
 
+    async fn evaluate_noun_formulas(
+        &self,
+        noun: &mut Noun,
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<()> {
+        let attribute_types = transaction.find_attribute_type_by_all().await?;
+        if !attribute_types.iter().any(|x| x.formula.is_some()) {
+            return Ok(());
+        }
+        if let Some(attributes) = noun.attributes.as_mut() {
+            self.evaluate_formulas_recursive(
+                attributes,
+                &attribute_types,
+                transaction,
+                &mut HashMap::new(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    #[async_recursion(?Send)]
+    async fn evaluate_formulas_recursive(
+        &self,
+        attributes: &mut [Attribute],
+        attribute_types: &[AttributeType],
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+        computed: &mut HashMap<i64, DataObject>,
+    ) -> anyhow::Result<()> {
+        for attribute in attributes.iter_mut() {
+            if let Some(children) = attribute.children.as_mut() {
+                self.evaluate_formulas_recursive(children, attribute_types, transaction, computed)
+                    .await?;
+            }
+        }
+
+        let siblings = attributes.to_vec();
+        for (index, attribute) in siblings.iter().enumerate() {
+            if attribute_types.iter().any(|x| {
+                x.attribute_type_id == Some(attribute.attribute_type_id) && x.formula.is_some()
+            }) {
+                let mut stack = Vec::new();
+                attributes[index].data = self
+                    .compute_attribute(
+                        attribute,
+                        &siblings,
+                        attribute_types,
+                        transaction,
+                        &mut stack,
+                        computed,
+                    )
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    #[async_recursion(?Send)]
+    async fn compute_attribute(
+        &self,
+        attribute: &Attribute,
+        siblings: &[Attribute],
+        attribute_types: &[AttributeType],
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+        stack: &mut Vec<i64>,
+        computed: &mut HashMap<i64, DataObject>,
+    ) -> anyhow::Result<DataObject> {
+        let attribute_type = match attribute_types
+            .iter()
+            .find(|x| x.attribute_type_id == Some(attribute.attribute_type_id))
+        {
+            Some(attribute_type) => attribute_type,
+            None => anyhow::bail!(ClwmError::AttributeTypeNotFound),
+        };
+        let formula = match &attribute_type.formula {
+            Some(formula) => formula,
+            None => return Ok(attribute.data.clone()),
+        };
+        let attribute_id = match attribute.attribute_id {
+            Some(attribute_id) => attribute_id,
+            None => anyhow::bail!(ClwmError::AttributeHasNoId),
+        };
+        // Attributes reached through several references are only computed once.
+        if let Some(data) = computed.get(&attribute_id) {
+            return Ok(data.clone());
+        }
+        if stack.contains(&attribute_id) {
+            anyhow::bail!(ClwmError::FormulaCycle {
+                attribute_type: attribute_type.attribute_name.clone()
+            })
+        }
+        stack.push(attribute_id);
+
+        let expression = parse_formula(formula)?;
+        let mut values = HashMap::new();
+        for reference in formula_references(&expression) {
+            let mut data = Vec::new();
+            for (target, target_siblings) in self
+                .resolve_formula_reference(reference, siblings, attribute_types, transaction)
+                .await?
+            {
+                data.push(
+                    self.compute_attribute(
+                        &target,
+                        &target_siblings,
+                        attribute_types,
+                        transaction,
+                        stack,
+                        computed,
+                    )
+                    .await?,
+                );
+            }
+            values.insert(reference.text.clone(), data);
+        }
+        stack.pop();
+
+        let result = evaluate_formula(&expression, &values)?;
+        let found_data_type = transaction
+            .find_data_type_all_by_name(attribute_type.data_type.clone())
+            .await?;
+        let data = match found_data_type
+            .iter()
+            .find(|&x| x.version == Some(attribute.data_type_version))
+            .map(|x| &x.definition)
+        {
+            Some(DataTypeDefinition::Integer) => DataObject::Integer(result.trunc() as i64),
+            Some(DataTypeDefinition::Float) => DataObject::Float(result),
+            Some(_) => anyhow::bail!(ClwmError::FormulaRequiresNumericDataType),
+            None => anyhow::bail!(ClwmError::DataTypeVersionNotFound),
+        };
+        computed.insert(attribute_id, data.clone());
+        Ok(data)
+    }
+
+    async fn resolve_formula_reference(
+        &self,
+        reference: &FormulaReference,
+        siblings: &[Attribute],
+        attribute_types: &[AttributeType],
+        transaction: &Box<dyn DataInterfaceAccessTransaction>,
+    ) -> anyhow::Result<Vec<(Attribute, Vec<Attribute>)>> {
+        let mut current: Vec<(Attribute, Vec<Attribute>)> = Vec::new();
+        for segment in reference.segments.iter() {
+            let attribute_type_id = match attribute_types
+                .iter()
+                .find(|x| x.attribute_name == segment.attribute_name)
+            {
+                Some(attribute_type) => attribute_type.attribute_type_id,
+                None => anyhow::bail!(ClwmError::FormulaInputMissing {
+                    reference: reference.text.clone()
+                }),
+            };
+
+            let mut candidate_lists = Vec::new();
+            match segment.step {
+                FormulaStep::Sibling => candidate_lists.push(siblings.to_vec()),
+                FormulaStep::Child => {
+                    for (attribute, _) in current.iter() {
+                        candidate_lists.push(attribute.children.clone().unwrap_or_default());
+                    }
+                }
+                FormulaStep::FollowReference => {
+                    for (attribute, _) in current.iter() {
+                        let noun_id = match attribute.data {
+                            DataObject::NounReference(noun_id) => noun_id,
+                            DataObject::Null => continue,
+                            _ => anyhow::bail!(ClwmError::FormulaInputNotANounReference {
+                                reference: reference.text.clone()
+                            }),
+                        };
+                        // A hidden noun contributes nothing, as if the reference were empty.
+                        if !Self::is_noun_visible(&self.viewer, noun_id, transaction).await? {
+                            continue;
+                        }
+                        let mut noun_attributes = transaction
+                            .find_attribute_by_parent_noun_id(noun_id)
+                            .await?;
+                        noun_attributes.retain(|x| self.viewer.can_see(&x.visibility));
+                        for noun_attribute in noun_attributes.iter_mut() {
+                            self.populate_attribute_recursive(noun_attribute, transaction)
+                                .await?;
+                        }
+                        candidate_lists.push(noun_attributes);
+                    }
+                }
+            }
+
+            current = candidate_lists
+                .iter()
+                .flat_map(|list| {
+                    list.iter()
+                        .filter(|x| Some(x.attribute_type_id) == attribute_type_id)
+                        .map(|x| (x.clone(), list.clone()))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+        }
+        Ok(current)
+    }
+
     pub async fn populate_attribute(&mut self, attribute: &mut Attribute) -> anyhow::Result<()> {
         let transaction = self
             .data_interface
@@ -731,6 +4459,7 @@ impl Clwm {
         let mut found_attributes = transaction
             .find_attribute_by_parent_attribute_id(attribute_id)
             .await?;
+        found_attributes.retain(|x| self.viewer.can_see(&x.visibility));
 
         future::join_all(
             found_attributes
@@ -808,6 +4537,25 @@ fn is_data_of_data_def(
                 false
             }
         }
+        DataTypeDefinition::Map(key_type, value_type) => {
+            if let DataObject::Map(map) = data {
+                map.0.iter().enumerate().all(|(index, (key, value))| {
+                    is_data_of_data_def(key, key_type, false)
+                        && !map.0[..index].iter().any(|(other_key, _)| other_key == key)
+                        && is_data_of_data_def(value, value_type, allow_nulls)
+                })
+            } else {
+                false
+            }
+        }
+        DataTypeDefinition::Asset => matches!(data, DataObject::Asset(_)),
+        DataTypeDefinition::Dice => {
+            if let DataObject::Dice(expression) = data {
+                expression.parse::<DiceExpression>().is_ok()
+            } else {
+                false
+            }
+        }
         DataTypeDefinition::Custom(custom_type) => {
             if let DataObject::Custom(custom) = data {
                 custom.0.iter().all(|(key, x)| {
@@ -820,3 +4568,285 @@ fn is_data_of_data_def(
         }
     }
 }
+
+fn collect_dice_expressions<'a>(data: &'a DataObject, expressions: &mut Vec<&'a String>) {
+    match data {
+        DataObject::Dice(expression) => expressions.push(expression),
+        DataObject::Array(array) => array
+            .iter()
+            .for_each(|x| collect_dice_expressions(x, expressions)),
+        DataObject::Map(map) => map
+            .0
+            .iter()
+            .for_each(|(_, value)| collect_dice_expressions(value, expressions)),
+        DataObject::Custom(custom) => custom
+            .0
+            .values()
+            .for_each(|x| collect_dice_expressions(x, expressions)),
+        _ => {}
+    }
+}
+
+/// The text attribute history diffs data as. TOML has no top-level null, so
+/// the null held by computed attributes is recorded as no text at all.
+fn history_data(data: &DataObject) -> anyhow::Result<String> {
+    match data {
+        DataObject::Null => Ok(String::new()),
+        _ => Ok(toml::to_string(data)?),
+    }
+}
+
+fn collect_asset_references<'a>(data: &'a DataObject, references: &mut Vec<&'a AssetReference>) {
+    match data {
+        DataObject::Asset(reference) => references.push(reference),
+        DataObject::Array(array) => array
+            .iter()
+            .for_each(|x| collect_asset_references(x, references)),
+        DataObject::Map(map) => map
+            .0
+            .iter()
+            .for_each(|(_, value)| collect_asset_references(value, references)),
+        DataObject::Custom(custom) => custom
+            .0
+            .values()
+            .for_each(|x| collect_asset_references(x, references)),
+        _ => {}
+    }
+}
+
+fn collect_template_data(attributes: &[TemplateAttribute], data: &mut Vec<DataObject>) {
+    for attribute in attributes.iter() {
+        data.push(attribute.data.clone());
+        collect_template_data(&attribute.children, data);
+    }
+}
+
+fn collect_attribute_asset_references<'a>(
+    attributes: &'a [Attribute],
+    references: &mut Vec<&'a AssetReference>,
+) {
+    for attribute in attributes.iter() {
+        collect_asset_references(&attribute.data, references);
+        collect_attribute_asset_references(
+            attribute.children.as_deref().unwrap_or_default(),
+            references,
+        );
+    }
+}
+
+fn collect_attribute_ids(attributes: &[Attribute], attribute_ids: &mut Vec<i64>) {
+    for attribute in attributes.iter() {
+        attribute_ids.extend(attribute.attribute_id);
+        collect_attribute_ids(
+            attribute.children.as_deref().unwrap_or_default(),
+            attribute_ids,
+        );
+    }
+}
+
+fn remap_template_attributes(
+    attributes: &mut [TemplateAttribute],
+    attribute_type_ids: &HashMap<i64, i64>,
+    noun_ids: &HashMap<i64, i64>,
+) -> anyhow::Result<()> {
+    for attribute in attributes.iter_mut() {
+        attribute.attribute_type_id = mapped_id(
+            attribute_type_ids,
+            attribute.attribute_type_id,
+            ClwmError::AttributeTypeNotFound,
+        )?;
+        remap_noun_references(&mut attribute.data, noun_ids);
+        remap_template_attributes(&mut attribute.children, attribute_type_ids, noun_ids)?;
+    }
+    Ok(())
+}
+
+/// Looks up the id an imported record was given, failing for ids that are
+/// not in the bundle.
+fn mapped_id(ids: &HashMap<i64, i64>, id: i64, error: ClwmError) -> anyhow::Result<i64> {
+    match ids.get(&id) {
+        Some(mapped_id) => Ok(*mapped_id),
+        None => Err(error.into()),
+    }
+}
+
+/// Points references at the imported nouns. References to nouns that are not
+/// in the bundle, such as nouns hidden from the exporting viewer, become
+/// `Null` rather than pointing at whatever noun has that id here.
+fn remap_noun_references(data: &mut DataObject, noun_ids: &HashMap<i64, i64>) {
+    match data {
+        DataObject::NounReference(noun_id) => match noun_ids.get(noun_id) {
+            Some(mapped_id) => *noun_id = *mapped_id,
+            None => *data = DataObject::Null,
+        },
+        DataObject::Array(array) => array
+            .iter_mut()
+            .for_each(|x| remap_noun_references(x, noun_ids)),
+        DataObject::Map(map) => map.0.iter_mut().for_each(|(key, value)| {
+            remap_noun_references(key, noun_ids);
+            remap_noun_references(value, noun_ids);
+        }),
+        DataObject::Custom(custom) => custom
+            .0
+            .values_mut()
+            .for_each(|x| remap_noun_references(x, noun_ids)),
+        _ => {}
+    }
+}
+
+fn replace_noun_references(data: &mut DataObject, replacements: &HashMap<i64, i64>) {
+    match data {
+        DataObject::NounReference(noun_id) => {
+            if let Some(replacement) = replacements.get(noun_id) {
+                *noun_id = *replacement;
+            }
+        }
+        DataObject::Array(array) => array
+            .iter_mut()
+            .for_each(|x| replace_noun_references(x, replacements)),
+        DataObject::Map(map) => map.0.iter_mut().for_each(|(key, value)| {
+            replace_noun_references(key, replacements);
+            replace_noun_references(value, replacements);
+        }),
+        DataObject::Custom(custom) => custom
+            .0
+            .values_mut()
+            .for_each(|x| replace_noun_references(x, replacements)),
+        _ => {}
+    }
+}
+
+fn collect_noun_references(data: &DataObject, references: &mut Vec<i64>) {
+    match data {
+        DataObject::NounReference(noun_id) => references.push(*noun_id),
+        DataObject::Array(array) => array
+            .iter()
+            .for_each(|x| collect_noun_references(x, references)),
+        DataObject::Map(map) => map.0.iter().for_each(|(key, value)| {
+            collect_noun_references(key, references);
+            collect_noun_references(value, references);
+        }),
+        DataObject::Custom(custom) => custom
+            .0
+            .values()
+            .for_each(|x| collect_noun_references(x, references)),
+        _ => {}
+    }
+}
+
+fn fill_placeholders(
+    data: &DataObject,
+    placeholders: &HashMap<String, String>,
+) -> anyhow::Result<DataObject> {
+    Ok(match data {
+        DataObject::Text(text) => DataObject::Text(fill_placeholder_text(text, placeholders)?),
+        DataObject::LongText(text) => {
+            DataObject::LongText(fill_placeholder_text(text, placeholders)?)
+        }
+        DataObject::Dice(expression) => {
+            DataObject::Dice(fill_placeholder_text(expression, placeholders)?)
+        }
+        DataObject::Array(array) => DataObject::Array(
+            array
+                .iter()
+                .map(|x| fill_placeholders(x, placeholders))
+                .collect::<anyhow::Result<Vec<DataObject>>>()?,
+        ),
+        DataObject::Map(map) => DataObject::Map(MapDataObject(
+            map.0
+                .iter()
+                .map(|(key, value)| {
+                    Ok((
+                        fill_placeholders(key, placeholders)?,
+                        fill_placeholders(value, placeholders)?,
+                    ))
+                })
+                .collect::<anyhow::Result<Vec<(DataObject, DataObject)>>>()?,
+        )),
+        DataObject::Custom(custom) => DataObject::Custom(CustomDataObject(
+            custom
+                .0
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), fill_placeholders(value, placeholders)?)))
+                .collect::<anyhow::Result<HashMap<String, DataObject>>>()?,
+        )),
+        _ => data.clone(),
+    })
+}
+
+fn fill_placeholder_text(
+    text: &str,
+    placeholders: &HashMap<String, String>,
+) -> anyhow::Result<String> {
+    let mut filled = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let end = match rest[start..].find("}}") {
+            Some(end) => start + end,
+            None => break,
+        };
+        let placeholder = rest[start + 2..end].trim();
+        match placeholders.get(placeholder) {
+            Some(value) => {
+                filled.push_str(&rest[..start]);
+                filled.push_str(value);
+            }
+            None => anyhow::bail!(ClwmError::TemplatePlaceholderMissing {
+                placeholder: placeholder.to_owned()
+            }),
+        }
+        rest = &rest[end + 2..];
+    }
+    filled.push_str(rest);
+    Ok(filled)
+}
+
+fn optional_id_to_string(id: Option<i64>) -> String {
+    id.map(|id| id.to_string()).unwrap_or_default()
+}
+
+fn check_formula(formula: &Option<String>, data_def: &DataTypeDefinition) -> anyhow::Result<()> {
+    if let Some(formula) = formula {
+        parse_formula(formula)?;
+        if !matches!(
+            data_def,
+            DataTypeDefinition::Integer | DataTypeDefinition::Float
+        ) {
+            anyhow::bail!(ClwmError::FormulaRequiresNumericDataType)
+        }
+    }
+    Ok(())
+}
+
+fn is_valid_data_def(data_def: &DataTypeDefinition) -> bool {
+    match data_def {
+        DataTypeDefinition::Array(array_type) => is_valid_data_def(array_type),
+        DataTypeDefinition::Map(key_type, value_type) => {
+            matches!(
+                **key_type,
+                DataTypeDefinition::Text
+                    | DataTypeDefinition::Integer
+                    | DataTypeDefinition::Boolean
+                    | DataTypeDefinition::NounReference
+            ) && is_valid_data_def(value_type)
+        }
+        DataTypeDefinition::Custom(custom_type) => custom_type.0.values().all(is_valid_data_def),
+        _ => true,
+    }
+}
+
+fn parse_map_key(key: &str, key_def: &DataTypeDefinition) -> anyhow::Result<DataObject> {
+    let map_key = match key_def {
+        DataTypeDefinition::Text => Some(DataObject::Text(key.to_string())),
+        DataTypeDefinition::Integer => key.parse::<i64>().ok().map(DataObject::Integer),
+        DataTypeDefinition::Boolean => key.parse::<bool>().ok().map(DataObject::Boolean),
+        DataTypeDefinition::NounReference => key.parse::<i64>().ok().map(DataObject::NounReference),
+        _ => None,
+    };
+    match map_key {
+        Some(map_key) => Ok(map_key),
+        None => anyhow::bail!(ClwmError::MapKeyDoesNotMatchKeyType {
+            key: key.to_string()
+        }),
+    }
+}
@@ -46,4 +46,112 @@ pub enum ClwmError {
         "the provided parent attribute id does not match the parent attribute id of the attribute"
     )]
     ParentAttributeIdDoesNotMatch,
+    #[error("map keys must be Text, Integer, Boolean or NounReference")]
+    InvalidMapKeyType,
+    #[error("the data type of the provided attribute type is not a map")]
+    DataTypeIsNotAMap,
+    #[error("the key {key:?} does not match the key type of the map")]
+    MapKeyDoesNotMatchKeyType { key: String },
+    #[error("the provided asset could not be found")]
+    AssetNotFound,
+    #[error("the provided asset reference does not match the stored asset")]
+    AssetReferenceDoesNotMatch,
+    #[error("this content is already stored as asset {asset_hash} with mime type {mime_type:?}")]
+    AssetMimeTypeDoesNotMatch {
+        asset_hash: String,
+        mime_type: String,
+    },
+    #[error("the formula {formula:?} could not be parsed: {message}")]
+    FormulaParseError { formula: String, message: String },
+    #[error("the formula input [{reference}] is missing")]
+    FormulaInputMissing { reference: String },
+    #[error("the formula input [{reference}] is not an Integer or Float")]
+    FormulaInputWrongType { reference: String },
+    #[error("the formula input [{reference}] has {count} values but one was expected")]
+    FormulaInputNotSingular { reference: String, count: usize },
+    #[error("the formula input [{reference}] is not a noun reference")]
+    FormulaInputNotANounReference { reference: String },
+    #[error("the formula function {function:?} was called with the wrong number of arguments")]
+    FormulaFunctionNeedsArguments { function: String },
+    #[error("the formula divided by zero")]
+    FormulaDivisionByZero,
+    #[error("the formula of attribute type {attribute_type:?} depends on itself")]
+    FormulaCycle { attribute_type: String },
+    #[error("attribute types with a formula must have an Integer or Float data type")]
+    FormulaRequiresNumericDataType,
+    #[error("computed attributes can not have data")]
+    ComputedAttributeCannotHaveData,
+    #[error("the attribute type {attribute_type:?} is forbidden for noun type {noun_type:?}")]
+    AttributeTypeForbiddenForNounType {
+        attribute_type: String,
+        noun_type: String,
+    },
+    #[error("the attribute type {attribute_type:?} allows at most {max_count} attributes")]
+    AttributeTypeCardinalityExceeded {
+        attribute_type: String,
+        max_count: i64,
+    },
+    #[error("the provided attribute cardinality is not valid for the requirement")]
+    InvalidAttributeCardinality,
+    #[error("the noun type {noun_type:?} can not inherit from one of its own subtypes")]
+    NounTypeInheritanceCycle { noun_type: String },
+    #[error("the attribute type {attribute_type:?} can not have a default value")]
+    AttributeTypeCannotHaveDefault { attribute_type: String },
+    #[error("template not found")]
+    TemplateNotFound,
+    #[error("no value was provided for the template placeholder {{{{{placeholder}}}}}")]
+    TemplatePlaceholderMissing { placeholder: String },
+    #[error("the template {template:?} is for {template_noun_type:?} and {noun_type:?} is not one of its subtypes")]
+    TemplateNounTypeMismatch {
+        template: String,
+        template_noun_type: String,
+        noun_type: String,
+    },
+    #[error("roll table not found")]
+    RollTableNotFound,
+    #[error("the roll table is not valid: {message}")]
+    InvalidRollTable { message: String },
+    #[error("the dice expression {expression:?} is not valid")]
+    InvalidDiceExpression { expression: String },
+    #[error("roll tables can only be nested {max_depth} deep")]
+    RollTableTooDeep { max_depth: usize },
+    #[error("no nouns match the roll filter")]
+    RollNoMatchingNouns,
+    #[error("the noun {noun:?} can not be moved inside itself or one of its descendants")]
+    NounContainmentCycle { noun: String },
+    #[error("event not found")]
+    EventNotFound,
+    #[error("an event can not end before it starts")]
+    EventEndsBeforeStart,
+    #[error("event participant not found")]
+    EventParticipantNotFound,
+    #[error("calendar not found")]
+    CalendarNotFound,
+    #[error("the calendar is not valid: {message}")]
+    InvalidCalendar { message: String },
+    #[error("the date {date:?} is not valid in this calendar")]
+    InvalidWorldDate { date: String },
+    #[error("relationship type not found")]
+    RelationshipTypeNotFound,
+    #[error("the name {name:?} is already used by a relationship type")]
+    RelationshipTypeNameInUse { name: String },
+    #[error("relationship not found")]
+    RelationshipNotFound,
+    #[error("the relationship already exists")]
+    RelationshipAlreadyExists,
+    #[error("noun {noun_id} can only be {relationship_type:?} one noun")]
+    RelationshipCardinalityExceeded {
+        relationship_type: String,
+        noun_id: i64,
+    },
+    #[error("world bundle format version {version} is not supported")]
+    UnsupportedBundleVersion { version: i64 },
+    #[error("the front matter of {path:?} is not valid: {message}")]
+    InvalidFrontMatter { path: String, message: String },
+    #[error("a unit of work is already open")]
+    UnitOfWorkAlreadyOpen,
+    #[error("no unit of work is open")]
+    NoUnitOfWorkOpen,
+    #[error("world schema version {version} is newer than this version supports ({supported})")]
+    UnsupportedSchemaVersion { version: i64, supported: i64 },
 }
@@ -2,18 +2,36 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 use crate::model::{
-    Attribute, AttributeHistory, AttributeType, AttributeTypeHistory, DataType, Noun, NounHistory,
-    NounType, NounTypeHistory,
+    Asset, Attribute, AttributeHistory, AttributeType, AttributeTypeHistory, Calendar, ChangeSet,
+    DataType, Event, EventHistory, EventParticipant, Noun, NounHistory, NounReferenceEdge,
+    NounType, NounTypeAttributeRule, NounTypeHistory, Relationship, RelationshipHistory,
+    RelationshipType, RollTable, Template, TimelineFilter, TraversalOptions, TraversalResult,
 };
 
 #[async_trait]
 pub trait DataInterface {
     async fn init(&mut self) -> anyhow::Result<()>;
 
+    async fn copy_to(&self, path: String) -> anyhow::Result<String>;
+
+    /// Rebuilds the database so deleted rows no longer remain in the file.
+    async fn compact(&self) -> anyhow::Result<()>;
+
+    /// Disconnects and moves the database to `path`, returning its new url.
+    async fn move_to(&mut self, path: String) -> anyhow::Result<String>;
+
     async fn create_transaction(
         &self,
         change_source: String,
     ) -> anyhow::Result<Box<dyn DataInterfaceAccessTransaction>>;
+
+    async fn begin_unit_of_work(&mut self, change_source: String) -> anyhow::Result<()>;
+
+    async fn commit_unit_of_work(&mut self) -> anyhow::Result<()>;
+
+    async fn rollback_unit_of_work(&mut self) -> anyhow::Result<()>;
+
+    fn in_unit_of_work(&self) -> bool;
 }
 
 #[async_trait]
@@ -21,18 +39,39 @@ pub trait DataInterfaceAccessTransaction {
     async fn commit(&self) -> anyhow::Result<()>;
     async fn rollback(&self) -> anyhow::Result<()>;
 
+    async fn new_change_set(&self, change_set: ChangeSet) -> anyhow::Result<ChangeSet>;
+
+    async fn find_change_set_by_all(&self) -> anyhow::Result<Vec<ChangeSet>>;
+
     async fn new_noun(&self, noun: Noun) -> anyhow::Result<Noun>;
 
     async fn new_noun_history(&self, noun_history: NounHistory) -> anyhow::Result<NounHistory>;
 
+    async fn find_noun_history_by_all(&self) -> anyhow::Result<Vec<NounHistory>>;
+
+    async fn find_noun_history_by_noun_id(&self, noun_id: i64) -> anyhow::Result<Vec<NounHistory>>;
+
     async fn update_noun(&self, noun: Noun) -> anyhow::Result<Noun>;
 
+    async fn delete_noun(&self, noun_id: i64) -> anyhow::Result<()>;
+
     async fn find_noun_by_name(&self, name: String) -> anyhow::Result<Vec<Noun>>;
 
     async fn find_noun_by_all(&self) -> anyhow::Result<Vec<Noun>>;
 
+    async fn find_noun_by_noun_type(&self, noun_type: String) -> anyhow::Result<Vec<Noun>>;
+
+    async fn find_noun_by_noun_type_including_subtypes(
+        &self,
+        noun_type: String,
+    ) -> anyhow::Result<Vec<Noun>>;
+
     async fn find_noun_by_id(&self, id: i64) -> anyhow::Result<Option<Noun>>;
 
+    async fn find_noun_ancestors_by_id(&self, id: i64) -> anyhow::Result<Vec<Noun>>;
+
+    async fn find_noun_descendants_by_id(&self, id: i64) -> anyhow::Result<Vec<Noun>>;
+
     async fn new_noun_type(&self, noun_type: NounType) -> anyhow::Result<NounType>;
 
     async fn new_noun_type_history(
@@ -40,6 +79,8 @@ pub trait DataInterfaceAccessTransaction {
         noun_type_history: NounTypeHistory,
     ) -> anyhow::Result<NounTypeHistory>;
 
+    async fn find_noun_type_history_by_all(&self) -> anyhow::Result<Vec<NounTypeHistory>>;
+
     async fn update_noun_type(&self, noun_type: NounType) -> anyhow::Result<NounType>;
 
     async fn find_noun_type_by_noun_type(&self, noun_type: String)
@@ -49,6 +90,134 @@ pub trait DataInterfaceAccessTransaction {
 
     async fn find_noun_type_by_id(&self, noun_type_id: i64) -> anyhow::Result<Option<NounType>>;
 
+    async fn set_noun_type_attribute_rule(
+        &self,
+        noun_type_attribute_rule: NounTypeAttributeRule,
+    ) -> anyhow::Result<NounTypeAttributeRule>;
+
+    async fn delete_noun_type_attribute_rule(
+        &self,
+        noun_type_id: i64,
+        attribute_type_id: i64,
+    ) -> anyhow::Result<()>;
+
+    async fn find_noun_type_attribute_rule_by_noun_type_id(
+        &self,
+        noun_type_id: i64,
+    ) -> anyhow::Result<Vec<NounTypeAttributeRule>>;
+
+    async fn set_template(&self, template: Template) -> anyhow::Result<Template>;
+
+    async fn delete_template(&self, template_name: String) -> anyhow::Result<()>;
+
+    async fn find_template_by_name(
+        &self,
+        template_name: String,
+    ) -> anyhow::Result<Option<Template>>;
+
+    async fn find_template_by_all(&self) -> anyhow::Result<Vec<Template>>;
+
+    async fn new_relationship_type(
+        &self,
+        relationship_type: RelationshipType,
+    ) -> anyhow::Result<RelationshipType>;
+
+    async fn find_relationship_type_by_name(
+        &self,
+        name: String,
+    ) -> anyhow::Result<Vec<RelationshipType>>;
+
+    async fn find_relationship_type_by_all(&self) -> anyhow::Result<Vec<RelationshipType>>;
+
+    async fn new_relationship(&self, relationship: Relationship) -> anyhow::Result<Relationship>;
+
+    async fn delete_relationship(&self, relationship_id: i64) -> anyhow::Result<()>;
+
+    async fn find_relationship_by_noun_id(&self, noun_id: i64)
+        -> anyhow::Result<Vec<Relationship>>;
+
+    async fn find_relationship_by_all(&self) -> anyhow::Result<Vec<Relationship>>;
+
+    async fn new_relationship_history(
+        &self,
+        relationship_history: RelationshipHistory,
+    ) -> anyhow::Result<RelationshipHistory>;
+
+    async fn find_relationship_history_by_all(&self) -> anyhow::Result<Vec<RelationshipHistory>>;
+
+    async fn new_noun_reference(
+        &self,
+        noun_reference: NounReferenceEdge,
+    ) -> anyhow::Result<NounReferenceEdge>;
+
+    async fn delete_noun_reference_by_attribute_id(&self, attribute_id: i64) -> anyhow::Result<()>;
+
+    async fn find_noun_reference_by_all(&self) -> anyhow::Result<Vec<NounReferenceEdge>>;
+
+    /// Walks outward from a noun, never entering a hidden noun and never
+    /// following a reference held by a hidden attribute.
+    async fn traverse_from_noun(
+        &self,
+        noun_id: i64,
+        options: TraversalOptions,
+        hidden_noun_ids: Vec<i64>,
+        hidden_attribute_ids: Vec<i64>,
+    ) -> anyhow::Result<Vec<TraversalResult>>;
+
+    async fn set_event(&self, event: Event) -> anyhow::Result<Event>;
+
+    async fn delete_event(&self, noun_id: i64) -> anyhow::Result<()>;
+
+    async fn find_event_by_noun_id(&self, noun_id: i64) -> anyhow::Result<Option<Event>>;
+
+    async fn find_event_by_timeline_filter(
+        &self,
+        filter: TimelineFilter,
+    ) -> anyhow::Result<Vec<Event>>;
+
+    async fn new_event_history(&self, event_history: EventHistory) -> anyhow::Result<EventHistory>;
+
+    async fn find_event_history_by_all(&self) -> anyhow::Result<Vec<EventHistory>>;
+
+    async fn new_event_participant(
+        &self,
+        event_noun_id: i64,
+        participant: EventParticipant,
+    ) -> anyhow::Result<EventParticipant>;
+
+    async fn delete_event_participant(
+        &self,
+        event_noun_id: i64,
+        participant: EventParticipant,
+    ) -> anyhow::Result<()>;
+
+    async fn find_event_participant_by_event_noun_id(
+        &self,
+        event_noun_id: i64,
+    ) -> anyhow::Result<Vec<EventParticipant>>;
+
+    async fn set_roll_table(&self, roll_table: RollTable) -> anyhow::Result<RollTable>;
+
+    async fn delete_roll_table(&self, roll_table_name: String) -> anyhow::Result<()>;
+
+    async fn find_roll_table_by_name(
+        &self,
+        roll_table_name: String,
+    ) -> anyhow::Result<Option<RollTable>>;
+
+    async fn find_roll_table_by_all(&self) -> anyhow::Result<Vec<RollTable>>;
+
+    async fn set_calendar(&self, calendar: Calendar) -> anyhow::Result<Calendar>;
+
+    async fn delete_calendar(&self, calendar_name: String) -> anyhow::Result<()>;
+
+    async fn find_calendar_by_name(
+        &self,
+        calendar_name: String,
+    ) -> anyhow::Result<Option<Calendar>>;
+
+    async fn find_calendar_by_all(&self) -> anyhow::Result<Vec<Calendar>>;
+
     async fn new_data_type(&self, data_type: DataType) -> anyhow::Result<DataType>;
 
     async fn find_data_type_latest_by_name(&self, name: String)
@@ -70,6 +239,9 @@ pub trait DataInterfaceAccessTransaction {
         attribute_type_history: AttributeTypeHistory,
     ) -> anyhow::Result<AttributeTypeHistory>;
 
+    async fn find_attribute_type_history_by_all(&self)
+        -> anyhow::Result<Vec<AttributeTypeHistory>>;
+
     async fn update_attribute_type(
         &self,
         attribute_type: AttributeType,
@@ -86,10 +258,17 @@ pub trait DataInterfaceAccessTransaction {
 
     async fn update_attribute(&self, attribute: Attribute) -> anyhow::Result<Attribute>;
 
+    async fn delete_attribute(&self, attribute_id: i64) -> anyhow::Result<()>;
+
     async fn find_attribute_by_all(&self) -> anyhow::Result<Vec<Attribute>>;
 
     async fn find_attribute_by_id(&self, id: i64) -> anyhow::Result<Option<Attribute>>;
 
+    async fn find_attribute_by_attribute_type_id(
+        &self,
+        attribute_type_id: i64,
+    ) -> anyhow::Result<Vec<Attribute>>;
+
     async fn find_attribute_by_parent_noun_id(
         &self,
         parent_noun_id: i64,
@@ -116,6 +295,34 @@ pub trait DataInterfaceAccessTransaction {
         &self,
         attribute_history: AttributeHistory,
     ) -> anyhow::Result<AttributeHistory>;
+
+    async fn find_attribute_history_by_all(&self) -> anyhow::Result<Vec<AttributeHistory>>;
+
+    async fn find_attribute_history_by_attribute_id(
+        &self,
+        attribute_id: i64,
+    ) -> anyhow::Result<Vec<AttributeHistory>>;
+
+    async fn new_asset(&self, asset: Asset, content: Vec<u8>) -> anyhow::Result<Asset>;
+
+    async fn delete_asset(&self, asset_hash: String) -> anyhow::Result<()>;
+
+    async fn find_asset_by_hash(&self, asset_hash: String) -> anyhow::Result<Option<Asset>>;
+
+    async fn find_asset_by_all(&self) -> anyhow::Result<Vec<Asset>>;
+
+    async fn find_asset_content_by_hash(
+        &self,
+        asset_hash: String,
+    ) -> anyhow::Result<Option<Vec<u8>>>;
+
+    async fn delete_all_history(&self) -> anyhow::Result<()>;
+
+    async fn delete_unreferenced_change_sets(&self) -> anyhow::Result<()>;
+
+    /// Brings a world created by an older version up to the current schema,
+    /// returning whether anything had to change.
+    async fn upgrade_schema(&self) -> anyhow::Result<bool>;
 }
 
 #[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -1,4 +1,7 @@
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex as StdMutex,
+};
 
 use anyhow::Error;
 use async_trait::async_trait;
@@ -7,16 +10,72 @@ use sqlx::{Pool, Sqlite, SqlitePool, Transaction};
 use tokio::sync::Mutex;
 
 use crate::{
+    clwm_error::ClwmError,
     data_interface::{DataInterface, DataInterfaceAccessTransaction},
     model::{
-        Attribute, AttributeHistory, AttributeType, AttributeTypeHistory, DataType, Noun,
-        NounHistory, NounType, NounTypeHistory,
+        Asset, Attribute, AttributeHistory, AttributeRequirement, AttributeType,
+        AttributeTypeHistory, Calendar, ChangeSet, DataType, Event, EventHistory, EventParticipant,
+        EventRole, Noun, NounHistory, NounReferenceEdge, NounType, NounTypeAttributeRule,
+        NounTypeHistory, Relationship, RelationshipCardinality, RelationshipHistory,
+        RelationshipType, RollTable, Template, TimelineFilter, TraversalDirection,
+        TraversalOptions, TraversalResult, Visibility,
     },
 };
 
+/// The schema version create_schema.sql records in `PRAGMA user_version`.
+const SCHEMA_VERSION: i64 = 1;
+
+type SharedTransaction<'a> = Arc<Mutex<Option<Transaction<'a, Sqlite>>>>;
+
+/// Savepoints whose handles were dropped without a commit or rollback. Drop
+/// can not run queries, so the unit of work rolls them back before it hands
+/// out the next savepoint or commits.
+type AbandonedSavepoints = Arc<StdMutex<Vec<usize>>>;
+
+/// Rolls back the abandoned savepoints opened after `nested_in`, or all of them.
+/// Savepoints nest, so releasing or rolling back one would also drop any still
+/// open inside it.
+async fn roll_back_abandoned_savepoints(
+    abandoned_savepoints: &AbandonedSavepoints,
+    transaction: &SharedTransaction<'_>,
+    nested_in: Option<usize>,
+) -> anyhow::Result<()> {
+    let mut nested = {
+        let mut abandoned = abandoned_savepoints.lock().unwrap();
+        let (nested, enclosing) = abandoned
+            .iter()
+            .partition(|savepoint| nested_in.is_none_or(|outer| **savepoint > outer));
+        *abandoned = enclosing;
+        nested
+    };
+    // Innermost first, so every savepoint is still open when it is reached.
+    nested.sort_unstable_by(|a: &usize, b| b.cmp(a));
+    let mut transaction = transaction.lock().await;
+    let transaction = transaction
+        .as_mut()
+        .ok_or(anyhow::anyhow!("Already taken"))?;
+    for savepoint in nested {
+        sqlx::query(&format!(
+            "ROLLBACK TO SAVEPOINT clwm_{0}; RELEASE SAVEPOINT clwm_{0}",
+            savepoint
+        ))
+        .execute(&mut *transaction)
+        .await?;
+    }
+    Ok(())
+}
+
 pub struct DataInterfaceSQLite {
     url: String,
     connection: Option<Pool<Sqlite>>,
+    unit_of_work: Option<UnitOfWorkSQLite>,
+}
+
+struct UnitOfWorkSQLite {
+    transaction: SharedTransaction<'static>,
+    change_set_id: i64,
+    savepoint_count: AtomicUsize,
+    abandoned_savepoints: AbandonedSavepoints,
 }
 
 impl DataInterfaceSQLite {
@@ -24,22 +83,14 @@ impl DataInterfaceSQLite {
         DataInterfaceSQLite {
             url: url,
             connection: None,
+            unit_of_work: None,
         }
     }
-}
-
-#[async_trait]
-impl DataInterface for DataInterfaceSQLite {
-    async fn init(&mut self) -> anyhow::Result<()> {
-        let pool = SqlitePool::connect(&self.url).await?;
-        self.connection = Some(pool);
-        Ok(())
-    }
 
-    async fn create_transaction(
+    async fn begin_transaction(
         &self,
         change_source: String,
-    ) -> anyhow::Result<Box<dyn DataInterfaceAccessTransaction>> {
+    ) -> anyhow::Result<(Transaction<'static, Sqlite>, i64)> {
         let mut transaction = self
             .connection
             .clone()
@@ -66,23 +117,175 @@ impl DataInterface for DataInterfaceSQLite {
         .fetch_one(&mut transaction)
         .await?;
 
+        Ok((transaction, record.change_set_id))
+    }
+
+    async fn take_unit_of_work(&mut self) -> anyhow::Result<Transaction<'static, Sqlite>> {
+        let unit_of_work = self
+            .unit_of_work
+            .take()
+            .ok_or(ClwmError::NoUnitOfWorkOpen)?;
+        let transaction = unit_of_work
+            .transaction
+            .lock()
+            .await
+            .take()
+            .ok_or(anyhow::anyhow!("Already taken"))?;
+        Ok(transaction)
+    }
+}
+
+#[async_trait]
+impl DataInterface for DataInterfaceSQLite {
+    async fn init(&mut self) -> anyhow::Result<()> {
+        let pool = SqlitePool::connect(&self.url).await?;
+        self.connection = Some(pool);
+        Ok(())
+    }
+
+    async fn copy_to(&self, path: String) -> anyhow::Result<String> {
+        let connection = self
+            .connection
+            .clone()
+            .ok_or(anyhow::anyhow!("Not connected to a database!"))?;
+        sqlx::query_file!("sqlite_sqls/maint/export/copy_to.sql", path)
+            .execute(&connection)
+            .await?;
+        Ok(format!("sqlite://{}", path))
+    }
+
+    async fn compact(&self) -> anyhow::Result<()> {
+        let connection = self
+            .connection
+            .clone()
+            .ok_or(anyhow::anyhow!("Not connected to a database!"))?;
+        sqlx::query_file!("sqlite_sqls/maint/export/compact.sql")
+            .execute(&connection)
+            .await?;
+        Ok(())
+    }
+
+    async fn move_to(&mut self, path: String) -> anyhow::Result<String> {
+        if let Some(connection) = self.connection.take() {
+            connection.close().await;
+        }
+        let current_path = self.url.trim_start_matches("sqlite://");
+        let current_path = current_path.split('?').next().unwrap_or(current_path);
+        std::fs::rename(current_path, &path)?;
+        self.url = format!("sqlite://{}", path);
+        Ok(self.url.clone())
+    }
+
+    async fn create_transaction(
+        &self,
+        change_source: String,
+    ) -> anyhow::Result<Box<dyn DataInterfaceAccessTransaction>> {
+        if let Some(unit_of_work) = &self.unit_of_work {
+            roll_back_abandoned_savepoints(
+                &unit_of_work.abandoned_savepoints,
+                &unit_of_work.transaction,
+                None,
+            )
+            .await?;
+            let savepoint = unit_of_work.savepoint_count.fetch_add(1, Ordering::Relaxed);
+            sqlx::query(&format!("SAVEPOINT clwm_{}", savepoint))
+                .execute(
+                    unit_of_work
+                        .transaction
+                        .lock()
+                        .await
+                        .as_mut()
+                        .ok_or(anyhow::anyhow!("Already taken"))?,
+                )
+                .await?;
+            return Ok(Box::new(Arc::new(Mutex::new(
+                DataInterfaceTransactionSQLite {
+                    transaction: unit_of_work.transaction.clone(),
+                    change_set_id: unit_of_work.change_set_id,
+                    savepoint: Some(SavepointSQLite {
+                        savepoint,
+                        abandoned_savepoints: unit_of_work.abandoned_savepoints.clone(),
+                        finished: false,
+                    }),
+                },
+            ))));
+        }
+        let (transaction, change_set_id) = self.begin_transaction(change_source).await?;
         Ok(Box::new(Arc::new(Mutex::new(
             DataInterfaceTransactionSQLite {
-                transaction: Some(transaction),
-                change_set_id: record.change_set_id,
+                transaction: Arc::new(Mutex::new(Some(transaction))),
+                change_set_id,
+                savepoint: None,
             },
         ))))
     }
+
+    async fn begin_unit_of_work(&mut self, change_source: String) -> anyhow::Result<()> {
+        if self.unit_of_work.is_some() {
+            anyhow::bail!(ClwmError::UnitOfWorkAlreadyOpen);
+        }
+        let (transaction, change_set_id) = self.begin_transaction(change_source).await?;
+        self.unit_of_work = Some(UnitOfWorkSQLite {
+            transaction: Arc::new(Mutex::new(Some(transaction))),
+            change_set_id,
+            savepoint_count: AtomicUsize::new(0),
+            abandoned_savepoints: Arc::new(StdMutex::new(Vec::new())),
+        });
+        Ok(())
+    }
+
+    async fn commit_unit_of_work(&mut self) -> anyhow::Result<()> {
+        if let Some(unit_of_work) = &self.unit_of_work {
+            roll_back_abandoned_savepoints(
+                &unit_of_work.abandoned_savepoints,
+                &unit_of_work.transaction,
+                None,
+            )
+            .await?;
+        }
+        self.take_unit_of_work().await?.commit().await?;
+        Ok(())
+    }
+
+    async fn rollback_unit_of_work(&mut self) -> anyhow::Result<()> {
+        self.take_unit_of_work().await?.rollback().await?;
+        Ok(())
+    }
+
+    fn in_unit_of_work(&self) -> bool {
+        self.unit_of_work.is_some()
+    }
 }
 
+/// A transaction handle. Inside a unit of work every handle shares the unit's
+/// transaction and commits or rolls back its own savepoint instead.
 struct DataInterfaceTransactionSQLite<'a> {
-    transaction: Option<Transaction<'a, Sqlite>>,
+    transaction: SharedTransaction<'a>,
     change_set_id: i64,
+    savepoint: Option<SavepointSQLite>,
+}
+
+struct SavepointSQLite {
+    savepoint: usize,
+    abandoned_savepoints: AbandonedSavepoints,
+    finished: bool,
+}
+
+impl Drop for SavepointSQLite {
+    fn drop(&mut self) {
+        if !self.finished {
+            if let Ok(mut abandoned) = self.abandoned_savepoints.lock() {
+                abandoned.push(self.savepoint);
+            }
+        }
+    }
 }
 
 macro_rules! data_transaction {
     ($dit:ident) => {
         $dit.transaction
+            .lock()
+            .await
             .as_mut()
             .ok_or(anyhow::anyhow!("Already taken"))?
     };
@@ -91,36 +294,134 @@ macro_rules! data_transaction {
 #[async_trait]
 impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite<'_>>> {
     async fn commit(&self) -> anyhow::Result<()> {
-        self.lock()
-            .await
-            .transaction
-            .take()
-            .ok_or(anyhow::anyhow!("Already taken"))?
-            .commit()
-            .await?;
+        let mut data_interface_transaction = self.lock().await;
+        match data_interface_transaction
+            .savepoint
+            .as_ref()
+            .map(|savepoint| (savepoint.savepoint, savepoint.abandoned_savepoints.clone()))
+        {
+            Some((savepoint, abandoned_savepoints)) => {
+                roll_back_abandoned_savepoints(
+                    &abandoned_savepoints,
+                    &data_interface_transaction.transaction,
+                    Some(savepoint),
+                )
+                .await?;
+                sqlx::query(&format!("RELEASE SAVEPOINT clwm_{}", savepoint))
+                    .execute(data_transaction!(data_interface_transaction))
+                    .await?;
+                data_interface_transaction
+                    .savepoint
+                    .as_mut()
+                    .unwrap()
+                    .finished = true;
+            }
+            None => {
+                data_interface_transaction
+                    .transaction
+                    .lock()
+                    .await
+                    .take()
+                    .ok_or(anyhow::anyhow!("Already taken"))?
+                    .commit()
+                    .await?;
+            }
+        }
         Ok(())
     }
 
     async fn rollback(&self) -> anyhow::Result<()> {
-        self.lock()
-            .await
-            .transaction
-            .take()
-            .ok_or(anyhow::anyhow!("Already taken"))?
-            .rollback()
-            .await?;
+        let mut data_interface_transaction = self.lock().await;
+        match data_interface_transaction
+            .savepoint
+            .as_ref()
+            .map(|savepoint| (savepoint.savepoint, savepoint.abandoned_savepoints.clone()))
+        {
+            Some((savepoint, abandoned_savepoints)) => {
+                roll_back_abandoned_savepoints(
+                    &abandoned_savepoints,
+                    &data_interface_transaction.transaction,
+                    Some(savepoint),
+                )
+                .await?;
+                sqlx::query(&format!(
+                    "ROLLBACK TO SAVEPOINT clwm_{0}; RELEASE SAVEPOINT clwm_{0}",
+                    savepoint
+                ))
+                .execute(data_transaction!(data_interface_transaction))
+                .await?;
+                data_interface_transaction
+                    .savepoint
+                    .as_mut()
+                    .unwrap()
+                    .finished = true;
+            }
+            None => {
+                data_interface_transaction
+                    .transaction
+                    .lock()
+                    .await
+                    .take()
+                    .ok_or(anyhow::anyhow!("Already taken"))?
+                    .rollback()
+                    .await?;
+            }
+        }
         Ok(())
     }
 
+    async fn new_change_set(&self, change_set: ChangeSet) -> anyhow::Result<ChangeSet> {
+        let data_interface_transaction = self.lock().await;
+        let change_date = change_set
+            .change_date
+            .map_or(Utc::now().timestamp(), |change_date| {
+                change_date.timestamp()
+            });
+        let id = sqlx::query_file!(
+            "sqlite_sqls/change_set/new.sql",
+            change_date,
+            change_set.change_source
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?
+        .last_insert_rowid();
+        let change_set_record = sqlx::query_file!("sqlite_sqls/change_set/find/by_row_id.sql", id)
+            .fetch_one(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(ChangeSet {
+            change_set_id: Some(change_set_record.change_set_id),
+            change_date: Some(Utc.timestamp_opt(change_set_record.change_date, 0).unwrap()),
+            change_source: change_set_record.change_source,
+        })
+    }
+
+    async fn find_change_set_by_all(&self) -> anyhow::Result<Vec<ChangeSet>> {
+        let data_interface_transaction = self.lock().await;
+        let change_set_records = sqlx::query_file!("sqlite_sqls/change_set/find/by_all.sql")
+            .fetch_all(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(change_set_records
+            .into_iter()
+            .map(|change_set_record| ChangeSet {
+                change_set_id: Some(change_set_record.change_set_id),
+                change_date: Some(Utc.timestamp_opt(change_set_record.change_date, 0).unwrap()),
+                change_source: change_set_record.change_source,
+            })
+            .collect())
+    }
+
     async fn new_noun(&self, noun: Noun) -> anyhow::Result<Noun> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let change_set_id = data_interface_transaction.change_set_id;
+        let visibility = noun.visibility.to_string();
         let id = sqlx::query_file!(
             "sqlite_sqls/noun/new.sql",
             noun.name,
             change_set_id,
             noun.noun_type,
-            noun.metadata
+            noun.parent_noun_id,
+            noun.metadata,
+            visibility
         )
         .execute(data_transaction!(data_interface_transaction))
         .await?
@@ -134,21 +435,27 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
             last_changed: Some(Utc.timestamp_opt(noun_record.change_date, 0).unwrap()),
             name: noun_record.name,
             noun_type: noun_record.noun_type,
+            parent_noun_id: noun_record.parent_noun_id,
             metadata: noun_record.metadata,
+            visibility: noun_record.visibility.parse::<Visibility>()?,
             attributes: None,
         })
     }
 
     async fn new_noun_history(&self, noun_history: NounHistory) -> anyhow::Result<NounHistory> {
-        let mut data_interface_transaction = self.lock().await;
-        let change_set_id = data_interface_transaction.change_set_id;
+        let data_interface_transaction = self.lock().await;
+        let change_set_id = noun_history
+            .change_set_id
+            .unwrap_or(data_interface_transaction.change_set_id);
         let id = sqlx::query_file!(
             "sqlite_sqls/noun/history/new.sql",
             noun_history.noun_id,
             change_set_id,
             noun_history.diff_name,
             noun_history.diff_noun_type,
-            noun_history.diff_metadata
+            noun_history.diff_parent_noun_id,
+            noun_history.diff_metadata,
+            noun_history.diff_visibility
         )
         .execute(data_transaction!(data_interface_transaction))
         .await?
@@ -160,26 +467,79 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
                 .await?;
         Ok(NounHistory {
             noun_id: noun_history_record.noun_id,
+            change_set_id: Some(noun_history_record.change_set_id),
             change_date: Some(
                 Utc.timestamp_opt(noun_history_record.change_date, 0)
                     .unwrap(),
             ),
             diff_name: noun_history_record.diff_name,
             diff_noun_type: noun_history_record.diff_noun_type,
+            diff_parent_noun_id: noun_history_record.diff_parent_noun_id,
             diff_metadata: noun_history_record.diff_metadata,
+            diff_visibility: noun_history_record.diff_visibility,
         })
     }
 
+    async fn find_noun_history_by_all(&self) -> anyhow::Result<Vec<NounHistory>> {
+        let data_interface_transaction = self.lock().await;
+        let noun_history_records = sqlx::query_file!("sqlite_sqls/noun/history/find/by_all.sql")
+            .fetch_all(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(noun_history_records
+            .into_iter()
+            .map(|noun_history_record| NounHistory {
+                noun_id: noun_history_record.noun_id,
+                change_set_id: Some(noun_history_record.change_set_id),
+                change_date: Some(
+                    Utc.timestamp_opt(noun_history_record.change_date, 0)
+                        .unwrap(),
+                ),
+                diff_name: noun_history_record.diff_name,
+                diff_noun_type: noun_history_record.diff_noun_type,
+                diff_parent_noun_id: noun_history_record.diff_parent_noun_id,
+                diff_metadata: noun_history_record.diff_metadata,
+                diff_visibility: noun_history_record.diff_visibility,
+            })
+            .collect())
+    }
+
+    async fn find_noun_history_by_noun_id(&self, noun_id: i64) -> anyhow::Result<Vec<NounHistory>> {
+        let data_interface_transaction = self.lock().await;
+        let noun_history_records =
+            sqlx::query_file!("sqlite_sqls/noun/history/find/by_noun_id.sql", noun_id)
+                .fetch_all(data_transaction!(data_interface_transaction))
+                .await?;
+        Ok(noun_history_records
+            .into_iter()
+            .map(|noun_history_record| NounHistory {
+                noun_id: noun_history_record.noun_id,
+                change_set_id: Some(noun_history_record.change_set_id),
+                change_date: Some(
+                    Utc.timestamp_opt(noun_history_record.change_date, 0)
+                        .unwrap(),
+                ),
+                diff_name: noun_history_record.diff_name,
+                diff_noun_type: noun_history_record.diff_noun_type,
+                diff_parent_noun_id: noun_history_record.diff_parent_noun_id,
+                diff_metadata: noun_history_record.diff_metadata,
+                diff_visibility: noun_history_record.diff_visibility,
+            })
+            .collect())
+    }
+
     async fn update_noun(&self, noun: Noun) -> anyhow::Result<Noun> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let change_set_id = data_interface_transaction.change_set_id;
         let noun_id = noun.noun_id.ok_or(anyhow::anyhow!("No ID"))?;
+        let visibility = noun.visibility.to_string();
         sqlx::query_file!(
             "sqlite_sqls/noun/update.sql",
             noun.name,
             change_set_id,
             noun.noun_type,
+            noun.parent_noun_id,
             noun.metadata,
+            visibility,
             noun_id
         )
         .execute(data_transaction!(data_interface_transaction))
@@ -194,53 +554,130 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
             last_changed: Some(Utc.timestamp_opt(noun_record.change_date, 0).unwrap()),
             name: noun_record.name,
             noun_type: noun_record.noun_type,
+            parent_noun_id: noun_record.parent_noun_id,
             metadata: noun_record.metadata,
+            visibility: noun_record.visibility.parse::<Visibility>()?,
             attributes: None,
         })
     }
 
-    async fn find_noun_by_name(&self, name: String) -> anyhow::Result<Vec<Noun>> {
-        let mut data_interface_transaction = self.lock().await;
+    async fn delete_noun(&self, noun_id: i64) -> anyhow::Result<()> {
+        let data_interface_transaction = self.lock().await;
+        sqlx::query_file!("sqlite_sqls/noun/delete.sql", noun_id)
+            .execute(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(())
+    }
 
-        let noun_records = sqlx::query_file!("sqlite_sqls/noun/find/by_name.sql", name)
+    async fn find_noun_by_name(&self, name: String) -> anyhow::Result<Vec<Noun>> {
+        let data_interface_transaction = self.lock().await;
+
+        // Names are matched as substrings, so LIKE wildcards in them are escaped.
+        let pattern = name
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        let noun_records = sqlx::query_file!("sqlite_sqls/noun/find/by_name.sql", pattern)
             .fetch_all(data_transaction!(data_interface_transaction))
             .await?;
 
-        Ok(noun_records
+        noun_records
             .iter()
-            .map(|noun_record| Noun {
-                noun_id: Some(noun_record.noun_id),
-                last_changed: Some(Utc.timestamp_opt(noun_record.change_date, 0).unwrap()),
-                name: noun_record.name.to_string(),
-                noun_type: noun_record.noun_type.to_string(),
-                metadata: noun_record.metadata.to_string(),
-                attributes: None,
+            .map(|noun_record| {
+                Ok(Noun {
+                    noun_id: Some(noun_record.noun_id),
+                    last_changed: Some(Utc.timestamp_opt(noun_record.change_date, 0).unwrap()),
+                    name: noun_record.name.to_string(),
+                    noun_type: noun_record.noun_type.to_string(),
+                    parent_noun_id: noun_record.parent_noun_id,
+                    metadata: noun_record.metadata.to_string(),
+                    visibility: noun_record.visibility.parse::<Visibility>()?,
+                    attributes: None,
+                })
             })
-            .collect())
+            .collect::<anyhow::Result<Vec<Noun>>>()
     }
 
     async fn find_noun_by_all(&self) -> anyhow::Result<Vec<Noun>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
 
         let noun_records = sqlx::query_file!("sqlite_sqls/noun/find/by_all.sql")
             .fetch_all(data_transaction!(data_interface_transaction))
             .await?;
 
-        Ok(noun_records
+        noun_records
             .iter()
-            .map(|noun_record| Noun {
-                noun_id: Some(noun_record.noun_id),
-                last_changed: Some(Utc.timestamp_opt(noun_record.change_date, 0).unwrap()),
-                name: noun_record.name.to_string(),
-                noun_type: noun_record.noun_type.to_string(),
-                metadata: noun_record.metadata.to_string(),
-                attributes: None,
+            .map(|noun_record| {
+                Ok(Noun {
+                    noun_id: Some(noun_record.noun_id),
+                    last_changed: Some(Utc.timestamp_opt(noun_record.change_date, 0).unwrap()),
+                    name: noun_record.name.to_string(),
+                    noun_type: noun_record.noun_type.to_string(),
+                    parent_noun_id: noun_record.parent_noun_id,
+                    metadata: noun_record.metadata.to_string(),
+                    visibility: noun_record.visibility.parse::<Visibility>()?,
+                    attributes: None,
+                })
             })
-            .collect())
+            .collect::<anyhow::Result<Vec<Noun>>>()
+    }
+
+    async fn find_noun_by_noun_type(&self, noun_type: String) -> anyhow::Result<Vec<Noun>> {
+        let data_interface_transaction = self.lock().await;
+
+        let noun_records = sqlx::query_file!("sqlite_sqls/noun/find/by_noun_type.sql", noun_type)
+            .fetch_all(data_transaction!(data_interface_transaction))
+            .await?;
+
+        noun_records
+            .iter()
+            .map(|noun_record| {
+                Ok(Noun {
+                    noun_id: Some(noun_record.noun_id),
+                    last_changed: Some(Utc.timestamp_opt(noun_record.change_date, 0).unwrap()),
+                    name: noun_record.name.to_string(),
+                    noun_type: noun_record.noun_type.to_string(),
+                    parent_noun_id: noun_record.parent_noun_id,
+                    metadata: noun_record.metadata.to_string(),
+                    visibility: noun_record.visibility.parse::<Visibility>()?,
+                    attributes: None,
+                })
+            })
+            .collect::<anyhow::Result<Vec<Noun>>>()
+    }
+
+    async fn find_noun_by_noun_type_including_subtypes(
+        &self,
+        noun_type: String,
+    ) -> anyhow::Result<Vec<Noun>> {
+        let data_interface_transaction = self.lock().await;
+
+        let noun_records = sqlx::query_file!(
+            "sqlite_sqls/noun/find/by_noun_type_including_subtypes.sql",
+            noun_type
+        )
+        .fetch_all(data_transaction!(data_interface_transaction))
+        .await?;
+
+        noun_records
+            .iter()
+            .map(|noun_record| {
+                Ok(Noun {
+                    noun_id: Some(noun_record.noun_id),
+                    last_changed: Some(Utc.timestamp_opt(noun_record.change_date, 0).unwrap()),
+                    name: noun_record.name.to_string(),
+                    noun_type: noun_record.noun_type.to_string(),
+                    parent_noun_id: noun_record.parent_noun_id,
+                    metadata: noun_record.metadata.to_string(),
+                    visibility: noun_record.visibility.parse::<Visibility>()?,
+                    attributes: None,
+                })
+            })
+            .collect::<anyhow::Result<Vec<Noun>>>()
     }
 
     async fn find_noun_by_id(&self, id: i64) -> anyhow::Result<Option<Noun>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
 
         let possible_noun_record = sqlx::query_file!("sqlite_sqls/noun/find/by_id.sql", id)
             .fetch_optional(data_transaction!(data_interface_transaction))
@@ -251,19 +688,68 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
                 last_changed: Some(Utc.timestamp_opt(noun_record.change_date, 0).unwrap()),
                 name: noun_record.name.to_string(),
                 noun_type: noun_record.noun_type.to_string(),
+                parent_noun_id: noun_record.parent_noun_id,
                 metadata: noun_record.metadata.to_string(),
+                visibility: noun_record.visibility.parse::<Visibility>()?,
                 attributes: None,
             })),
             None => Ok(None),
         }
     }
 
+    async fn find_noun_ancestors_by_id(&self, id: i64) -> anyhow::Result<Vec<Noun>> {
+        let data_interface_transaction = self.lock().await;
+        let noun_records = sqlx::query_file!("sqlite_sqls/noun/find/ancestors_by_id.sql", id)
+            .fetch_all(data_transaction!(data_interface_transaction))
+            .await?;
+
+        noun_records
+            .iter()
+            .map(|noun_record| {
+                Ok(Noun {
+                    noun_id: Some(noun_record.noun_id),
+                    last_changed: Some(Utc.timestamp_opt(noun_record.change_date, 0).unwrap()),
+                    name: noun_record.name.to_string(),
+                    noun_type: noun_record.noun_type.to_string(),
+                    parent_noun_id: noun_record.parent_noun_id,
+                    metadata: noun_record.metadata.to_string(),
+                    visibility: noun_record.visibility.parse::<Visibility>()?,
+                    attributes: None,
+                })
+            })
+            .collect::<anyhow::Result<Vec<Noun>>>()
+    }
+
+    async fn find_noun_descendants_by_id(&self, id: i64) -> anyhow::Result<Vec<Noun>> {
+        let data_interface_transaction = self.lock().await;
+        let noun_records = sqlx::query_file!("sqlite_sqls/noun/find/descendants_by_id.sql", id)
+            .fetch_all(data_transaction!(data_interface_transaction))
+            .await?;
+
+        noun_records
+            .iter()
+            .map(|noun_record| {
+                Ok(Noun {
+                    noun_id: Some(noun_record.noun_id),
+                    last_changed: Some(Utc.timestamp_opt(noun_record.change_date, 0).unwrap()),
+                    name: noun_record.name.to_string(),
+                    noun_type: noun_record.noun_type.to_string(),
+                    parent_noun_id: noun_record.parent_noun_id,
+                    metadata: noun_record.metadata.to_string(),
+                    visibility: noun_record.visibility.parse::<Visibility>()?,
+                    attributes: None,
+                })
+            })
+            .collect::<anyhow::Result<Vec<Noun>>>()
+    }
+
     async fn new_noun_type(&self, noun_type: NounType) -> anyhow::Result<NounType> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let change_set_id = data_interface_transaction.change_set_id;
         let id = sqlx::query_file!(
             "sqlite_sqls/noun_type/new.sql",
             noun_type.noun_type,
+            noun_type.parent_noun_type_id,
             change_set_id,
             noun_type.metadata
         )
@@ -279,6 +765,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
             noun_type_id: Some(noun_type_record.noun_type_id),
             last_changed: Some(Utc.timestamp_opt(noun_type_record.change_date, 0).unwrap()),
             noun_type: noun_type_record.noun_type,
+            parent_noun_type_id: noun_type_record.parent_noun_type_id,
             metadata: noun_type_record.metadata,
         })
     }
@@ -287,13 +774,16 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
         &self,
         noun_type_history: NounTypeHistory,
     ) -> anyhow::Result<NounTypeHistory> {
-        let mut data_interface_transaction = self.lock().await;
-        let change_set_id = data_interface_transaction.change_set_id;
+        let data_interface_transaction = self.lock().await;
+        let change_set_id = noun_type_history
+            .change_set_id
+            .unwrap_or(data_interface_transaction.change_set_id);
         let id = sqlx::query_file!(
             "sqlite_sqls/noun_type/history/new.sql",
             noun_type_history.noun_type_id,
             change_set_id,
             noun_type_history.diff_noun_type,
+            noun_type_history.diff_parent_noun_type_id,
             noun_type_history.diff_metadata
         )
         .execute(data_transaction!(data_interface_transaction))
@@ -306,22 +796,47 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
                 .await?;
         Ok(NounTypeHistory {
             noun_type_id: noun_type_history_record.noun_type_id,
+            change_set_id: Some(noun_type_history_record.change_set_id),
             change_date: Some(
                 Utc.timestamp_opt(noun_type_history_record.change_date, 0)
                     .unwrap(),
             ),
             diff_noun_type: noun_type_history_record.diff_noun_type,
+            diff_parent_noun_type_id: noun_type_history_record.diff_parent_noun_type_id,
             diff_metadata: noun_type_history_record.diff_metadata,
         })
     }
 
+    async fn find_noun_type_history_by_all(&self) -> anyhow::Result<Vec<NounTypeHistory>> {
+        let data_interface_transaction = self.lock().await;
+        let noun_type_history_records =
+            sqlx::query_file!("sqlite_sqls/noun_type/history/find/by_all.sql")
+                .fetch_all(data_transaction!(data_interface_transaction))
+                .await?;
+        Ok(noun_type_history_records
+            .into_iter()
+            .map(|noun_type_history_record| NounTypeHistory {
+                noun_type_id: noun_type_history_record.noun_type_id,
+                change_set_id: Some(noun_type_history_record.change_set_id),
+                change_date: Some(
+                    Utc.timestamp_opt(noun_type_history_record.change_date, 0)
+                        .unwrap(),
+                ),
+                diff_noun_type: noun_type_history_record.diff_noun_type,
+                diff_parent_noun_type_id: noun_type_history_record.diff_parent_noun_type_id,
+                diff_metadata: noun_type_history_record.diff_metadata,
+            })
+            .collect())
+    }
+
     async fn update_noun_type(&self, noun_type: NounType) -> anyhow::Result<NounType> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let change_set_id = data_interface_transaction.change_set_id;
         let noun_type_id = noun_type.noun_type_id.ok_or(anyhow::anyhow!("No ID"))?;
         sqlx::query_file!(
             "sqlite_sqls/noun_type/update.sql",
             noun_type.noun_type,
+            noun_type.parent_noun_type_id,
             change_set_id,
             noun_type.metadata,
             noun_type_id
@@ -339,6 +854,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
             noun_type_id: Some(noun_type_record.noun_type_id),
             last_changed: Some(Utc.timestamp_opt(noun_type_record.change_date, 0).unwrap()),
             noun_type: noun_type_record.noun_type,
+            parent_noun_type_id: noun_type_record.parent_noun_type_id,
             metadata: noun_type_record.metadata,
         })
     }
@@ -347,7 +863,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
         &self,
         noun_type: String,
     ) -> anyhow::Result<Vec<NounType>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let noun_type_records =
             sqlx::query_file!("sqlite_sqls/noun_type/find/by_noun_type.sql", noun_type)
                 .fetch_all(data_transaction!(data_interface_transaction))
@@ -359,46 +875,898 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
                 noun_type_id: Some(noun_type_record.noun_type_id),
                 last_changed: Some(Utc.timestamp_opt(noun_type_record.change_date, 0).unwrap()),
                 noun_type: noun_type_record.noun_type.to_string(),
+                parent_noun_type_id: noun_type_record.parent_noun_type_id,
+                metadata: noun_type_record.metadata.to_string(),
+            })
+            .collect())
+    }
+
+    async fn find_noun_type_by_all(&self) -> anyhow::Result<Vec<NounType>> {
+        let data_interface_transaction = self.lock().await;
+        let noun_type_records = sqlx::query_file!("sqlite_sqls/noun_type/find/by_all.sql")
+            .fetch_all(data_transaction!(data_interface_transaction))
+            .await?;
+
+        Ok(noun_type_records
+            .iter()
+            .map(|noun_type_record| NounType {
+                noun_type_id: Some(noun_type_record.noun_type_id),
+                last_changed: Some(Utc.timestamp_opt(noun_type_record.change_date, 0).unwrap()),
+                noun_type: noun_type_record.noun_type.to_string(),
+                parent_noun_type_id: noun_type_record.parent_noun_type_id,
                 metadata: noun_type_record.metadata.to_string(),
             })
             .collect())
     }
 
-    async fn find_noun_type_by_all(&self) -> anyhow::Result<Vec<NounType>> {
-        let mut data_interface_transaction = self.lock().await;
-        let noun_type_records = sqlx::query_file!("sqlite_sqls/noun_type/find/by_all.sql")
+    async fn find_noun_type_by_id(&self, noun_type_id: i64) -> anyhow::Result<Option<NounType>> {
+        let data_interface_transaction = self.lock().await;
+        let possible_noun_type_record =
+            sqlx::query_file!("sqlite_sqls/noun_type/find/by_id.sql", noun_type_id)
+                .fetch_optional(data_transaction!(data_interface_transaction))
+                .await?;
+        match possible_noun_type_record {
+            Some(noun_type_record) => Ok(Some(NounType {
+                noun_type_id: Some(noun_type_record.noun_type_id),
+                last_changed: Some(Utc.timestamp_opt(noun_type_record.change_date, 0).unwrap()),
+                noun_type: noun_type_record.noun_type.to_string(),
+                parent_noun_type_id: noun_type_record.parent_noun_type_id,
+                metadata: noun_type_record.metadata.to_string(),
+            })),
+            None => Ok(None),
+        }
+    }
+    async fn set_noun_type_attribute_rule(
+        &self,
+        noun_type_attribute_rule: NounTypeAttributeRule,
+    ) -> anyhow::Result<NounTypeAttributeRule> {
+        let data_interface_transaction = self.lock().await;
+        let change_set_id = data_interface_transaction.change_set_id;
+        let requirement = requirement_to_string(noun_type_attribute_rule.requirement);
+        let encoded_default_data = match &noun_type_attribute_rule.default_data {
+            Some(default_data) => Some(rmp_serde::to_vec(default_data)?),
+            None => None,
+        };
+        sqlx::query_file!(
+            "sqlite_sqls/noun_type_attribute_rule/set.sql",
+            noun_type_attribute_rule.noun_type_id,
+            noun_type_attribute_rule.attribute_type_id,
+            requirement,
+            noun_type_attribute_rule.min_count,
+            noun_type_attribute_rule.max_count,
+            encoded_default_data,
+            change_set_id
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?;
+
+        let rule_record = sqlx::query_file!(
+            "sqlite_sqls/noun_type_attribute_rule/find/by_noun_type_id_and_attribute_type_id.sql",
+            noun_type_attribute_rule.noun_type_id,
+            noun_type_attribute_rule.attribute_type_id
+        )
+        .fetch_one(data_transaction!(data_interface_transaction))
+        .await?;
+
+        Ok(NounTypeAttributeRule {
+            noun_type_id: rule_record.noun_type_id,
+            attribute_type_id: rule_record.attribute_type_id,
+            last_changed: Some(Utc.timestamp_opt(rule_record.change_date, 0).unwrap()),
+            requirement: rule_record.requirement.parse::<AttributeRequirement>()?,
+            min_count: rule_record.min_count,
+            max_count: rule_record.max_count,
+            default_data: match &rule_record.default_data {
+                Some(default_data) => Some(rmp_serde::from_slice(default_data)?),
+                None => None,
+            },
+        })
+    }
+
+    async fn delete_noun_type_attribute_rule(
+        &self,
+        noun_type_id: i64,
+        attribute_type_id: i64,
+    ) -> anyhow::Result<()> {
+        let data_interface_transaction = self.lock().await;
+        sqlx::query_file!(
+            "sqlite_sqls/noun_type_attribute_rule/delete.sql",
+            noun_type_id,
+            attribute_type_id
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?;
+        Ok(())
+    }
+
+    async fn find_noun_type_attribute_rule_by_noun_type_id(
+        &self,
+        noun_type_id: i64,
+    ) -> anyhow::Result<Vec<NounTypeAttributeRule>> {
+        let data_interface_transaction = self.lock().await;
+        let rule_records = sqlx::query_file!(
+            "sqlite_sqls/noun_type_attribute_rule/find/by_noun_type_id.sql",
+            noun_type_id
+        )
+        .fetch_all(data_transaction!(data_interface_transaction))
+        .await?;
+        rule_records
+            .iter()
+            .map(|rule_record| {
+                Ok(NounTypeAttributeRule {
+                    noun_type_id: rule_record.noun_type_id,
+                    attribute_type_id: rule_record.attribute_type_id,
+                    last_changed: Some(Utc.timestamp_opt(rule_record.change_date, 0).unwrap()),
+                    requirement: rule_record.requirement.parse::<AttributeRequirement>()?,
+                    min_count: rule_record.min_count,
+                    max_count: rule_record.max_count,
+                    default_data: match &rule_record.default_data {
+                        Some(default_data) => Some(rmp_serde::from_slice(default_data)?),
+                        None => None,
+                    },
+                })
+            })
+            .collect::<anyhow::Result<Vec<NounTypeAttributeRule>>>()
+    }
+
+    async fn set_template(&self, template: Template) -> anyhow::Result<Template> {
+        let data_interface_transaction = self.lock().await;
+        let change_set_id = data_interface_transaction.change_set_id;
+        let encoded_attributes = rmp_serde::to_vec(&template.attributes)?;
+        sqlx::query_file!(
+            "sqlite_sqls/template/set.sql",
+            template.template_name,
+            template.noun_type,
+            encoded_attributes,
+            change_set_id,
+            template.metadata
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?;
+
+        let template_record = sqlx::query_file!(
+            "sqlite_sqls/template/find/by_name.sql",
+            template.template_name
+        )
+        .fetch_one(data_transaction!(data_interface_transaction))
+        .await?;
+
+        Ok(Template {
+            template_id: Some(template_record.template_id),
+            last_changed: Some(Utc.timestamp_opt(template_record.change_date, 0).unwrap()),
+            template_name: template_record.template_name,
+            noun_type: template_record.noun_type,
+            attributes: rmp_serde::from_slice(&template_record.attributes)?,
+            metadata: template_record.metadata,
+        })
+    }
+
+    async fn delete_template(&self, template_name: String) -> anyhow::Result<()> {
+        let data_interface_transaction = self.lock().await;
+        sqlx::query_file!("sqlite_sqls/template/delete.sql", template_name)
+            .execute(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(())
+    }
+
+    async fn find_template_by_name(
+        &self,
+        template_name: String,
+    ) -> anyhow::Result<Option<Template>> {
+        let data_interface_transaction = self.lock().await;
+        let possible_template_record =
+            sqlx::query_file!("sqlite_sqls/template/find/by_name.sql", template_name)
+                .fetch_optional(data_transaction!(data_interface_transaction))
+                .await?;
+        match possible_template_record {
+            Some(template_record) => Ok(Some(Template {
+                template_id: Some(template_record.template_id),
+                last_changed: Some(Utc.timestamp_opt(template_record.change_date, 0).unwrap()),
+                template_name: template_record.template_name,
+                noun_type: template_record.noun_type,
+                attributes: rmp_serde::from_slice(&template_record.attributes)?,
+                metadata: template_record.metadata,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    async fn find_template_by_all(&self) -> anyhow::Result<Vec<Template>> {
+        let data_interface_transaction = self.lock().await;
+        let template_records = sqlx::query_file!("sqlite_sqls/template/find/by_all.sql")
+            .fetch_all(data_transaction!(data_interface_transaction))
+            .await?;
+        template_records
+            .iter()
+            .map(|template_record| {
+                Ok(Template {
+                    template_id: Some(template_record.template_id),
+                    last_changed: Some(Utc.timestamp_opt(template_record.change_date, 0).unwrap()),
+                    template_name: template_record.template_name.to_string(),
+                    noun_type: template_record.noun_type.to_string(),
+                    attributes: rmp_serde::from_slice(&template_record.attributes)?,
+                    metadata: template_record.metadata.to_string(),
+                })
+            })
+            .collect::<anyhow::Result<Vec<Template>>>()
+    }
+
+    async fn new_relationship_type(
+        &self,
+        relationship_type: RelationshipType,
+    ) -> anyhow::Result<RelationshipType> {
+        let data_interface_transaction = self.lock().await;
+        let change_set_id = data_interface_transaction.change_set_id;
+        let cardinality = cardinality_to_string(relationship_type.cardinality);
+        let id = sqlx::query_file!(
+            "sqlite_sqls/relationship_type/new.sql",
+            relationship_type.relationship_name,
+            relationship_type.inverse_name,
+            cardinality,
+            change_set_id,
+            relationship_type.metadata
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?
+        .last_insert_rowid();
+
+        let relationship_type_record =
+            sqlx::query_file!("sqlite_sqls/relationship_type/find/by_row_id.sql", id)
+                .fetch_one(data_transaction!(data_interface_transaction))
+                .await?;
+
+        Ok(RelationshipType {
+            relationship_type_id: Some(relationship_type_record.relationship_type_id),
+            last_changed: Some(
+                Utc.timestamp_opt(relationship_type_record.change_date, 0)
+                    .unwrap(),
+            ),
+            relationship_name: relationship_type_record.relationship_name,
+            inverse_name: relationship_type_record.inverse_name,
+            cardinality: relationship_type_record
+                .cardinality
+                .parse::<RelationshipCardinality>()?,
+            metadata: relationship_type_record.metadata,
+        })
+    }
+
+    async fn find_relationship_type_by_name(
+        &self,
+        name: String,
+    ) -> anyhow::Result<Vec<RelationshipType>> {
+        let data_interface_transaction = self.lock().await;
+        let relationship_type_records =
+            sqlx::query_file!("sqlite_sqls/relationship_type/find/by_name.sql", name)
+                .fetch_all(data_transaction!(data_interface_transaction))
+                .await?;
+        relationship_type_records
+            .iter()
+            .map(|relationship_type_record| {
+                Ok(RelationshipType {
+                    relationship_type_id: Some(relationship_type_record.relationship_type_id),
+                    last_changed: Some(
+                        Utc.timestamp_opt(relationship_type_record.change_date, 0)
+                            .unwrap(),
+                    ),
+                    relationship_name: relationship_type_record.relationship_name.to_string(),
+                    inverse_name: relationship_type_record.inverse_name.to_string(),
+                    cardinality: relationship_type_record
+                        .cardinality
+                        .parse::<RelationshipCardinality>()?,
+                    metadata: relationship_type_record.metadata.to_string(),
+                })
+            })
+            .collect::<anyhow::Result<Vec<RelationshipType>>>()
+    }
+
+    async fn find_relationship_type_by_all(&self) -> anyhow::Result<Vec<RelationshipType>> {
+        let data_interface_transaction = self.lock().await;
+        let relationship_type_records =
+            sqlx::query_file!("sqlite_sqls/relationship_type/find/by_all.sql")
+                .fetch_all(data_transaction!(data_interface_transaction))
+                .await?;
+        relationship_type_records
+            .iter()
+            .map(|relationship_type_record| {
+                Ok(RelationshipType {
+                    relationship_type_id: Some(relationship_type_record.relationship_type_id),
+                    last_changed: Some(
+                        Utc.timestamp_opt(relationship_type_record.change_date, 0)
+                            .unwrap(),
+                    ),
+                    relationship_name: relationship_type_record.relationship_name.to_string(),
+                    inverse_name: relationship_type_record.inverse_name.to_string(),
+                    cardinality: relationship_type_record
+                        .cardinality
+                        .parse::<RelationshipCardinality>()?,
+                    metadata: relationship_type_record.metadata.to_string(),
+                })
+            })
+            .collect::<anyhow::Result<Vec<RelationshipType>>>()
+    }
+
+    async fn new_relationship(&self, relationship: Relationship) -> anyhow::Result<Relationship> {
+        let data_interface_transaction = self.lock().await;
+        let change_set_id = data_interface_transaction.change_set_id;
+        let id = sqlx::query_file!(
+            "sqlite_sqls/relationship/new.sql",
+            relationship.relationship_type,
+            relationship.source_noun_id,
+            relationship.target_noun_id,
+            change_set_id,
+            relationship.metadata
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?
+        .last_insert_rowid();
+
+        let relationship_record =
+            sqlx::query_file!("sqlite_sqls/relationship/find/by_row_id.sql", id)
+                .fetch_one(data_transaction!(data_interface_transaction))
+                .await?;
+
+        Ok(Relationship {
+            relationship_id: Some(relationship_record.relationship_id),
+            last_changed: Some(
+                Utc.timestamp_opt(relationship_record.change_date, 0)
+                    .unwrap(),
+            ),
+            relationship_type: relationship_record.relationship_name,
+            source_noun_id: relationship_record.source_noun_id,
+            target_noun_id: relationship_record.target_noun_id,
+            metadata: relationship_record.metadata,
+        })
+    }
+
+    async fn delete_relationship(&self, relationship_id: i64) -> anyhow::Result<()> {
+        let data_interface_transaction = self.lock().await;
+        sqlx::query_file!("sqlite_sqls/relationship/delete.sql", relationship_id)
+            .execute(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(())
+    }
+
+    async fn find_relationship_by_noun_id(
+        &self,
+        noun_id: i64,
+    ) -> anyhow::Result<Vec<Relationship>> {
+        let data_interface_transaction = self.lock().await;
+        let relationship_records =
+            sqlx::query_file!("sqlite_sqls/relationship/find/by_noun_id.sql", noun_id)
+                .fetch_all(data_transaction!(data_interface_transaction))
+                .await?;
+        Ok(relationship_records
+            .iter()
+            .map(|relationship_record| Relationship {
+                relationship_id: Some(relationship_record.relationship_id),
+                last_changed: Some(
+                    Utc.timestamp_opt(relationship_record.change_date, 0)
+                        .unwrap(),
+                ),
+                relationship_type: relationship_record.relationship_name.to_string(),
+                source_noun_id: relationship_record.source_noun_id,
+                target_noun_id: relationship_record.target_noun_id,
+                metadata: relationship_record.metadata.to_string(),
+            })
+            .collect())
+    }
+
+    async fn find_relationship_by_all(&self) -> anyhow::Result<Vec<Relationship>> {
+        let data_interface_transaction = self.lock().await;
+        let relationship_records = sqlx::query_file!("sqlite_sqls/relationship/find/by_all.sql")
+            .fetch_all(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(relationship_records
+            .iter()
+            .map(|relationship_record| Relationship {
+                relationship_id: Some(relationship_record.relationship_id),
+                last_changed: Some(
+                    Utc.timestamp_opt(relationship_record.change_date, 0)
+                        .unwrap(),
+                ),
+                relationship_type: relationship_record.relationship_name.to_string(),
+                source_noun_id: relationship_record.source_noun_id,
+                target_noun_id: relationship_record.target_noun_id,
+                metadata: relationship_record.metadata.to_string(),
+            })
+            .collect())
+    }
+
+    async fn new_relationship_history(
+        &self,
+        relationship_history: RelationshipHistory,
+    ) -> anyhow::Result<RelationshipHistory> {
+        let data_interface_transaction = self.lock().await;
+        let change_set_id = relationship_history
+            .change_set_id
+            .unwrap_or(data_interface_transaction.change_set_id);
+        let id = sqlx::query_file!(
+            "sqlite_sqls/relationship/history/new.sql",
+            relationship_history.relationship_id,
+            change_set_id,
+            relationship_history.diff_relationship_type,
+            relationship_history.diff_source_noun_id,
+            relationship_history.diff_target_noun_id,
+            relationship_history.diff_metadata
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?
+        .last_insert_rowid();
+
+        let relationship_history_record =
+            sqlx::query_file!("sqlite_sqls/relationship/history/find/by_row_id.sql", id)
+                .fetch_one(data_transaction!(data_interface_transaction))
+                .await?;
+        Ok(RelationshipHistory {
+            relationship_id: relationship_history_record.relationship_id,
+            change_set_id: Some(relationship_history_record.change_set_id),
+            change_date: Some(
+                Utc.timestamp_opt(relationship_history_record.change_date, 0)
+                    .unwrap(),
+            ),
+            diff_relationship_type: relationship_history_record.diff_relationship_type,
+            diff_source_noun_id: relationship_history_record.diff_source_noun_id,
+            diff_target_noun_id: relationship_history_record.diff_target_noun_id,
+            diff_metadata: relationship_history_record.diff_metadata,
+        })
+    }
+
+    async fn find_relationship_history_by_all(&self) -> anyhow::Result<Vec<RelationshipHistory>> {
+        let data_interface_transaction = self.lock().await;
+        let relationship_history_records =
+            sqlx::query_file!("sqlite_sqls/relationship/history/find/by_all.sql")
+                .fetch_all(data_transaction!(data_interface_transaction))
+                .await?;
+        Ok(relationship_history_records
+            .into_iter()
+            .map(|relationship_history_record| RelationshipHistory {
+                relationship_id: relationship_history_record.relationship_id,
+                change_set_id: Some(relationship_history_record.change_set_id),
+                change_date: Some(
+                    Utc.timestamp_opt(relationship_history_record.change_date, 0)
+                        .unwrap(),
+                ),
+                diff_relationship_type: relationship_history_record.diff_relationship_type,
+                diff_source_noun_id: relationship_history_record.diff_source_noun_id,
+                diff_target_noun_id: relationship_history_record.diff_target_noun_id,
+                diff_metadata: relationship_history_record.diff_metadata,
+            })
+            .collect())
+    }
+
+    async fn new_noun_reference(
+        &self,
+        noun_reference: NounReferenceEdge,
+    ) -> anyhow::Result<NounReferenceEdge> {
+        let data_interface_transaction = self.lock().await;
+        sqlx::query_file!(
+            "sqlite_sqls/noun_reference/new.sql",
+            noun_reference.attribute_id,
+            noun_reference.attribute_type_id,
+            noun_reference.source_noun_id,
+            noun_reference.target_noun_id
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?;
+        Ok(noun_reference)
+    }
+
+    async fn delete_noun_reference_by_attribute_id(&self, attribute_id: i64) -> anyhow::Result<()> {
+        let data_interface_transaction = self.lock().await;
+        sqlx::query_file!(
+            "sqlite_sqls/noun_reference/delete_by_attribute_id.sql",
+            attribute_id
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?;
+        Ok(())
+    }
+
+    async fn find_noun_reference_by_all(&self) -> anyhow::Result<Vec<NounReferenceEdge>> {
+        let data_interface_transaction = self.lock().await;
+        let noun_reference_records =
+            sqlx::query_file!("sqlite_sqls/noun_reference/find/by_all.sql")
+                .fetch_all(data_transaction!(data_interface_transaction))
+                .await?;
+        Ok(noun_reference_records
+            .iter()
+            .map(|noun_reference_record| NounReferenceEdge {
+                attribute_id: noun_reference_record.attribute_id,
+                attribute_type_id: noun_reference_record.attribute_type_id,
+                source_noun_id: noun_reference_record.source_noun_id,
+                target_noun_id: noun_reference_record.target_noun_id,
+            })
+            .collect())
+    }
+
+    async fn traverse_from_noun(
+        &self,
+        noun_id: i64,
+        options: TraversalOptions,
+        hidden_noun_ids: Vec<i64>,
+        hidden_attribute_ids: Vec<i64>,
+    ) -> anyhow::Result<Vec<TraversalResult>> {
+        let data_interface_transaction = self.lock().await;
+        let hidden_noun_ids = serde_json::to_string(&hidden_noun_ids)?;
+        let hidden_attribute_ids = serde_json::to_string(&hidden_attribute_ids)?;
+        let direction = direction_to_string(options.direction);
+        let attribute_type_ids = match &options.attribute_type_ids {
+            Some(attribute_type_ids) => Some(serde_json::to_string(attribute_type_ids)?),
+            None => None,
+        };
+        let relationship_types = match &options.relationship_types {
+            Some(relationship_types) => Some(serde_json::to_string(relationship_types)?),
+            None => None,
+        };
+        let traversal_records = sqlx::query_file!(
+            "sqlite_sqls/traversal/from_noun.sql",
+            noun_id,
+            options.max_depth,
+            direction,
+            attribute_type_ids,
+            relationship_types,
+            hidden_attribute_ids,
+            hidden_noun_ids
+        )
+        .fetch_all(data_transaction!(data_interface_transaction))
+        .await?;
+        traversal_records
+            .iter()
+            .map(|traversal_record| {
+                Ok(TraversalResult {
+                    noun_id: traversal_record.noun_id,
+                    depth: traversal_record.depth,
+                    path: traversal_record
+                        .path
+                        .split(',')
+                        .filter(|x| !x.is_empty())
+                        .map(|x| x.parse::<i64>())
+                        .collect::<Result<Vec<i64>, _>>()?,
+                })
+            })
+            .collect::<anyhow::Result<Vec<TraversalResult>>>()
+    }
+
+    async fn set_event(&self, event: Event) -> anyhow::Result<Event> {
+        let data_interface_transaction = self.lock().await;
+        let change_set_id = data_interface_transaction.change_set_id;
+        sqlx::query_file!(
+            "sqlite_sqls/event/set.sql",
+            event.noun_id,
+            event.start_day,
+            event.end_day,
+            change_set_id
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?;
+
+        let event_record =
+            sqlx::query_file!("sqlite_sqls/event/find/by_noun_id.sql", event.noun_id)
+                .fetch_one(data_transaction!(data_interface_transaction))
+                .await?;
+
+        Ok(Event {
+            noun_id: event_record.noun_id,
+            last_changed: Some(Utc.timestamp_opt(event_record.change_date, 0).unwrap()),
+            start_day: event_record.start_day,
+            end_day: event_record.end_day,
+            participants: None,
+        })
+    }
+
+    async fn delete_event(&self, noun_id: i64) -> anyhow::Result<()> {
+        let data_interface_transaction = self.lock().await;
+        sqlx::query_file!("sqlite_sqls/event/delete.sql", noun_id)
+            .execute(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(())
+    }
+
+    async fn find_event_by_noun_id(&self, noun_id: i64) -> anyhow::Result<Option<Event>> {
+        let data_interface_transaction = self.lock().await;
+        let possible_event_record =
+            sqlx::query_file!("sqlite_sqls/event/find/by_noun_id.sql", noun_id)
+                .fetch_optional(data_transaction!(data_interface_transaction))
+                .await?;
+        match possible_event_record {
+            Some(event_record) => Ok(Some(Event {
+                noun_id: event_record.noun_id,
+                last_changed: Some(Utc.timestamp_opt(event_record.change_date, 0).unwrap()),
+                start_day: event_record.start_day,
+                end_day: event_record.end_day,
+                participants: None,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    async fn find_event_by_timeline_filter(
+        &self,
+        filter: TimelineFilter,
+    ) -> anyhow::Result<Vec<Event>> {
+        let data_interface_transaction = self.lock().await;
+        let event_records = sqlx::query_file!(
+            "sqlite_sqls/event/find/by_timeline_filter.sql",
+            filter.from_day,
+            filter.to_day,
+            filter.involving_noun_id
+        )
+        .fetch_all(data_transaction!(data_interface_transaction))
+        .await?;
+        Ok(event_records
+            .iter()
+            .map(|event_record| Event {
+                noun_id: event_record.noun_id,
+                last_changed: Some(Utc.timestamp_opt(event_record.change_date, 0).unwrap()),
+                start_day: event_record.start_day,
+                end_day: event_record.end_day,
+                participants: None,
+            })
+            .collect())
+    }
+
+    async fn new_event_history(&self, event_history: EventHistory) -> anyhow::Result<EventHistory> {
+        let data_interface_transaction = self.lock().await;
+        let change_set_id = event_history
+            .change_set_id
+            .unwrap_or(data_interface_transaction.change_set_id);
+        let id = sqlx::query_file!(
+            "sqlite_sqls/event/history/new.sql",
+            event_history.noun_id,
+            change_set_id,
+            event_history.diff_start_day,
+            event_history.diff_end_day
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?
+        .last_insert_rowid();
+
+        let event_history_record =
+            sqlx::query_file!("sqlite_sqls/event/history/find/by_row_id.sql", id)
+                .fetch_one(data_transaction!(data_interface_transaction))
+                .await?;
+        Ok(EventHistory {
+            noun_id: event_history_record.noun_id,
+            change_set_id: Some(event_history_record.change_set_id),
+            change_date: Some(
+                Utc.timestamp_opt(event_history_record.change_date, 0)
+                    .unwrap(),
+            ),
+            diff_start_day: event_history_record.diff_start_day,
+            diff_end_day: event_history_record.diff_end_day,
+        })
+    }
+
+    async fn find_event_history_by_all(&self) -> anyhow::Result<Vec<EventHistory>> {
+        let data_interface_transaction = self.lock().await;
+        let event_history_records = sqlx::query_file!("sqlite_sqls/event/history/find/by_all.sql")
+            .fetch_all(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(event_history_records
+            .into_iter()
+            .map(|event_history_record| EventHistory {
+                noun_id: event_history_record.noun_id,
+                change_set_id: Some(event_history_record.change_set_id),
+                change_date: Some(
+                    Utc.timestamp_opt(event_history_record.change_date, 0)
+                        .unwrap(),
+                ),
+                diff_start_day: event_history_record.diff_start_day,
+                diff_end_day: event_history_record.diff_end_day,
+            })
+            .collect())
+    }
+
+    async fn new_event_participant(
+        &self,
+        event_noun_id: i64,
+        participant: EventParticipant,
+    ) -> anyhow::Result<EventParticipant> {
+        let data_interface_transaction = self.lock().await;
+        let role = event_role_to_string(participant.role);
+        sqlx::query_file!(
+            "sqlite_sqls/event_participant/new.sql",
+            event_noun_id,
+            participant.noun_id,
+            role
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?;
+        Ok(participant)
+    }
+
+    async fn delete_event_participant(
+        &self,
+        event_noun_id: i64,
+        participant: EventParticipant,
+    ) -> anyhow::Result<()> {
+        let data_interface_transaction = self.lock().await;
+        let role = event_role_to_string(participant.role);
+        sqlx::query_file!(
+            "sqlite_sqls/event_participant/delete.sql",
+            event_noun_id,
+            participant.noun_id,
+            role
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?;
+        Ok(())
+    }
+
+    async fn find_event_participant_by_event_noun_id(
+        &self,
+        event_noun_id: i64,
+    ) -> anyhow::Result<Vec<EventParticipant>> {
+        let data_interface_transaction = self.lock().await;
+        let participant_records = sqlx::query_file!(
+            "sqlite_sqls/event_participant/find/by_event_noun_id.sql",
+            event_noun_id
+        )
+        .fetch_all(data_transaction!(data_interface_transaction))
+        .await?;
+        participant_records
+            .iter()
+            .map(|participant_record| {
+                Ok(EventParticipant {
+                    noun_id: participant_record.noun_id,
+                    role: participant_record.role.parse::<EventRole>()?,
+                })
+            })
+            .collect::<anyhow::Result<Vec<EventParticipant>>>()
+    }
+
+    async fn set_roll_table(&self, roll_table: RollTable) -> anyhow::Result<RollTable> {
+        let data_interface_transaction = self.lock().await;
+        let change_set_id = data_interface_transaction.change_set_id;
+        let encoded_entries = rmp_serde::to_vec(&roll_table.entries)?;
+        sqlx::query_file!(
+            "sqlite_sqls/roll_table/set.sql",
+            roll_table.roll_table_name,
+            encoded_entries,
+            change_set_id,
+            roll_table.metadata
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?;
+
+        let roll_table_record = sqlx::query_file!(
+            "sqlite_sqls/roll_table/find/by_name.sql",
+            roll_table.roll_table_name
+        )
+        .fetch_one(data_transaction!(data_interface_transaction))
+        .await?;
+
+        Ok(RollTable {
+            roll_table_id: Some(roll_table_record.roll_table_id),
+            last_changed: Some(Utc.timestamp_opt(roll_table_record.change_date, 0).unwrap()),
+            roll_table_name: roll_table_record.roll_table_name,
+            entries: rmp_serde::from_slice(&roll_table_record.entries)?,
+            metadata: roll_table_record.metadata,
+        })
+    }
+
+    async fn delete_roll_table(&self, roll_table_name: String) -> anyhow::Result<()> {
+        let data_interface_transaction = self.lock().await;
+        sqlx::query_file!("sqlite_sqls/roll_table/delete.sql", roll_table_name)
+            .execute(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(())
+    }
+
+    async fn find_roll_table_by_name(
+        &self,
+        roll_table_name: String,
+    ) -> anyhow::Result<Option<RollTable>> {
+        let data_interface_transaction = self.lock().await;
+        let possible_roll_table_record =
+            sqlx::query_file!("sqlite_sqls/roll_table/find/by_name.sql", roll_table_name)
+                .fetch_optional(data_transaction!(data_interface_transaction))
+                .await?;
+        match possible_roll_table_record {
+            Some(roll_table_record) => Ok(Some(RollTable {
+                roll_table_id: Some(roll_table_record.roll_table_id),
+                last_changed: Some(Utc.timestamp_opt(roll_table_record.change_date, 0).unwrap()),
+                roll_table_name: roll_table_record.roll_table_name,
+                entries: rmp_serde::from_slice(&roll_table_record.entries)?,
+                metadata: roll_table_record.metadata,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    async fn find_roll_table_by_all(&self) -> anyhow::Result<Vec<RollTable>> {
+        let data_interface_transaction = self.lock().await;
+        let roll_table_records = sqlx::query_file!("sqlite_sqls/roll_table/find/by_all.sql")
             .fetch_all(data_transaction!(data_interface_transaction))
             .await?;
-
-        Ok(noun_type_records
+        roll_table_records
             .iter()
-            .map(|noun_type_record| NounType {
-                noun_type_id: Some(noun_type_record.noun_type_id),
-                last_changed: Some(Utc.timestamp_opt(noun_type_record.change_date, 0).unwrap()),
-                noun_type: noun_type_record.noun_type.to_string(),
-                metadata: noun_type_record.metadata.to_string(),
+            .map(|roll_table_record| {
+                Ok(RollTable {
+                    roll_table_id: Some(roll_table_record.roll_table_id),
+                    last_changed: Some(
+                        Utc.timestamp_opt(roll_table_record.change_date, 0).unwrap(),
+                    ),
+                    roll_table_name: roll_table_record.roll_table_name.to_string(),
+                    entries: rmp_serde::from_slice(&roll_table_record.entries)?,
+                    metadata: roll_table_record.metadata.to_string(),
+                })
             })
-            .collect())
+            .collect::<anyhow::Result<Vec<RollTable>>>()
     }
 
-    async fn find_noun_type_by_id(&self, noun_type_id: i64) -> anyhow::Result<Option<NounType>> {
-        let mut data_interface_transaction = self.lock().await;
-        let possible_noun_type_record =
-            sqlx::query_file!("sqlite_sqls/noun_type/find/by_id.sql", noun_type_id)
+    async fn set_calendar(&self, calendar: Calendar) -> anyhow::Result<Calendar> {
+        let data_interface_transaction = self.lock().await;
+        let change_set_id = data_interface_transaction.change_set_id;
+        let encoded_definition = rmp_serde::to_vec(&calendar.definition)?;
+        sqlx::query_file!(
+            "sqlite_sqls/calendar/set.sql",
+            calendar.calendar_name,
+            encoded_definition,
+            change_set_id
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?;
+
+        let calendar_record = sqlx::query_file!(
+            "sqlite_sqls/calendar/find/by_name.sql",
+            calendar.calendar_name
+        )
+        .fetch_one(data_transaction!(data_interface_transaction))
+        .await?;
+
+        Ok(Calendar {
+            calendar_name: calendar_record.calendar_name,
+            last_changed: Some(Utc.timestamp_opt(calendar_record.change_date, 0).unwrap()),
+            definition: rmp_serde::from_slice(&calendar_record.definition)?,
+        })
+    }
+
+    async fn delete_calendar(&self, calendar_name: String) -> anyhow::Result<()> {
+        let data_interface_transaction = self.lock().await;
+        sqlx::query_file!("sqlite_sqls/calendar/delete.sql", calendar_name)
+            .execute(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(())
+    }
+
+    async fn find_calendar_by_name(
+        &self,
+        calendar_name: String,
+    ) -> anyhow::Result<Option<Calendar>> {
+        let data_interface_transaction = self.lock().await;
+        let possible_calendar_record =
+            sqlx::query_file!("sqlite_sqls/calendar/find/by_name.sql", calendar_name)
                 .fetch_optional(data_transaction!(data_interface_transaction))
                 .await?;
-        match possible_noun_type_record {
-            Some(noun_type_record) => Ok(Some(NounType {
-                noun_type_id: Some(noun_type_record.noun_type_id),
-                last_changed: Some(Utc.timestamp_opt(noun_type_record.change_date, 0).unwrap()),
-                noun_type: noun_type_record.noun_type.to_string(),
-                metadata: noun_type_record.metadata.to_string(),
+        match possible_calendar_record {
+            Some(calendar_record) => Ok(Some(Calendar {
+                calendar_name: calendar_record.calendar_name,
+                last_changed: Some(Utc.timestamp_opt(calendar_record.change_date, 0).unwrap()),
+                definition: rmp_serde::from_slice(&calendar_record.definition)?,
             })),
             None => Ok(None),
         }
     }
+
+    async fn find_calendar_by_all(&self) -> anyhow::Result<Vec<Calendar>> {
+        let data_interface_transaction = self.lock().await;
+        let calendar_records = sqlx::query_file!("sqlite_sqls/calendar/find/by_all.sql")
+            .fetch_all(data_transaction!(data_interface_transaction))
+            .await?;
+        calendar_records
+            .iter()
+            .map(|calendar_record| {
+                Ok(Calendar {
+                    calendar_name: calendar_record.calendar_name.to_string(),
+                    last_changed: Some(Utc.timestamp_opt(calendar_record.change_date, 0).unwrap()),
+                    definition: rmp_serde::from_slice(&calendar_record.definition)?,
+                })
+            })
+            .collect::<anyhow::Result<Vec<Calendar>>>()
+    }
+
     async fn new_data_type(&self, data_type: DataType) -> anyhow::Result<DataType> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let change_set_id = data_interface_transaction.change_set_id;
 
         let encoded_definition = rmp_serde::to_vec(&data_type.definition)?;
@@ -433,7 +1801,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
         &self,
         name: String,
     ) -> anyhow::Result<Option<DataType>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let possible_data_type_record =
             sqlx::query_file!("sqlite_sqls/data_type/find/latest_by_name.sql", name)
                 .fetch_optional(data_transaction!(data_interface_transaction))
@@ -451,7 +1819,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
     }
 
     async fn find_data_type_all_by_name(&self, name: String) -> anyhow::Result<Vec<DataType>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let data_type_records =
             sqlx::query_file!("sqlite_sqls/data_type/find/all_by_name.sql", name)
                 .fetch_all(data_transaction!(data_interface_transaction))
@@ -471,7 +1839,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
     }
 
     async fn find_data_type_all_by_all(&self) -> anyhow::Result<Vec<DataType>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let data_type_records = sqlx::query_file!("sqlite_sqls/data_type/find/all_by_all.sql")
             .fetch_all(data_transaction!(data_interface_transaction))
             .await?;
@@ -490,7 +1858,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
     }
 
     async fn find_data_type_latest_by_all(&self) -> anyhow::Result<Vec<DataType>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let data_type_records = sqlx::query_file!("sqlite_sqls/data_type/find/latest_by_all.sql")
             .fetch_all(data_transaction!(data_interface_transaction))
             .await?;
@@ -512,7 +1880,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
         &self,
         attribute_type: AttributeType,
     ) -> anyhow::Result<AttributeType> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let change_set_id = data_interface_transaction.change_set_id;
 
         let id = sqlx::query_file!(
@@ -520,6 +1888,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
             attribute_type.attribute_name,
             attribute_type.data_type,
             attribute_type.multiple_allowed,
+            attribute_type.formula,
             attribute_type.metadata,
             change_set_id
         )
@@ -537,6 +1906,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
             attribute_name: attribute_type_record.attribute_name,
             data_type: attribute_type_record.data_type_name,
             multiple_allowed: attribute_type_record.multiple_allowed != 0,
+            formula: attribute_type_record.formula,
             metadata: attribute_type_record.metadata,
             last_changed: Some(
                 Utc.timestamp_opt(attribute_type_record.change_date, 0)
@@ -549,14 +1919,17 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
         &self,
         attribute_type_history: AttributeTypeHistory,
     ) -> anyhow::Result<AttributeTypeHistory> {
-        let mut data_interface_transaction = self.lock().await;
-        let change_set_id = data_interface_transaction.change_set_id;
+        let data_interface_transaction = self.lock().await;
+        let change_set_id = attribute_type_history
+            .change_set_id
+            .unwrap_or(data_interface_transaction.change_set_id);
         let id = sqlx::query_file!(
             "sqlite_sqls/attribute_type/history/new.sql",
             attribute_type_history.attribute_type_id,
             change_set_id,
             attribute_type_history.diff_attribute_name,
             attribute_type_history.diff_multiple_allowed,
+            attribute_type_history.diff_formula,
             attribute_type_history.diff_metadata
         )
         .execute(data_transaction!(data_interface_transaction))
@@ -569,21 +1942,48 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
                 .await?;
         Ok(AttributeTypeHistory {
             attribute_type_id: attribute_type_history_record.attribute_type_id,
+            change_set_id: Some(attribute_type_history_record.change_set_id),
             change_date: Some(
                 Utc.timestamp_opt(attribute_type_history_record.change_date, 0)
                     .unwrap(),
             ),
             diff_attribute_name: attribute_type_history_record.diff_attribute_name,
             diff_multiple_allowed: attribute_type_history_record.diff_multiple_allowed,
+            diff_formula: attribute_type_history_record.diff_formula,
             diff_metadata: attribute_type_history_record.diff_metadata,
         })
     }
 
+    async fn find_attribute_type_history_by_all(
+        &self,
+    ) -> anyhow::Result<Vec<AttributeTypeHistory>> {
+        let data_interface_transaction = self.lock().await;
+        let attribute_type_history_records =
+            sqlx::query_file!("sqlite_sqls/attribute_type/history/find/by_all.sql")
+                .fetch_all(data_transaction!(data_interface_transaction))
+                .await?;
+        Ok(attribute_type_history_records
+            .into_iter()
+            .map(|attribute_type_history_record| AttributeTypeHistory {
+                attribute_type_id: attribute_type_history_record.attribute_type_id,
+                change_set_id: Some(attribute_type_history_record.change_set_id),
+                change_date: Some(
+                    Utc.timestamp_opt(attribute_type_history_record.change_date, 0)
+                        .unwrap(),
+                ),
+                diff_attribute_name: attribute_type_history_record.diff_attribute_name,
+                diff_multiple_allowed: attribute_type_history_record.diff_multiple_allowed,
+                diff_formula: attribute_type_history_record.diff_formula,
+                diff_metadata: attribute_type_history_record.diff_metadata,
+            })
+            .collect())
+    }
+
     async fn update_attribute_type(
         &self,
         attribute_type: AttributeType,
     ) -> anyhow::Result<AttributeType> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let change_set_id = data_interface_transaction.change_set_id;
         if attribute_type.attribute_type_id.is_none() {
             return Err(anyhow::anyhow!("Attribute type id is required"));
@@ -595,8 +1995,9 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
             "sqlite_sqls/attribute_type/update.sql",
             attribute_type.attribute_name,
             attribute_type.multiple_allowed,
-            attribute_type.metadata,
             change_set_id,
+            attribute_type.metadata,
+            attribute_type.formula,
             attribute_type_id
         )
         .execute(data_transaction!(data_interface_transaction))
@@ -614,6 +2015,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
             attribute_name: attribute_type_record.attribute_name,
             data_type: attribute_type_record.data_type_name,
             multiple_allowed: attribute_type_record.multiple_allowed != 0,
+            formula: attribute_type_record.formula,
             metadata: attribute_type_record.metadata,
             last_changed: Some(
                 Utc.timestamp_opt(attribute_type_record.change_date, 0)
@@ -626,7 +2028,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
         &self,
         attribute_name: String,
     ) -> anyhow::Result<Vec<AttributeType>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let attribute_type_record = sqlx::query_file!(
             "sqlite_sqls/attribute_type/find/by_name.sql",
             attribute_name
@@ -640,6 +2042,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
                 attribute_name: attribute_type_record.attribute_name.clone(),
                 data_type: attribute_type_record.data_type_name.clone(),
                 multiple_allowed: attribute_type_record.multiple_allowed != 0,
+                formula: attribute_type_record.formula.clone(),
                 metadata: attribute_type_record.metadata.clone(),
                 last_changed: Some(
                     Utc.timestamp_opt(attribute_type_record.change_date, 0)
@@ -650,7 +2053,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
     }
 
     async fn find_attribute_type_by_all(&self) -> anyhow::Result<Vec<AttributeType>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let attribute_type_record = sqlx::query_file!("sqlite_sqls/attribute_type/find/by_all.sql")
             .fetch_all(data_transaction!(data_interface_transaction))
             .await?;
@@ -661,6 +2064,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
                 attribute_name: attribute_type_record.attribute_name.clone(),
                 data_type: attribute_type_record.data_type_name.clone(),
                 multiple_allowed: attribute_type_record.multiple_allowed != 0,
+                formula: attribute_type_record.formula.clone(),
                 metadata: attribute_type_record.metadata.clone(),
                 last_changed: Some(
                     Utc.timestamp_opt(attribute_type_record.change_date, 0)
@@ -674,7 +2078,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
         &self,
         attribute_type_id: i64,
     ) -> anyhow::Result<Option<AttributeType>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let possible_attribute_type_record = sqlx::query_file!(
             "sqlite_sqls/attribute_type/find/by_id.sql",
             attribute_type_id
@@ -687,6 +2091,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
                 attribute_name: attribute_type_record.attribute_name.clone(),
                 data_type: attribute_type_record.data_type_name.clone(),
                 multiple_allowed: attribute_type_record.multiple_allowed != 0,
+                formula: attribute_type_record.formula.clone(),
                 metadata: attribute_type_record.metadata.clone(),
                 last_changed: Some(
                     Utc.timestamp_opt(attribute_type_record.change_date, 0)
@@ -698,10 +2103,11 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
     }
 
     async fn new_attribute(&self, attribute: Attribute) -> anyhow::Result<Attribute> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let change_set_id = data_interface_transaction.change_set_id;
 
         let encoded_data = rmp_serde::to_vec(&attribute.data)?;
+        let visibility = attribute.visibility.to_string();
 
         let id = sqlx::query_file!(
             "sqlite_sqls/attribute/new.sql",
@@ -711,7 +2117,8 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
             encoded_data,
             attribute.data_type_version,
             attribute.metadata,
-            change_set_id
+            change_set_id,
+            visibility
         )
         .execute(data_transaction!(data_interface_transaction))
         .await?
@@ -730,26 +2137,29 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
             data_type_version: attribute_record.data_type_version,
             metadata: attribute_record.metadata,
             last_changed: Some(Utc.timestamp_opt(attribute_record.change_date, 0).unwrap()),
+            visibility: attribute_record.visibility.parse::<Visibility>()?,
             children: None,
         })
     }
 
     async fn update_attribute(&self, attribute: Attribute) -> anyhow::Result<Attribute> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let change_set_id = data_interface_transaction.change_set_id;
 
         let encoded_data = rmp_serde::to_vec(&attribute.data)?;
+        let visibility = attribute.visibility.to_string();
 
         sqlx::query_file!(
             "sqlite_sqls/attribute/update.sql",
-            attribute.attribute_id,
             attribute.attribute_type_id,
             attribute.parent_noun_id,
             attribute.parent_attribute_id,
             encoded_data,
             attribute.data_type_version,
             attribute.metadata,
-            change_set_id
+            change_set_id,
+            visibility,
+            attribute.attribute_id
         )
         .execute(data_transaction!(data_interface_transaction))
         .await?;
@@ -770,33 +2180,45 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
             data_type_version: attribute_record.data_type_version,
             metadata: attribute_record.metadata,
             last_changed: Some(Utc.timestamp_opt(attribute_record.change_date, 0).unwrap()),
+            visibility: attribute_record.visibility.parse::<Visibility>()?,
             children: None,
         })
     }
 
+    async fn delete_attribute(&self, attribute_id: i64) -> anyhow::Result<()> {
+        let data_interface_transaction = self.lock().await;
+        sqlx::query_file!("sqlite_sqls/attribute/delete.sql", attribute_id)
+            .execute(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(())
+    }
+
     async fn find_attribute_by_all(&self) -> anyhow::Result<Vec<Attribute>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let attribute_record = sqlx::query_file!("sqlite_sqls/attribute/find/by_all.sql")
             .fetch_all(data_transaction!(data_interface_transaction))
             .await?;
-        Ok(attribute_record
+        attribute_record
             .iter()
-            .map(|attribute_record| Attribute {
-                attribute_id: Some(attribute_record.attribute_id),
-                attribute_type_id: attribute_record.attribute_type_id,
-                parent_noun_id: attribute_record.parent_noun_id,
-                parent_attribute_id: attribute_record.parent_attribute_id,
-                data: rmp_serde::from_slice(&attribute_record.data).unwrap(),
-                data_type_version: attribute_record.data_type_version,
-                metadata: attribute_record.metadata.clone(),
-                last_changed: Some(Utc.timestamp_opt(attribute_record.change_date, 0).unwrap()),
-                children: None,
+            .map(|attribute_record| {
+                Ok(Attribute {
+                    attribute_id: Some(attribute_record.attribute_id),
+                    attribute_type_id: attribute_record.attribute_type_id,
+                    parent_noun_id: attribute_record.parent_noun_id,
+                    parent_attribute_id: attribute_record.parent_attribute_id,
+                    data: rmp_serde::from_slice(&attribute_record.data).unwrap(),
+                    data_type_version: attribute_record.data_type_version,
+                    metadata: attribute_record.metadata.clone(),
+                    last_changed: Some(Utc.timestamp_opt(attribute_record.change_date, 0).unwrap()),
+                    visibility: attribute_record.visibility.parse::<Visibility>()?,
+                    children: None,
+                })
             })
-            .collect())
+            .collect::<anyhow::Result<Vec<Attribute>>>()
     }
 
     async fn find_attribute_by_id(&self, attribute_id: i64) -> anyhow::Result<Option<Attribute>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let possible_attribute_record =
             sqlx::query_file!("sqlite_sqls/attribute/find/by_id.sql", attribute_id)
                 .fetch_optional(data_transaction!(data_interface_transaction))
@@ -811,64 +2233,101 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
                 data_type_version: attribute_record.data_type_version,
                 metadata: attribute_record.metadata.clone(),
                 last_changed: Some(Utc.timestamp_opt(attribute_record.change_date, 0).unwrap()),
+                visibility: attribute_record.visibility.parse::<Visibility>()?,
                 children: None,
             })),
             None => Ok(None),
         }
     }
 
+    async fn find_attribute_by_attribute_type_id(
+        &self,
+        attribute_type_id: i64,
+    ) -> anyhow::Result<Vec<Attribute>> {
+        let data_interface_transaction = self.lock().await;
+        let attribute_record = sqlx::query_file!(
+            "sqlite_sqls/attribute/find/by_attribute_type_id.sql",
+            attribute_type_id
+        )
+        .fetch_all(data_transaction!(data_interface_transaction))
+        .await?;
+        attribute_record
+            .iter()
+            .map(|attribute_record| {
+                Ok(Attribute {
+                    attribute_id: Some(attribute_record.attribute_id),
+                    attribute_type_id: attribute_record.attribute_type_id,
+                    parent_noun_id: attribute_record.parent_noun_id,
+                    parent_attribute_id: attribute_record.parent_attribute_id,
+                    data: rmp_serde::from_slice(&attribute_record.data).unwrap(),
+                    data_type_version: attribute_record.data_type_version,
+                    metadata: attribute_record.metadata.clone(),
+                    last_changed: Some(Utc.timestamp_opt(attribute_record.change_date, 0).unwrap()),
+                    visibility: attribute_record.visibility.parse::<Visibility>()?,
+                    children: None,
+                })
+            })
+            .collect::<anyhow::Result<Vec<Attribute>>>()
+    }
+
     async fn find_attribute_by_parent_noun_id(
         &self,
         parent_noun_id: i64,
     ) -> anyhow::Result<Vec<Attribute>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let attribute_record = sqlx::query_file!(
             "sqlite_sqls/attribute/find/by_parent_noun_id.sql",
             parent_noun_id
         )
         .fetch_all(data_transaction!(data_interface_transaction))
         .await?;
-        Ok(attribute_record
+        attribute_record
             .iter()
-            .map(|attribute_record| Attribute {
-                attribute_id: Some(attribute_record.attribute_id),
-                attribute_type_id: attribute_record.attribute_type_id,
-                parent_noun_id: attribute_record.parent_noun_id,
-                parent_attribute_id: attribute_record.parent_attribute_id,
-                data: rmp_serde::from_slice(&attribute_record.data).unwrap(),
-                data_type_version: attribute_record.data_type_version,
-                metadata: attribute_record.metadata.clone(),
-                last_changed: Some(Utc.timestamp_opt(attribute_record.change_date, 0).unwrap()),
-                children: None,
+            .map(|attribute_record| {
+                Ok(Attribute {
+                    attribute_id: Some(attribute_record.attribute_id),
+                    attribute_type_id: attribute_record.attribute_type_id,
+                    parent_noun_id: attribute_record.parent_noun_id,
+                    parent_attribute_id: attribute_record.parent_attribute_id,
+                    data: rmp_serde::from_slice(&attribute_record.data).unwrap(),
+                    data_type_version: attribute_record.data_type_version,
+                    metadata: attribute_record.metadata.clone(),
+                    last_changed: Some(Utc.timestamp_opt(attribute_record.change_date, 0).unwrap()),
+                    visibility: attribute_record.visibility.parse::<Visibility>()?,
+                    children: None,
+                })
             })
-            .collect())
+            .collect::<anyhow::Result<Vec<Attribute>>>()
     }
 
     async fn find_attribute_by_parent_attribute_id(
         &self,
         parent_attribute_id: i64,
     ) -> anyhow::Result<Vec<Attribute>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let attribute_record = sqlx::query_file!(
             "sqlite_sqls/attribute/find/by_parent_attribute_id.sql",
             parent_attribute_id
         )
         .fetch_all(data_transaction!(data_interface_transaction))
         .await?;
-        Ok(attribute_record
+        attribute_record
             .iter()
-            .map(|attribute_record| Attribute {
-                attribute_id: Some(attribute_record.attribute_id),
-                attribute_type_id: attribute_record.attribute_type_id,
-                parent_noun_id: attribute_record.parent_noun_id,
-                parent_attribute_id: attribute_record.parent_attribute_id,
-                data: rmp_serde::from_slice(&attribute_record.data).unwrap(),
-                data_type_version: attribute_record.data_type_version,
-                metadata: attribute_record.metadata.clone(),
-                last_changed: Some(Utc.timestamp_opt(attribute_record.change_date, 0).unwrap()),
-                children: None,
+            .map(|attribute_record| {
+                Ok(Attribute {
+                    attribute_id: Some(attribute_record.attribute_id),
+                    attribute_type_id: attribute_record.attribute_type_id,
+                    parent_noun_id: attribute_record.parent_noun_id,
+                    parent_attribute_id: attribute_record.parent_attribute_id,
+                    data: rmp_serde::from_slice(&attribute_record.data).unwrap(),
+                    data_type_version: attribute_record.data_type_version,
+                    metadata: attribute_record.metadata.clone(),
+                    last_changed: Some(Utc.timestamp_opt(attribute_record.change_date, 0).unwrap()),
+                    visibility: attribute_record.visibility.parse::<Visibility>()?,
+                    children: None,
+                })
             })
-            .collect())
+            .collect::<anyhow::Result<Vec<Attribute>>>()
     }
 
     async fn find_attribute_by_parent_noun_id_and_attribute_type_id(
@@ -876,7 +2335,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
         parent_noun_id: i64,
         attribute_type_id: i64,
     ) -> anyhow::Result<Vec<Attribute>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let attribute_record = sqlx::query_file!(
             "sqlite_sqls/attribute/find/by_parent_noun_id_and_attribute_type_id.sql",
             parent_noun_id,
@@ -884,20 +2343,23 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
         )
         .fetch_all(data_transaction!(data_interface_transaction))
         .await?;
-        Ok(attribute_record
+        attribute_record
             .iter()
-            .map(|attribute_record| Attribute {
-                attribute_id: Some(attribute_record.attribute_id),
-                attribute_type_id: attribute_record.attribute_type_id,
-                parent_noun_id: attribute_record.parent_noun_id,
-                parent_attribute_id: attribute_record.parent_attribute_id,
-                data_type_version: attribute_record.data_type_version,
-                data: rmp_serde::from_slice(&attribute_record.data).unwrap(),
-                metadata: attribute_record.metadata.clone(),
-                last_changed: Some(Utc.timestamp_opt(attribute_record.change_date, 0).unwrap()),
-                children: None,
+            .map(|attribute_record| {
+                Ok(Attribute {
+                    attribute_id: Some(attribute_record.attribute_id),
+                    attribute_type_id: attribute_record.attribute_type_id,
+                    parent_noun_id: attribute_record.parent_noun_id,
+                    parent_attribute_id: attribute_record.parent_attribute_id,
+                    data_type_version: attribute_record.data_type_version,
+                    data: rmp_serde::from_slice(&attribute_record.data).unwrap(),
+                    metadata: attribute_record.metadata.clone(),
+                    last_changed: Some(Utc.timestamp_opt(attribute_record.change_date, 0).unwrap()),
+                    visibility: attribute_record.visibility.parse::<Visibility>()?,
+                    children: None,
+                })
             })
-            .collect())
+            .collect::<anyhow::Result<Vec<Attribute>>>()
     }
 
     async fn find_attribute_by_parent_attribute_id_and_attribute_type_id(
@@ -905,7 +2367,7 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
         parent_attribute_id: i64,
         attribute_type_id: i64,
     ) -> anyhow::Result<Vec<Attribute>> {
-        let mut data_interface_transaction = self.lock().await;
+        let data_interface_transaction = self.lock().await;
         let attribute_record = sqlx::query_file!(
             "sqlite_sqls/attribute/find/by_parent_attribute_id_and_attribute_type_id.sql",
             parent_attribute_id,
@@ -913,35 +2375,41 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
         )
         .fetch_all(data_transaction!(data_interface_transaction))
         .await?;
-        Ok(attribute_record
+        attribute_record
             .iter()
-            .map(|attribute_record| Attribute {
-                attribute_id: Some(attribute_record.attribute_id),
-                attribute_type_id: attribute_record.attribute_type_id,
-                parent_noun_id: attribute_record.parent_noun_id,
-                parent_attribute_id: attribute_record.parent_attribute_id,
-                data: rmp_serde::from_slice(&attribute_record.data).unwrap(),
-                data_type_version: attribute_record.data_type_version,
-                metadata: attribute_record.metadata.clone(),
-                last_changed: Some(Utc.timestamp_opt(attribute_record.change_date, 0).unwrap()),
-                children: None,
+            .map(|attribute_record| {
+                Ok(Attribute {
+                    attribute_id: Some(attribute_record.attribute_id),
+                    attribute_type_id: attribute_record.attribute_type_id,
+                    parent_noun_id: attribute_record.parent_noun_id,
+                    parent_attribute_id: attribute_record.parent_attribute_id,
+                    data: rmp_serde::from_slice(&attribute_record.data).unwrap(),
+                    data_type_version: attribute_record.data_type_version,
+                    metadata: attribute_record.metadata.clone(),
+                    last_changed: Some(Utc.timestamp_opt(attribute_record.change_date, 0).unwrap()),
+                    visibility: attribute_record.visibility.parse::<Visibility>()?,
+                    children: None,
+                })
             })
-            .collect())
+            .collect::<anyhow::Result<Vec<Attribute>>>()
     }
 
     async fn new_attribute_history(
         &self,
         attribute_history: AttributeHistory,
     ) -> anyhow::Result<AttributeHistory> {
-        let mut data_interface_transaction = self.lock().await;
-        let change_set_id = data_interface_transaction.change_set_id;
+        let data_interface_transaction = self.lock().await;
+        let change_set_id = attribute_history
+            .change_set_id
+            .unwrap_or(data_interface_transaction.change_set_id);
         let id = sqlx::query_file!(
             "sqlite_sqls/attribute/history/new.sql",
             attribute_history.attribute_id,
             change_set_id,
             attribute_history.diff_data,
             attribute_history.diff_data_type_version,
-            attribute_history.diff_metadata
+            attribute_history.diff_metadata,
+            attribute_history.diff_visibility
         )
         .execute(data_transaction!(data_interface_transaction))
         .await?
@@ -954,13 +2422,233 @@ impl DataInterfaceAccessTransaction for Arc<Mutex<DataInterfaceTransactionSQLite
 
         Ok(AttributeHistory {
             attribute_id: attribute_history_record.attribute_id,
+            change_set_id: Some(attribute_history_record.change_set_id),
             diff_data: attribute_history_record.diff_data,
             diff_data_type_version: attribute_history_record.diff_data_type_version,
             diff_metadata: attribute_history_record.diff_metadata,
+            diff_visibility: attribute_history_record.diff_visibility,
             change_date: Some(
                 Utc.timestamp_opt(attribute_history_record.change_date, 0)
                     .unwrap(),
             ),
         })
     }
+
+    async fn find_attribute_history_by_all(&self) -> anyhow::Result<Vec<AttributeHistory>> {
+        let data_interface_transaction = self.lock().await;
+        let attribute_history_records =
+            sqlx::query_file!("sqlite_sqls/attribute/history/find/by_all.sql")
+                .fetch_all(data_transaction!(data_interface_transaction))
+                .await?;
+        Ok(attribute_history_records
+            .into_iter()
+            .map(|attribute_history_record| AttributeHistory {
+                attribute_id: attribute_history_record.attribute_id,
+                change_set_id: Some(attribute_history_record.change_set_id),
+                diff_data: attribute_history_record.diff_data,
+                diff_data_type_version: attribute_history_record.diff_data_type_version,
+                diff_metadata: attribute_history_record.diff_metadata,
+                diff_visibility: attribute_history_record.diff_visibility,
+                change_date: Some(
+                    Utc.timestamp_opt(attribute_history_record.change_date, 0)
+                        .unwrap(),
+                ),
+            })
+            .collect())
+    }
+
+    async fn find_attribute_history_by_attribute_id(
+        &self,
+        attribute_id: i64,
+    ) -> anyhow::Result<Vec<AttributeHistory>> {
+        let data_interface_transaction = self.lock().await;
+        let attribute_history_records = sqlx::query_file!(
+            "sqlite_sqls/attribute/history/find/by_attribute_id.sql",
+            attribute_id
+        )
+        .fetch_all(data_transaction!(data_interface_transaction))
+        .await?;
+        Ok(attribute_history_records
+            .into_iter()
+            .map(|attribute_history_record| AttributeHistory {
+                attribute_id: attribute_history_record.attribute_id,
+                change_set_id: Some(attribute_history_record.change_set_id),
+                diff_data: attribute_history_record.diff_data,
+                diff_data_type_version: attribute_history_record.diff_data_type_version,
+                diff_metadata: attribute_history_record.diff_metadata,
+                diff_visibility: attribute_history_record.diff_visibility,
+                change_date: Some(
+                    Utc.timestamp_opt(attribute_history_record.change_date, 0)
+                        .unwrap(),
+                ),
+            })
+            .collect())
+    }
+
+    async fn new_asset(&self, asset: Asset, content: Vec<u8>) -> anyhow::Result<Asset> {
+        let data_interface_transaction = self.lock().await;
+        let change_set_id = data_interface_transaction.change_set_id;
+        sqlx::query_file!(
+            "sqlite_sqls/asset/new.sql",
+            asset.asset_hash,
+            asset.mime_type,
+            asset.size,
+            content,
+            change_set_id
+        )
+        .execute(data_transaction!(data_interface_transaction))
+        .await?;
+
+        let asset_record =
+            sqlx::query_file!("sqlite_sqls/asset/find/by_hash.sql", asset.asset_hash)
+                .fetch_one(data_transaction!(data_interface_transaction))
+                .await?;
+
+        Ok(Asset {
+            asset_hash: asset_record.asset_hash,
+            last_changed: Some(Utc.timestamp_opt(asset_record.change_date, 0).unwrap()),
+            mime_type: asset_record.mime_type,
+            size: asset_record.size,
+        })
+    }
+
+    async fn delete_asset(&self, asset_hash: String) -> anyhow::Result<()> {
+        let data_interface_transaction = self.lock().await;
+        sqlx::query_file!("sqlite_sqls/asset/delete.sql", asset_hash)
+            .execute(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(())
+    }
+
+    async fn find_asset_by_hash(&self, asset_hash: String) -> anyhow::Result<Option<Asset>> {
+        let data_interface_transaction = self.lock().await;
+        let possible_asset_record =
+            sqlx::query_file!("sqlite_sqls/asset/find/by_hash.sql", asset_hash)
+                .fetch_optional(data_transaction!(data_interface_transaction))
+                .await?;
+        match possible_asset_record {
+            Some(asset_record) => Ok(Some(Asset {
+                asset_hash: asset_record.asset_hash,
+                last_changed: Some(Utc.timestamp_opt(asset_record.change_date, 0).unwrap()),
+                mime_type: asset_record.mime_type,
+                size: asset_record.size,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    async fn find_asset_by_all(&self) -> anyhow::Result<Vec<Asset>> {
+        let data_interface_transaction = self.lock().await;
+        let asset_records = sqlx::query_file!("sqlite_sqls/asset/find/by_all.sql")
+            .fetch_all(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(asset_records
+            .iter()
+            .map(|asset_record| Asset {
+                asset_hash: asset_record.asset_hash.clone(),
+                last_changed: Some(Utc.timestamp_opt(asset_record.change_date, 0).unwrap()),
+                mime_type: asset_record.mime_type.clone(),
+                size: asset_record.size,
+            })
+            .collect())
+    }
+
+    async fn find_asset_content_by_hash(
+        &self,
+        asset_hash: String,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let data_interface_transaction = self.lock().await;
+        let possible_content_record =
+            sqlx::query_file!("sqlite_sqls/asset/find/content_by_hash.sql", asset_hash)
+                .fetch_optional(data_transaction!(data_interface_transaction))
+                .await?;
+        Ok(possible_content_record.map(|content_record| content_record.content))
+    }
+
+    async fn delete_all_history(&self) -> anyhow::Result<()> {
+        let data_interface_transaction = self.lock().await;
+        sqlx::query_file!("sqlite_sqls/maint/export/strip_history.sql")
+            .execute(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_unreferenced_change_sets(&self) -> anyhow::Result<()> {
+        let data_interface_transaction = self.lock().await;
+        sqlx::query_file!("sqlite_sqls/maint/export/prune_change_sets.sql")
+            .execute(data_transaction!(data_interface_transaction))
+            .await?;
+        Ok(())
+    }
+
+    async fn upgrade_schema(&self) -> anyhow::Result<bool> {
+        let data_interface_transaction = self.lock().await;
+        let version: i64 = sqlx::query_scalar("PRAGMA user_version")
+            .fetch_one(data_transaction!(data_interface_transaction))
+            .await?;
+        if version > SCHEMA_VERSION {
+            anyhow::bail!(ClwmError::UnsupportedSchemaVersion {
+                version,
+                supported: SCHEMA_VERSION
+            });
+        }
+        if version == SCHEMA_VERSION {
+            return Ok(false);
+        }
+
+        // Worlds from before the schema was versioned either predate every
+        // addition in version 1 or were created with all of them.
+        let noun_columns: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_table_info('noun')")
+                .fetch_all(data_transaction!(data_interface_transaction))
+                .await?;
+        if noun_columns.iter().any(|column| column == "visibility") {
+            sqlx::query(&format!("PRAGMA user_version = {}", SCHEMA_VERSION))
+                .execute(data_transaction!(data_interface_transaction))
+                .await?;
+            return Ok(true);
+        }
+        sqlx::query(include_str!(
+            "../../sqlite_sqls/maint/upgrade/version_1.sql"
+        ))
+        .execute(data_transaction!(data_interface_transaction))
+        .await?;
+        Ok(true)
+    }
+}
+
+fn requirement_to_string(requirement: AttributeRequirement) -> String {
+    match requirement {
+        AttributeRequirement::Required => "Required",
+        AttributeRequirement::Optional => "Optional",
+        AttributeRequirement::Forbidden => "Forbidden",
+    }
+    .to_string()
+}
+
+fn cardinality_to_string(cardinality: RelationshipCardinality) -> String {
+    match cardinality {
+        RelationshipCardinality::OneToOne => "OneToOne",
+        RelationshipCardinality::OneToMany => "OneToMany",
+        RelationshipCardinality::ManyToOne => "ManyToOne",
+        RelationshipCardinality::ManyToMany => "ManyToMany",
+    }
+    .to_string()
+}
+
+fn direction_to_string(direction: TraversalDirection) -> String {
+    match direction {
+        TraversalDirection::Outgoing => "Outgoing",
+        TraversalDirection::Incoming => "Incoming",
+        TraversalDirection::Both => "Both",
+    }
+    .to_string()
+}
+
+fn event_role_to_string(role: EventRole) -> String {
+    match role {
+        EventRole::Participant => "Participant",
+        EventRole::Location => "Location",
+    }
+    .to_string()
 }
@@ -0,0 +1,216 @@
+use std::{fmt, str::FromStr};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::clwm_error::ClwmError;
+
+const MAX_DICE: i64 = 1000;
+const MAX_SIDES: i64 = 1_000_000;
+const MAX_MODIFIER: i64 = 1_000_000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum DiceTerm {
+    Dice { count: i64, sides: i64 },
+    Modifier(i64),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct DiceExpression {
+    pub terms: Vec<DiceTerm>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiceRoll {
+    pub total: i64,
+    pub rolls: Vec<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AttributeDiceRoll {
+    pub attribute_id: i64,
+    pub expression: DiceExpression,
+    pub roll: DiceRoll,
+}
+
+impl DiceExpression {
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> DiceRoll {
+        let mut total: i64 = 0;
+        let mut rolls = Vec::new();
+        for term in self.terms.iter() {
+            match term {
+                DiceTerm::Dice { count, sides } => {
+                    for _ in 0..count.unsigned_abs() {
+                        let roll = rng.gen_range(1..=(*sides).max(1));
+                        rolls.push(roll);
+                        total = total.saturating_add(roll * count.signum());
+                    }
+                }
+                DiceTerm::Modifier(modifier) => total = total.saturating_add(*modifier),
+            }
+        }
+        DiceRoll { total, rolls }
+    }
+
+    pub fn minimum(&self) -> i64 {
+        self.terms
+            .iter()
+            .map(|term| match term {
+                DiceTerm::Dice { count, sides } if *count < 0 => count.saturating_mul(*sides),
+                DiceTerm::Dice { count, .. } => *count,
+                DiceTerm::Modifier(modifier) => *modifier,
+            })
+            .fold(0, i64::saturating_add)
+    }
+
+    pub fn maximum(&self) -> i64 {
+        self.terms
+            .iter()
+            .map(|term| match term {
+                DiceTerm::Dice { count, .. } if *count < 0 => *count,
+                DiceTerm::Dice { count, sides } => count.saturating_mul(*sides),
+                DiceTerm::Modifier(modifier) => *modifier,
+            })
+            .fold(0, i64::saturating_add)
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.terms
+            .iter()
+            .map(|term| match term {
+                DiceTerm::Dice { count, sides } => *count as f64 * (*sides as f64 + 1.0) / 2.0,
+                DiceTerm::Modifier(modifier) => *modifier as f64,
+            })
+            .sum()
+    }
+}
+
+impl FromStr for DiceExpression {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ClwmError::InvalidDiceExpression {
+            expression: s.to_owned(),
+        };
+        let expression: String = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_lowercase();
+        if expression.is_empty() {
+            return Err(invalid().into());
+        }
+
+        let mut terms = Vec::new();
+        let mut rest = expression.as_str();
+        while !rest.is_empty() {
+            let (sign, unsigned) = match rest.strip_prefix('-') {
+                Some(unsigned) => (-1, unsigned),
+                None => (1, rest.strip_prefix('+').unwrap_or(rest)),
+            };
+            let end = unsigned.find(['+', '-']).unwrap_or(unsigned.len());
+            let term = &unsigned[..end];
+            rest = &unsigned[end..];
+
+            match term.split_once('d') {
+                Some((count, sides)) => {
+                    let count = if count.is_empty() {
+                        1
+                    } else {
+                        count.parse::<i64>().map_err(|_| invalid())?
+                    };
+                    let sides = if sides == "%" {
+                        100
+                    } else {
+                        sides.parse::<i64>().map_err(|_| invalid())?
+                    };
+                    if !(1..=MAX_DICE).contains(&count) || !(1..=MAX_SIDES).contains(&sides) {
+                        return Err(invalid().into());
+                    }
+                    terms.push(DiceTerm::Dice {
+                        count: sign * count,
+                        sides,
+                    });
+                }
+                None => {
+                    let modifier = term.parse::<i64>().map_err(|_| invalid())?;
+                    if modifier > MAX_MODIFIER {
+                        return Err(invalid().into());
+                    }
+                    terms.push(DiceTerm::Modifier(sign * modifier));
+                }
+            }
+        }
+        Ok(DiceExpression { terms })
+    }
+}
+
+impl fmt::Display for DiceExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, term) in self.terms.iter().enumerate() {
+            let value = match term {
+                DiceTerm::Dice { count, .. } | DiceTerm::Modifier(count) => *count,
+            };
+            if value < 0 {
+                write!(f, "-")?;
+            } else if index > 0 {
+                write!(f, "+")?;
+            }
+            match term {
+                DiceTerm::Dice { count, sides } => write!(f, "{}d{}", count.abs(), sides)?,
+                DiceTerm::Modifier(modifier) => write!(f, "{}", modifier.abs())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn dice(expression: &str) -> DiceExpression {
+        expression.parse().unwrap()
+    }
+
+    #[test]
+    fn negative_dice_lower_the_minimum_by_their_largest_roll() {
+        assert_eq!(dice("-1d6").minimum(), -6);
+        assert_eq!(dice("2d6-1d4+3").minimum(), 1);
+        assert_eq!(dice("1d20-2d4").minimum(), -7);
+    }
+
+    #[test]
+    fn negative_dice_lower_the_maximum_by_their_smallest_roll() {
+        assert_eq!(dice("-1d6").maximum(), -1);
+        assert_eq!(dice("2d6-1d4+3").maximum(), 14);
+        assert_eq!(dice("1d20-2d4").maximum(), 18);
+    }
+
+    #[test]
+    fn negative_modifiers_shift_both_bounds() {
+        assert_eq!(dice("1d6-2").minimum(), -1);
+        assert_eq!(dice("1d6-2").maximum(), 4);
+        assert_eq!(dice("-3").minimum(), -3);
+        assert_eq!(dice("-3").maximum(), -3);
+    }
+
+    #[test]
+    fn rolls_stay_within_the_bounds() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for expression in ["2d6-1d4+3", "1d20-2d4", "-1d6", "d%-5"] {
+            let expression = dice(expression);
+            for _ in 0..200 {
+                let total = expression.roll(&mut rng).total;
+                assert!(
+                    (expression.minimum()..=expression.maximum()).contains(&total),
+                    "{} rolled {}",
+                    expression,
+                    total
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,458 @@
+use std::collections::HashMap;
+
+use crate::{clwm_error::ClwmError, model::DataObject};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormulaStep {
+    Sibling,
+    Child,
+    FollowReference,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormulaPathSegment {
+    pub step: FormulaStep,
+    pub attribute_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormulaReference {
+    pub text: String,
+    pub segments: Vec<FormulaPathSegment>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FormulaOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormulaExpression {
+    Number(f64),
+    Reference(FormulaReference),
+    Negate(Box<FormulaExpression>),
+    Binary(
+        FormulaOperator,
+        Box<FormulaExpression>,
+        Box<FormulaExpression>,
+    ),
+    Call(String, Vec<FormulaExpression>),
+}
+
+const FUNCTIONS: [&str; 8] = [
+    "sum", "count", "min", "max", "floor", "ceil", "round", "abs",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Reference(String),
+    Operator(char),
+    OpenParen,
+    CloseParen,
+    Comma,
+}
+
+/// Parses a formula such as `floor(([Strength] - 10) / 2)` or
+/// `sum([Inventory/Weight])`.
+///
+/// References are written in square brackets and start from an attribute
+/// that shares a parent with the computed attribute. `/` steps into child
+/// attributes and `->` follows a `NounReference` to the referenced noun's
+/// top level attributes, e.g. `[Born In->Founded]`.
+pub fn parse_formula(formula: &str) -> anyhow::Result<FormulaExpression> {
+    let tokens = tokenize(formula)?;
+    let mut parser = FormulaParser {
+        formula,
+        tokens,
+        position: 0,
+    };
+    let expression = parser.parse_expression()?;
+    if parser.position != parser.tokens.len() {
+        return Err(parser.error("unexpected trailing input"));
+    }
+    Ok(expression)
+}
+
+pub fn formula_references(expression: &FormulaExpression) -> Vec<&FormulaReference> {
+    let mut references = Vec::new();
+    collect_references(expression, &mut references);
+    references
+}
+
+fn collect_references<'a>(
+    expression: &'a FormulaExpression,
+    references: &mut Vec<&'a FormulaReference>,
+) {
+    match expression {
+        FormulaExpression::Number(_) => {}
+        FormulaExpression::Reference(reference) => references.push(reference),
+        FormulaExpression::Negate(inner) => collect_references(inner, references),
+        FormulaExpression::Binary(_, left, right) => {
+            collect_references(left, references);
+            collect_references(right, references);
+        }
+        FormulaExpression::Call(_, arguments) => arguments
+            .iter()
+            .for_each(|argument| collect_references(argument, references)),
+    }
+}
+
+pub fn evaluate_formula(
+    expression: &FormulaExpression,
+    values: &HashMap<String, Vec<DataObject>>,
+) -> anyhow::Result<f64> {
+    match expression {
+        FormulaExpression::Number(number) => Ok(*number),
+        FormulaExpression::Reference(reference) => {
+            let numbers = reference_numbers(reference, values)?;
+            if numbers.is_empty() {
+                anyhow::bail!(ClwmError::FormulaInputMissing {
+                    reference: reference.text.clone()
+                })
+            }
+            if numbers.len() != 1 {
+                anyhow::bail!(ClwmError::FormulaInputNotSingular {
+                    reference: reference.text.clone(),
+                    count: numbers.len(),
+                })
+            }
+            Ok(numbers[0])
+        }
+        FormulaExpression::Negate(inner) => Ok(-evaluate_formula(inner, values)?),
+        FormulaExpression::Binary(operator, left, right) => {
+            let left = evaluate_formula(left, values)?;
+            let right = evaluate_formula(right, values)?;
+            match operator {
+                FormulaOperator::Add => Ok(left + right),
+                FormulaOperator::Subtract => Ok(left - right),
+                FormulaOperator::Multiply => Ok(left * right),
+                FormulaOperator::Divide | FormulaOperator::Remainder if right == 0.0 => {
+                    anyhow::bail!(ClwmError::FormulaDivisionByZero)
+                }
+                FormulaOperator::Divide => Ok(left / right),
+                FormulaOperator::Remainder => Ok(left % right),
+            }
+        }
+        FormulaExpression::Call(function, arguments) => {
+            let mut numbers = Vec::new();
+            for argument in arguments {
+                match argument {
+                    FormulaExpression::Reference(reference) => {
+                        numbers.extend(reference_numbers(reference, values)?)
+                    }
+                    _ => numbers.push(evaluate_formula(argument, values)?),
+                }
+            }
+            match function.as_str() {
+                "sum" => Ok(numbers.iter().sum()),
+                "count" => Ok(numbers.len() as f64),
+                "min" => numbers.into_iter().reduce(f64::min).ok_or(anyhow::anyhow!(
+                    ClwmError::FormulaFunctionNeedsArguments {
+                        function: function.clone()
+                    }
+                )),
+                "max" => numbers.into_iter().reduce(f64::max).ok_or(anyhow::anyhow!(
+                    ClwmError::FormulaFunctionNeedsArguments {
+                        function: function.clone()
+                    }
+                )),
+                _ => {
+                    if numbers.len() != 1 {
+                        anyhow::bail!(ClwmError::FormulaFunctionNeedsArguments {
+                            function: function.clone()
+                        })
+                    }
+                    Ok(match function.as_str() {
+                        "floor" => numbers[0].floor(),
+                        "ceil" => numbers[0].ceil(),
+                        "round" => numbers[0].round(),
+                        _ => numbers[0].abs(),
+                    })
+                }
+            }
+        }
+    }
+}
+
+fn reference_numbers(
+    reference: &FormulaReference,
+    values: &HashMap<String, Vec<DataObject>>,
+) -> anyhow::Result<Vec<f64>> {
+    // No match is an empty list, so aggregates such as sum and count see
+    // zero inputs. Only a single value used on its own treats it as missing.
+    let data = match values.get(&reference.text) {
+        Some(data) => data,
+        None => return Ok(Vec::new()),
+    };
+    data.iter()
+        .map(|x| match x {
+            DataObject::Integer(integer) => Ok(*integer as f64),
+            DataObject::Float(float) => Ok(*float),
+            DataObject::Null => anyhow::bail!(ClwmError::FormulaInputMissing {
+                reference: reference.text.clone()
+            }),
+            _ => anyhow::bail!(ClwmError::FormulaInputWrongType {
+                reference: reference.text.clone()
+            }),
+        })
+        .collect()
+}
+
+fn tokenize(formula: &str) -> anyhow::Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = formula.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+            while let Some(&d) = chars.peek() {
+                if d.is_ascii_digit() || d == '.' {
+                    number.push(d);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let parsed = number.parse::<f64>().map_err(|_| {
+                anyhow::anyhow!(ClwmError::FormulaParseError {
+                    formula: formula.to_string(),
+                    message: format!("invalid number {:?}", number),
+                })
+            })?;
+            tokens.push(Token::Number(parsed));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut identifier = String::new();
+            while let Some(&d) = chars.peek() {
+                if d.is_alphanumeric() || d == '_' {
+                    identifier.push(d);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Identifier(identifier));
+        } else if c == '[' {
+            chars.next();
+            let mut reference = String::new();
+            loop {
+                match chars.next() {
+                    Some(']') => break,
+                    Some(d) => reference.push(d),
+                    None => anyhow::bail!(ClwmError::FormulaParseError {
+                        formula: formula.to_string(),
+                        message: "unterminated reference".to_string(),
+                    }),
+                }
+            }
+            tokens.push(Token::Reference(reference));
+        } else {
+            chars.next();
+            tokens.push(match c {
+                '+' | '-' | '*' | '/' | '%' => Token::Operator(c),
+                '(' => Token::OpenParen,
+                ')' => Token::CloseParen,
+                ',' => Token::Comma,
+                _ => anyhow::bail!(ClwmError::FormulaParseError {
+                    formula: formula.to_string(),
+                    message: format!("unexpected character {:?}", c),
+                }),
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_reference(formula: &str, text: &str) -> anyhow::Result<FormulaReference> {
+    let mut segments = Vec::new();
+    let mut step = FormulaStep::Sibling;
+    let mut rest = text;
+    loop {
+        let slash = rest.find('/');
+        let arrow = rest.find("->");
+        let (name, next_step, next_rest) = match (slash, arrow) {
+            (Some(s), Some(a)) if a < s => (
+                &rest[..a],
+                Some(FormulaStep::FollowReference),
+                &rest[a + 2..],
+            ),
+            (Some(s), _) => (&rest[..s], Some(FormulaStep::Child), &rest[s + 1..]),
+            (None, Some(a)) => (
+                &rest[..a],
+                Some(FormulaStep::FollowReference),
+                &rest[a + 2..],
+            ),
+            (None, None) => (rest, None, ""),
+        };
+        let attribute_name = name.trim();
+        if attribute_name.is_empty() {
+            anyhow::bail!(ClwmError::FormulaParseError {
+                formula: formula.to_string(),
+                message: format!("empty attribute name in reference [{}]", text),
+            })
+        }
+        segments.push(FormulaPathSegment {
+            step,
+            attribute_name: attribute_name.to_string(),
+        });
+        match next_step {
+            Some(next_step) => {
+                step = next_step;
+                rest = next_rest;
+            }
+            None => break,
+        }
+    }
+    Ok(FormulaReference {
+        text: text.to_string(),
+        segments,
+    })
+}
+
+struct FormulaParser<'a> {
+    formula: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl FormulaParser<'_> {
+    fn error(&self, message: &str) -> anyhow::Error {
+        anyhow::anyhow!(ClwmError::FormulaParseError {
+            formula: self.formula.to_string(),
+            message: message.to_string(),
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn parse_expression(&mut self) -> anyhow::Result<FormulaExpression> {
+        let mut left = self.parse_term()?;
+        while let Some(Token::Operator(c @ ('+' | '-'))) = self.peek() {
+            let operator = if *c == '+' {
+                FormulaOperator::Add
+            } else {
+                FormulaOperator::Subtract
+            };
+            self.position += 1;
+            let right = self.parse_term()?;
+            left = FormulaExpression::Binary(operator, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_term(&mut self) -> anyhow::Result<FormulaExpression> {
+        let mut left = self.parse_factor()?;
+        while let Some(Token::Operator(c @ ('*' | '/' | '%'))) = self.peek() {
+            let operator = match c {
+                '*' => FormulaOperator::Multiply,
+                '/' => FormulaOperator::Divide,
+                _ => FormulaOperator::Remainder,
+            };
+            self.position += 1;
+            let right = self.parse_factor()?;
+            left = FormulaExpression::Binary(operator, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_factor(&mut self) -> anyhow::Result<FormulaExpression> {
+        match self.next() {
+            Some(Token::Number(number)) => Ok(FormulaExpression::Number(number)),
+            Some(Token::Reference(text)) => Ok(FormulaExpression::Reference(parse_reference(
+                self.formula,
+                &text,
+            )?)),
+            Some(Token::Operator('-')) => {
+                Ok(FormulaExpression::Negate(Box::new(self.parse_factor()?)))
+            }
+            Some(Token::OpenParen) => {
+                let expression = self.parse_expression()?;
+                match self.next() {
+                    Some(Token::CloseParen) => Ok(expression),
+                    _ => Err(self.error("expected )")),
+                }
+            }
+            Some(Token::Identifier(function)) => {
+                if !FUNCTIONS.contains(&function.as_str()) {
+                    return Err(self.error(&format!("unknown function {:?}", function)));
+                }
+                if self.next() != Some(Token::OpenParen) {
+                    return Err(self.error("expected ( after function name"));
+                }
+                let mut arguments = Vec::new();
+                if self.peek() == Some(&Token::CloseParen) {
+                    self.position += 1;
+                } else {
+                    loop {
+                        arguments.push(self.parse_expression()?);
+                        match self.next() {
+                            Some(Token::Comma) => continue,
+                            Some(Token::CloseParen) => break,
+                            _ => return Err(self.error("expected , or )")),
+                        }
+                    }
+                }
+                Ok(FormulaExpression::Call(function, arguments))
+            }
+            _ => Err(self.error("expected a number, reference, function or (")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(formula: &str, values: &[(&str, i64)]) -> f64 {
+        let values = values
+            .iter()
+            .map(|(name, value)| (name.to_string(), vec![DataObject::Integer(*value)]))
+            .collect();
+        evaluate_formula(&parse_formula(formula).unwrap(), &values).unwrap()
+    }
+
+    #[test]
+    fn multiplication_binds_tighter_than_addition() {
+        assert_eq!(evaluate("1 + 2 * 3", &[]), 7.0);
+        assert_eq!(evaluate("2 * 3 + 1", &[]), 7.0);
+        assert_eq!(evaluate("(1 + 2) * 3", &[]), 9.0);
+        assert_eq!(evaluate("7 - 6 / 2 % 2", &[]), 6.0);
+    }
+
+    #[test]
+    fn operators_of_equal_precedence_group_left() {
+        assert_eq!(evaluate("10 - 4 - 3", &[]), 3.0);
+        assert_eq!(evaluate("24 / 4 / 2", &[]), 3.0);
+    }
+
+    #[test]
+    fn negation_applies_before_binary_operators() {
+        assert_eq!(evaluate("-2 * 3", &[]), -6.0);
+        assert_eq!(evaluate("4 - -2", &[]), 6.0);
+    }
+
+    #[test]
+    fn references_and_functions_take_part_in_precedence() {
+        assert_eq!(
+            evaluate(
+                "floor(([Strength] - 10) / 2) + [Level] * 2",
+                &[("Strength", 15), ("Level", 3)]
+            ),
+            8.0
+        );
+    }
+}
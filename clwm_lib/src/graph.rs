@@ -0,0 +1,54 @@
+use crate::model::NounGraph;
+
+pub fn render_dot(graph: &NounGraph) -> String {
+    let mut dot = "digraph world {\n".to_owned();
+    for noun in graph.nouns.iter() {
+        dot.push_str(&format!(
+            "    n{} [label=\"{}\\n({})\"];\n",
+            noun.noun_id.unwrap(),
+            escape_dot(&noun.name),
+            escape_dot(&noun.noun_type)
+        ));
+    }
+    for edge in graph.edges.iter() {
+        dot.push_str(&format!(
+            "    n{} -> n{} [label=\"{}\"];\n",
+            edge.source_noun_id,
+            edge.target_noun_id,
+            escape_dot(&edge.label)
+        ));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+pub fn render_mermaid(graph: &NounGraph) -> String {
+    let mut mermaid = "graph LR\n".to_owned();
+    for noun in graph.nouns.iter() {
+        mermaid.push_str(&format!(
+            "    n{}[\"{} ({})\"]\n",
+            noun.noun_id.unwrap(),
+            escape_mermaid(&noun.name),
+            escape_mermaid(&noun.noun_type)
+        ));
+    }
+    for edge in graph.edges.iter() {
+        mermaid.push_str(&format!(
+            "    n{} -->|\"{}\"| n{}\n",
+            edge.source_noun_id,
+            escape_mermaid(&edge.label),
+            edge.target_noun_id
+        ));
+    }
+    mermaid
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;").replace('\n', " ")
+}
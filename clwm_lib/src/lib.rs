@@ -1,4 +1,6 @@
+pub mod calendar;
 pub mod data_interface;
+pub mod dice;
 pub mod model;
 pub mod data_interfaces {
     pub mod data_interface_sqlite;
@@ -6,3 +8,10 @@ pub mod data_interfaces {
 pub mod clwm;
 pub mod clwm_error;
 pub mod clwm_file;
+pub mod formula;
+pub mod graph;
+pub mod roll;
+pub mod site;
+pub mod table;
+pub mod vault;
+pub mod wiki;
@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
 use chrono::serde::ts_seconds_option;
 use chrono::{DateTime, Utc};
@@ -11,18 +13,234 @@ pub struct Noun {
     pub last_changed: Option<DateTime<Utc>>,
     pub name: String,
     pub noun_type: String,
+    pub parent_noun_id: Option<i64>,
     pub metadata: String,
+    #[serde(default)]
+    pub visibility: Visibility,
     pub attributes: Option<Vec<Attribute>>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChangeSet {
+    pub change_set_id: Option<i64>,
+    #[serde(with = "ts_seconds_option")]
+    pub change_date: Option<DateTime<Utc>>,
+    pub change_source: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct NounHistory {
     pub noun_id: i64,
+    #[serde(default)]
+    pub change_set_id: Option<i64>,
     #[serde(with = "ts_seconds_option")]
     pub change_date: Option<DateTime<Utc>>,
     pub diff_name: String,
     pub diff_noun_type: String,
+    pub diff_parent_noun_id: String,
     pub diff_metadata: String,
+    pub diff_visibility: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub enum Visibility {
+    #[default]
+    Public,
+    Group(String),
+    GameMaster,
+}
+
+impl FromStr for Visibility {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((kind, group)) = s.split_once(':') {
+            return match kind.to_lowercase().replace(['-', '_'], "").as_str() {
+                "group" | "playergroup" if !group.is_empty() => {
+                    Ok(Visibility::Group(group.to_owned()))
+                }
+                _ => Err(anyhow::anyhow!("unknown visibility {:?}", s)),
+            };
+        }
+        match s.to_lowercase().replace(['-', '_'], "").as_str() {
+            "public" => Ok(Visibility::Public),
+            "gm" | "gmonly" | "gamemaster" => Ok(Visibility::GameMaster),
+            _ => Err(anyhow::anyhow!("unknown visibility {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Visibility::Public => write!(f, "Public"),
+            Visibility::Group(group) => write!(f, "Group:{}", group),
+            Visibility::GameMaster => write!(f, "GameMaster"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub enum Viewer {
+    #[default]
+    GameMaster,
+    Player(Vec<String>),
+}
+
+impl Viewer {
+    pub fn can_see(&self, visibility: &Visibility) -> bool {
+        match (self, visibility) {
+            (Viewer::GameMaster, _) => true,
+            (Viewer::Player(_), Visibility::Public) => true,
+            (Viewer::Player(groups), Visibility::Group(group)) => groups.contains(group),
+            (Viewer::Player(_), Visibility::GameMaster) => false,
+        }
+    }
+}
+
+impl FromStr for Viewer {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, groups) = s.split_once(':').unwrap_or((s, ""));
+        match kind.to_lowercase().replace(['-', '_'], "").as_str() {
+            "gm" | "gamemaster" => Ok(Viewer::GameMaster),
+            "player" => Ok(Viewer::Player(
+                groups
+                    .split(',')
+                    .map(|group| group.trim())
+                    .filter(|group| !group.is_empty())
+                    .map(|group| group.to_owned())
+                    .collect(),
+            )),
+            _ => Err(anyhow::anyhow!("unknown viewer {:?}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RedactionRules {
+    #[serde(default)]
+    pub noun_types: Vec<String>,
+    #[serde(default)]
+    pub attribute_types: Vec<String>,
+    #[serde(default)]
+    pub include_nouns: Vec<i64>,
+    #[serde(default)]
+    pub exclude_nouns: Vec<i64>,
+    #[serde(default)]
+    pub calendars: Vec<String>,
+    #[serde(default = "default_placeholder_name")]
+    pub placeholder_name: String,
+    #[serde(default = "default_placeholder_name")]
+    pub placeholder_noun_type: String,
+}
+
+fn default_placeholder_name() -> String {
+    "Unknown".to_owned()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RedactionSummary {
+    pub nouns_removed: usize,
+    pub attributes_removed: usize,
+    pub placeholders_created: usize,
+    pub assets_removed: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VaultMapping {
+    #[serde(default = "default_vault_type_key")]
+    pub type_key: String,
+    #[serde(default)]
+    pub folders: HashMap<String, String>,
+    #[serde(default)]
+    pub fields: HashMap<String, String>,
+    #[serde(default)]
+    pub ignore_fields: Vec<String>,
+    #[serde(default = "default_vault_noun_type")]
+    pub default_noun_type: String,
+    #[serde(default = "default_vault_body_attribute")]
+    pub body_attribute: String,
+    #[serde(default = "default_vault_links_attribute")]
+    pub links_attribute: String,
+}
+
+impl Default for VaultMapping {
+    fn default() -> Self {
+        VaultMapping {
+            type_key: default_vault_type_key(),
+            folders: HashMap::new(),
+            fields: HashMap::new(),
+            ignore_fields: Vec::new(),
+            default_noun_type: default_vault_noun_type(),
+            body_attribute: default_vault_body_attribute(),
+            links_attribute: default_vault_links_attribute(),
+        }
+    }
+}
+
+fn default_vault_type_key() -> String {
+    "type".to_owned()
+}
+
+fn default_vault_noun_type() -> String {
+    "Note".to_owned()
+}
+
+fn default_vault_body_attribute() -> String {
+    "Body".to_owned()
+}
+
+fn default_vault_links_attribute() -> String {
+    "Links".to_owned()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct VaultImportPlan {
+    pub noun_types: Vec<VaultPlannedType>,
+    pub data_types: Vec<VaultPlannedDataType>,
+    pub attribute_types: Vec<VaultPlannedAttributeType>,
+    pub nouns: Vec<VaultPlannedNoun>,
+    pub unresolved_links: Vec<VaultNoteIssue>,
+    pub skipped_fields: Vec<VaultNoteIssue>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VaultPlannedType {
+    pub name: String,
+    pub exists: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VaultPlannedDataType {
+    pub name: String,
+    pub definition: DataTypeDefinition,
+    pub exists: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VaultPlannedAttributeType {
+    pub attribute_name: String,
+    pub data_type: String,
+    pub source: String,
+    pub exists: bool,
+}
+
+/// A noun to be created from a note. `NounReference` values in the attributes
+/// hold the index of the target note in `VaultImportPlan::nouns` until imported.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VaultPlannedNoun {
+    pub path: String,
+    pub name: String,
+    pub noun_type: String,
+    pub attributes: Vec<(String, DataObject)>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VaultNoteIssue {
+    pub path: String,
+    pub detail: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -31,18 +249,385 @@ pub struct NounType {
     #[serde(with = "ts_seconds_option")]
     pub last_changed: Option<DateTime<Utc>>,
     pub noun_type: String,
+    pub parent_noun_type_id: Option<i64>,
     pub metadata: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct NounTypeHistory {
     pub noun_type_id: i64,
+    #[serde(default)]
+    pub change_set_id: Option<i64>,
     #[serde(with = "ts_seconds_option")]
     pub change_date: Option<DateTime<Utc>>,
     pub diff_noun_type: String,
+    pub diff_parent_noun_type_id: String,
     pub diff_metadata: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeRequirement {
+    Required,
+    Optional,
+    Forbidden,
+}
+
+impl FromStr for AttributeRequirement {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "required" => Ok(AttributeRequirement::Required),
+            "optional" => Ok(AttributeRequirement::Optional),
+            "forbidden" => Ok(AttributeRequirement::Forbidden),
+            _ => Err(anyhow::anyhow!("unknown attribute requirement {:?}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NounTypeAttributeRule {
+    pub noun_type_id: i64,
+    pub attribute_type_id: i64,
+    #[serde(with = "ts_seconds_option")]
+    pub last_changed: Option<DateTime<Utc>>,
+    pub requirement: AttributeRequirement,
+    pub min_count: i64,
+    pub max_count: Option<i64>,
+    pub default_data: Option<DataObject>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum NounValidationIssue {
+    MissingRequiredAttribute {
+        attribute_type: String,
+        min_count: i64,
+        found: i64,
+    },
+    TooManyAttributes {
+        attribute_type: String,
+        max_count: i64,
+        found: i64,
+    },
+    ForbiddenAttribute {
+        attribute_type: String,
+        found: i64,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Template {
+    #[serde(default)]
+    pub template_id: Option<i64>,
+    #[serde(default, with = "ts_seconds_option")]
+    pub last_changed: Option<DateTime<Utc>>,
+    pub template_name: String,
+    pub noun_type: String,
+    #[serde(default)]
+    pub attributes: Vec<TemplateAttribute>,
+    #[serde(default)]
+    pub metadata: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TemplateAttribute {
+    pub attribute_type_id: i64,
+    pub data: DataObject,
+    #[serde(default)]
+    pub metadata: String,
+    #[serde(default)]
+    pub children: Vec<TemplateAttribute>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RollTable {
+    #[serde(default)]
+    pub roll_table_id: Option<i64>,
+    #[serde(default, with = "ts_seconds_option")]
+    pub last_changed: Option<DateTime<Utc>>,
+    pub roll_table_name: String,
+    pub entries: Vec<RollTableEntry>,
+    #[serde(default)]
+    pub metadata: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RollTableEntry {
+    #[serde(default = "default_roll_weight")]
+    pub weight: i64,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub table: Option<String>,
+    #[serde(default)]
+    pub nouns: Option<RollNounFilter>,
+}
+
+fn default_roll_weight() -> i64 {
+    1
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RollNounFilter {
+    #[serde(default)]
+    pub noun_type: Option<String>,
+    #[serde(default)]
+    pub include_subtypes: bool,
+    #[serde(default)]
+    pub attribute_type_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RollResult {
+    pub text: String,
+    pub noun_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RelationshipCardinality {
+    OneToOne,
+    OneToMany,
+    ManyToOne,
+    ManyToMany,
+}
+
+impl FromStr for RelationshipCardinality {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_'], "").as_str() {
+            "onetoone" => Ok(RelationshipCardinality::OneToOne),
+            "onetomany" => Ok(RelationshipCardinality::OneToMany),
+            "manytoone" => Ok(RelationshipCardinality::ManyToOne),
+            "manytomany" => Ok(RelationshipCardinality::ManyToMany),
+            _ => Err(anyhow::anyhow!("unknown relationship cardinality {:?}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RelationshipType {
+    pub relationship_type_id: Option<i64>,
+    #[serde(with = "ts_seconds_option")]
+    pub last_changed: Option<DateTime<Utc>>,
+    pub relationship_name: String,
+    pub inverse_name: String,
+    pub cardinality: RelationshipCardinality,
+    pub metadata: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Relationship {
+    pub relationship_id: Option<i64>,
+    #[serde(with = "ts_seconds_option")]
+    pub last_changed: Option<DateTime<Utc>>,
+    pub relationship_type: String,
+    pub source_noun_id: i64,
+    pub target_noun_id: i64,
+    pub metadata: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RelationshipHistory {
+    pub relationship_id: i64,
+    #[serde(default)]
+    pub change_set_id: Option<i64>,
+    #[serde(with = "ts_seconds_option")]
+    pub change_date: Option<DateTime<Utc>>,
+    pub diff_relationship_type: String,
+    pub diff_source_noun_id: String,
+    pub diff_target_noun_id: String,
+    pub diff_metadata: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RelationDirection {
+    Outgoing,
+    Incoming,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NounRelation {
+    pub relation_name: String,
+    pub direction: RelationDirection,
+    pub other_noun_id: i64,
+    pub relationship: Relationship,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NounReferenceEdge {
+    pub attribute_id: i64,
+    pub attribute_type_id: i64,
+    pub source_noun_id: i64,
+    pub target_noun_id: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TraversalDirection {
+    Outgoing,
+    Incoming,
+    Both,
+}
+
+impl FromStr for TraversalDirection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "outgoing" => Ok(TraversalDirection::Outgoing),
+            "incoming" => Ok(TraversalDirection::Incoming),
+            "both" => Ok(TraversalDirection::Both),
+            _ => Err(anyhow::anyhow!("unknown traversal direction {:?}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TraversalOptions {
+    pub max_depth: i64,
+    pub direction: TraversalDirection,
+    pub attribute_type_ids: Option<Vec<i64>>,
+    pub relationship_types: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TraversalResult {
+    pub noun_id: i64,
+    pub depth: i64,
+    pub path: Vec<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct GraphFilter {
+    pub noun_types: Vec<String>,
+    pub root_noun_id: Option<i64>,
+    pub max_depth: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GraphEdge {
+    pub source_noun_id: i64,
+    pub target_noun_id: i64,
+    pub label: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NounGraph {
+    pub nouns: Vec<Noun>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum EventRole {
+    Participant,
+    Location,
+}
+
+impl FromStr for EventRole {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "participant" => Ok(EventRole::Participant),
+            "location" => Ok(EventRole::Location),
+            _ => Err(anyhow::anyhow!("unknown event role {:?}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EventParticipant {
+    pub noun_id: i64,
+    pub role: EventRole,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Event {
+    pub noun_id: i64,
+    #[serde(with = "ts_seconds_option")]
+    pub last_changed: Option<DateTime<Utc>>,
+    pub start_day: i64,
+    pub end_day: Option<i64>,
+    pub participants: Option<Vec<EventParticipant>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EventHistory {
+    pub noun_id: i64,
+    #[serde(default)]
+    pub change_set_id: Option<i64>,
+    #[serde(with = "ts_seconds_option")]
+    pub change_date: Option<DateTime<Utc>>,
+    pub diff_start_day: String,
+    pub diff_end_day: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TimelineFilter {
+    pub from_day: Option<i64>,
+    pub to_day: Option<i64>,
+    pub involving_noun_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimelineEntry {
+    pub noun: Noun,
+    pub event: Event,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Calendar {
+    pub calendar_name: String,
+    #[serde(with = "ts_seconds_option")]
+    pub last_changed: Option<DateTime<Utc>>,
+    pub definition: CalendarDefinition,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CalendarDefinition {
+    pub months: Vec<CalendarMonth>,
+    #[serde(default)]
+    pub weekdays: Vec<String>,
+    #[serde(default)]
+    pub epoch_weekday: i64,
+    #[serde(default)]
+    pub leap_rule: Option<CalendarLeapRule>,
+    #[serde(default)]
+    pub eras: Vec<CalendarEra>,
+    #[serde(default)]
+    pub moons: Vec<CalendarMoon>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CalendarMonth {
+    pub name: String,
+    pub days: i64,
+    #[serde(default)]
+    pub leap_days: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CalendarLeapRule {
+    pub every: i64,
+    pub except_every: Option<i64>,
+    pub unless_every: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CalendarEra {
+    pub name: String,
+    pub abbreviation: String,
+    pub start_year: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CalendarMoon {
+    pub name: String,
+    pub cycle_days: i64,
+    #[serde(default)]
+    pub offset_days: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DataType {
     pub name: String,
@@ -53,7 +638,7 @@ pub struct DataType {
     pub change_date: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum DataTypeDefinition {
     Text,
     LongText,
@@ -62,10 +647,13 @@ pub enum DataTypeDefinition {
     Float,
     NounReference,
     Array(Box<DataTypeDefinition>),
+    Map(Box<DataTypeDefinition>, Box<DataTypeDefinition>),
+    Asset,
+    Dice,
     Custom(CustomDataTypeDefinition),
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct CustomDataTypeDefinition(pub HashMap<String, DataTypeDefinition>);
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -78,12 +666,43 @@ pub enum DataObject {
     Float(f64),
     NounReference(i64),
     Array(Vec<DataObject>),
+    Map(MapDataObject),
+    Asset(AssetReference),
+    Dice(String),
     Custom(CustomDataObject),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct CustomDataObject(pub HashMap<String, DataObject>);
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MapDataObject(pub Vec<(DataObject, DataObject)>);
+
+impl MapDataObject {
+    pub fn get(&self, key: &DataObject) -> Option<&DataObject> {
+        self.0
+            .iter()
+            .find(|(entry_key, _)| entry_key == key)
+            .map(|(_, value)| value)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AssetReference {
+    pub asset_hash: String,
+    pub mime_type: String,
+    pub size: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Asset {
+    pub asset_hash: String,
+    #[serde(with = "ts_seconds_option")]
+    pub last_changed: Option<DateTime<Utc>>,
+    pub mime_type: String,
+    pub size: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AttributeType {
     pub attribute_type_id: Option<i64>,
@@ -92,16 +711,20 @@ pub struct AttributeType {
     pub attribute_name: String,
     pub data_type: String,
     pub multiple_allowed: bool,
+    pub formula: Option<String>,
     pub metadata: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AttributeTypeHistory {
     pub attribute_type_id: i64,
+    #[serde(default)]
+    pub change_set_id: Option<i64>,
     #[serde(with = "ts_seconds_option")]
     pub change_date: Option<DateTime<Utc>>,
     pub diff_attribute_name: String,
     pub diff_multiple_allowed: String,
+    pub diff_formula: String,
     pub diff_metadata: String,
 }
 
@@ -116,15 +739,77 @@ pub struct Attribute {
     pub data: DataObject,
     pub data_type_version: i64,
     pub metadata: String,
+    #[serde(default)]
+    pub visibility: Visibility,
     pub children: Option<Vec<Attribute>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AttributeHistory {
     pub attribute_id: i64,
+    #[serde(default)]
+    pub change_set_id: Option<i64>,
     #[serde(with = "ts_seconds_option")]
     pub change_date: Option<DateTime<Utc>>,
     pub diff_data: String,
     pub diff_data_type_version: String,
     pub diff_metadata: String,
+    pub diff_visibility: String,
+}
+
+pub const WORLD_BUNDLE_VERSION: i64 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorldBundle {
+    pub format_version: i64,
+    #[serde(default)]
+    pub noun_types: Vec<NounType>,
+    #[serde(default)]
+    pub noun_type_attribute_rules: Vec<NounTypeAttributeRule>,
+    #[serde(default)]
+    pub data_types: Vec<DataType>,
+    #[serde(default)]
+    pub attribute_types: Vec<AttributeType>,
+    #[serde(default)]
+    pub nouns: Vec<Noun>,
+    #[serde(default)]
+    pub relationship_types: Vec<RelationshipType>,
+    #[serde(default)]
+    pub relationships: Vec<Relationship>,
+    #[serde(default)]
+    pub events: Vec<Event>,
+    #[serde(default)]
+    pub calendars: Vec<Calendar>,
+    #[serde(default)]
+    pub roll_tables: Vec<RollTable>,
+    #[serde(default)]
+    pub templates: Vec<Template>,
+    #[serde(default)]
+    pub assets: Vec<BundleAsset>,
+    #[serde(default)]
+    pub history: Option<WorldHistory>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundleAsset {
+    pub asset: Asset,
+    pub content: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct WorldHistory {
+    #[serde(default)]
+    pub change_sets: Vec<ChangeSet>,
+    #[serde(default)]
+    pub noun_types: Vec<NounTypeHistory>,
+    #[serde(default)]
+    pub attribute_types: Vec<AttributeTypeHistory>,
+    #[serde(default)]
+    pub nouns: Vec<NounHistory>,
+    #[serde(default)]
+    pub attributes: Vec<AttributeHistory>,
+    #[serde(default)]
+    pub relationships: Vec<RelationshipHistory>,
+    #[serde(default)]
+    pub events: Vec<EventHistory>,
 }
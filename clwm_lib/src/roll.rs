@@ -0,0 +1,117 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    clwm_error::ClwmError,
+    dice::{DiceExpression, DiceRoll},
+    model::{RollTable, RollTableEntry},
+};
+
+pub const MAX_ROLL_DEPTH: usize = 16;
+
+pub struct RollGenerator {
+    rng: StdRng,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RollTextPart {
+    Literal(String),
+    Table(String),
+    Dice(DiceExpression),
+}
+
+impl RollGenerator {
+    pub fn new(seed: Option<u64>) -> RollGenerator {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        RollGenerator { rng }
+    }
+
+    pub fn roll_dice(&mut self, expression: &DiceExpression) -> DiceRoll {
+        expression.roll(&mut self.rng)
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        items.get(self.rng.gen_range(0..items.len()))
+    }
+
+    pub fn pick_entry<'a>(&mut self, entries: &'a [RollTableEntry]) -> Option<&'a RollTableEntry> {
+        let total_weight: i64 = entries.iter().map(|entry| entry.weight).sum();
+        if total_weight < 1 {
+            return None;
+        }
+        let mut target = self.rng.gen_range(0..total_weight);
+        for entry in entries.iter() {
+            if target < entry.weight {
+                return Some(entry);
+            }
+            target -= entry.weight;
+        }
+        None
+    }
+}
+
+pub fn check_roll_table(roll_table: &RollTable) -> anyhow::Result<()> {
+    if roll_table.entries.is_empty() {
+        anyhow::bail!(invalid_roll_table("a roll table needs at least one entry"))
+    }
+    for entry in roll_table.entries.iter() {
+        if entry.weight < 1 {
+            anyhow::bail!(invalid_roll_table(
+                "every entry needs a weight of at least one"
+            ))
+        }
+        let results = [
+            entry.text.is_some(),
+            entry.table.is_some(),
+            entry.nouns.is_some(),
+        ];
+        if results.iter().filter(|is_set| **is_set).count() != 1 {
+            anyhow::bail!(invalid_roll_table(
+                "every entry needs exactly one of text, table or nouns"
+            ))
+        }
+        if let Some(text) = &entry.text {
+            parse_roll_text(text)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn parse_roll_text(text: &str) -> anyhow::Result<Vec<RollTextPart>> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let end = match rest[start..].find("}}") {
+            Some(end) => start + end,
+            None => break,
+        };
+        if start > 0 {
+            parts.push(RollTextPart::Literal(rest[..start].to_owned()));
+        }
+        let placeholder = rest[start + 2..end].trim();
+        match placeholder.split_once(':') {
+            Some(("table", table)) => parts.push(RollTextPart::Table(table.trim().to_owned())),
+            Some(("dice", dice)) => parts.push(RollTextPart::Dice(dice.parse()?)),
+            _ => anyhow::bail!(invalid_roll_table(&format!(
+                "unknown placeholder {{{{{}}}}}",
+                placeholder
+            ))),
+        }
+        rest = &rest[end + 2..];
+    }
+    if !rest.is_empty() {
+        parts.push(RollTextPart::Literal(rest.to_owned()));
+    }
+    Ok(parts)
+}
+
+fn invalid_roll_table(message: &str) -> ClwmError {
+    ClwmError::InvalidRollTable {
+        message: message.to_owned(),
+    }
+}
@@ -0,0 +1,197 @@
+use pulldown_cmark::{escape::escape_html, html, CowStr, Event, Parser, Tag};
+use serde::Serialize;
+
+use crate::{
+    model::{Asset, BundleAsset},
+    wiki::WikiPage,
+};
+
+#[derive(Debug, Clone)]
+pub struct SiteFile {
+    pub path: String,
+    pub content: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct SearchEntry {
+    title: String,
+    url: String,
+    text: String,
+}
+
+const STYLE: &str = r#"body { font-family: sans-serif; max-width: 50em; margin: 0 auto; padding: 1em; line-height: 1.5; color: #222; }
+header { display: flex; gap: 1em; align-items: center; border-bottom: 1px solid #ccc; padding-bottom: 0.5em; position: relative; }
+header input { flex: 1; padding: 0.3em; }
+#results { position: absolute; top: 2.5em; right: 0; left: 6em; background: #fff; border: 1px solid #ccc; list-style: none; margin: 0; padding: 0; }
+#results:empty { display: none; }
+#results li { padding: 0.3em 0.5em; }
+#results small { color: #777; }
+img { max-width: 100%; }
+a { color: #2a5db0; }
+"#;
+
+const SEARCH_SCRIPT: &str = r#"
+function clwmSearch(input) {
+  var results = document.getElementById("results");
+  var query = input.value.trim().toLowerCase();
+  results.innerHTML = "";
+  if (query.length === 0) {
+    return;
+  }
+  SEARCH_INDEX.filter(function (entry) {
+    return entry.title.toLowerCase().indexOf(query) !== -1 || entry.text.toLowerCase().indexOf(query) !== -1;
+  }).slice(0, 20).forEach(function (entry) {
+    var item = document.createElement("li");
+    var link = document.createElement("a");
+    link.href = SITE_ROOT + entry.url;
+    link.textContent = entry.title;
+    var url = document.createElement("small");
+    url.textContent = " " + entry.url;
+    item.appendChild(link);
+    item.appendChild(url);
+    results.appendChild(item);
+  });
+}
+"#;
+
+pub fn render_site(pages: &[WikiPage], assets: &[BundleAsset]) -> anyhow::Result<Vec<SiteFile>> {
+    let mut files = Vec::new();
+    let mut search_index = Vec::new();
+    for page in pages.iter() {
+        let path = format!("{}.html", page.path.trim_end_matches(".md"));
+        let root = "../".repeat(path.matches('/').count());
+        let title = page
+            .content
+            .lines()
+            .find_map(|line| line.strip_prefix("# "))
+            .unwrap_or(&path)
+            .to_owned();
+
+        let mut text = String::new();
+        let events: Vec<Event> = Parser::new(&page.content)
+            .map(|event| match event {
+                Event::Start(Tag::Link(link_type, destination, link_title)) => {
+                    Event::Start(Tag::Link(link_type, html_link(destination), link_title))
+                }
+                Event::End(Tag::Link(link_type, destination, link_title)) => {
+                    Event::End(Tag::Link(link_type, html_link(destination), link_title))
+                }
+                Event::Code(code) => match code
+                    .strip_prefix("asset:")
+                    .and_then(|hash| assets.iter().find(|x| x.asset.asset_hash == hash))
+                {
+                    Some(bundle_asset) => {
+                        Event::Html(asset_html(&bundle_asset.asset, &root).into())
+                    }
+                    None => Event::Code(code),
+                },
+                Event::Text(content) => {
+                    text.push_str(&content);
+                    text.push(' ');
+                    Event::Text(content)
+                }
+                // Names, metadata and text come from the world, so raw HTML in
+                // them is shown as text rather than run on the player's site.
+                Event::Html(content) => Event::Text(content),
+                event => event,
+            })
+            .collect();
+        let mut body = String::new();
+        html::push_html(&mut body, events.into_iter());
+
+        files.push(SiteFile {
+            path: path.clone(),
+            content: render_html_page(&title, &root, &body)?.into_bytes(),
+        });
+        search_index.push(SearchEntry {
+            title,
+            url: path,
+            text,
+        });
+    }
+
+    for bundle_asset in assets.iter() {
+        files.push(SiteFile {
+            path: format!("assets/{}", asset_file_name(&bundle_asset.asset)),
+            content: bundle_asset.content.clone(),
+        });
+    }
+    files.push(SiteFile {
+        path: "style.css".to_owned(),
+        content: STYLE.as_bytes().to_vec(),
+    });
+    files.push(SiteFile {
+        path: "search.js".to_owned(),
+        content: format!(
+            "var SEARCH_INDEX = {};\n{}",
+            serde_json::to_string(&search_index)?,
+            SEARCH_SCRIPT
+        )
+        .into_bytes(),
+    });
+    Ok(files)
+}
+
+pub fn asset_file_name(asset: &Asset) -> String {
+    let extension = match asset.mime_type.split('/').nth(1).unwrap_or_default() {
+        "jpeg" => "jpg",
+        "svg+xml" => "svg",
+        "plain" => "txt",
+        "" => "bin",
+        subtype => subtype,
+    };
+    format!("{}.{}", asset.asset_hash, extension)
+}
+
+fn html_link(destination: CowStr) -> CowStr {
+    if destination
+        .trim_start()
+        .to_lowercase()
+        .starts_with("javascript:")
+    {
+        return "#".into();
+    }
+    match destination.strip_suffix(".md") {
+        Some(page) => format!("{}.html", page).into(),
+        None => destination,
+    }
+}
+
+fn asset_html(asset: &Asset, root: &str) -> String {
+    let source = format!("{}assets/{}", root, asset_file_name(asset));
+    if asset.mime_type.starts_with("image/") {
+        format!("<img src=\"{}\" alt=\"{}\">", source, asset.asset_hash)
+    } else {
+        format!("<a href=\"{}\">{}</a>", source, asset.mime_type)
+    }
+}
+
+fn render_html_page(title: &str, root: &str, body: &str) -> anyhow::Result<String> {
+    let mut escaped_title = String::new();
+    escape_html(&mut escaped_title, title)?;
+    Ok(format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<link rel="stylesheet" href="{root}style.css">
+<script>var SITE_ROOT = "{root}";</script>
+<script src="{root}search.js"></script>
+</head>
+<body>
+<header>
+<a href="{root}index.html">Index</a>
+<input type="search" placeholder="Search" oninput="clwmSearch(this)">
+<ul id="results"></ul>
+</header>
+<main>
+{body}</main>
+</body>
+</html>
+"#,
+        title = escaped_title,
+        root = root,
+        body = body
+    ))
+}
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::{
+    dice::DiceExpression,
+    model::{AttributeType, CustomDataObject, DataObject, DataTypeDefinition},
+};
+
+/// One row per noun: an `id` and `name` column followed by a column per
+/// attribute type, with `Custom` fields flattened into `attribute.field` columns.
+#[derive(Debug, Clone, Default)]
+pub struct NounTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TableColumn {
+    pub attribute_type_id: i64,
+    pub header: String,
+    pub path: Vec<String>,
+    pub definition: DataTypeDefinition,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableRowError {
+    pub row: usize,
+    pub column: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TableImportSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub errors: Vec<TableRowError>,
+}
+
+pub const ID_COLUMN: &str = "id";
+pub const NAME_COLUMN: &str = "name";
+
+pub fn attribute_columns(
+    attribute_type: &AttributeType,
+    definition: &DataTypeDefinition,
+) -> Vec<TableColumn> {
+    let mut columns = Vec::new();
+    push_columns(attribute_type, definition, &mut Vec::new(), &mut columns);
+    columns
+}
+
+fn push_columns(
+    attribute_type: &AttributeType,
+    definition: &DataTypeDefinition,
+    path: &mut Vec<String>,
+    columns: &mut Vec<TableColumn>,
+) {
+    match definition {
+        DataTypeDefinition::Custom(custom) => {
+            let mut fields: Vec<(&String, &DataTypeDefinition)> = custom.0.iter().collect();
+            fields.sort_by_key(|(field, _)| *field);
+            for (field, field_definition) in fields {
+                path.push(field.clone());
+                push_columns(attribute_type, field_definition, path, columns);
+                path.pop();
+            }
+        }
+        _ => {
+            let mut header = attribute_type.attribute_name.clone();
+            for field in path.iter() {
+                header.push('.');
+                header.push_str(field);
+            }
+            columns.push(TableColumn {
+                attribute_type_id: attribute_type.attribute_type_id.unwrap(),
+                header,
+                path: path.clone(),
+                definition: definition.clone(),
+            });
+        }
+    }
+}
+
+pub fn column_value<'a>(data: &'a DataObject, path: &[String]) -> Option<&'a DataObject> {
+    match path.split_first() {
+        None => Some(data),
+        Some((field, rest)) => match data {
+            DataObject::Custom(custom) => column_value(custom.0.get(field)?, rest),
+            _ => None,
+        },
+    }
+}
+
+pub fn set_column_value(data: &mut DataObject, path: &[String], value: DataObject) {
+    match path.split_first() {
+        None => *data = value,
+        Some((field, rest)) => {
+            if !matches!(data, DataObject::Custom(_)) {
+                *data = DataObject::Custom(CustomDataObject(HashMap::new()));
+            }
+            if let DataObject::Custom(custom) = data {
+                let child = custom.0.entry(field.clone()).or_insert(DataObject::Null);
+                set_column_value(child, rest, value);
+            }
+        }
+    }
+}
+
+pub fn encode_cell(data: &DataObject) -> String {
+    match data {
+        DataObject::Null => String::new(),
+        DataObject::Text(text) | DataObject::LongText(text) | DataObject::Dice(text) => {
+            text.clone()
+        }
+        DataObject::Boolean(value) => value.to_string(),
+        DataObject::Integer(value) => value.to_string(),
+        DataObject::Float(value) => value.to_string(),
+        DataObject::NounReference(noun_id) => noun_id.to_string(),
+        _ => serde_json::to_string(data).unwrap_or_default(),
+    }
+}
+
+pub fn decode_cell(cell: &str, definition: &DataTypeDefinition) -> Result<DataObject, String> {
+    let value = cell.trim();
+    match definition {
+        DataTypeDefinition::Text => Ok(DataObject::Text(cell.to_owned())),
+        DataTypeDefinition::LongText => Ok(DataObject::LongText(cell.to_owned())),
+        DataTypeDefinition::Boolean => match value.to_lowercase().as_str() {
+            "true" => Ok(DataObject::Boolean(true)),
+            "false" => Ok(DataObject::Boolean(false)),
+            _ => Err(format!("{:?} is not a boolean", value)),
+        },
+        DataTypeDefinition::Integer => value
+            .parse()
+            .map(DataObject::Integer)
+            .map_err(|_| format!("{:?} is not an integer", value)),
+        DataTypeDefinition::Float => value
+            .parse()
+            .map(DataObject::Float)
+            .map_err(|_| format!("{:?} is not a number", value)),
+        DataTypeDefinition::NounReference => value
+            .trim_start_matches('#')
+            .parse()
+            .map(DataObject::NounReference)
+            .map_err(|_| format!("{:?} is not a noun id", value)),
+        DataTypeDefinition::Dice => match value.parse::<DiceExpression>() {
+            Ok(_) => Ok(DataObject::Dice(value.to_owned())),
+            Err(_) => Err(format!("{:?} is not a dice expression", value)),
+        },
+        _ => serde_json::from_str(value)
+            .map_err(|error| format!("{:?} is not a valid JSON data object: {}", value, error)),
+    }
+}
@@ -0,0 +1,486 @@
+use std::collections::HashMap;
+
+use serde_yaml::Value;
+
+use crate::{
+    clwm_error::ClwmError,
+    model::{
+        AttributeType, DataObject, DataType, DataTypeDefinition, NounType, VaultImportPlan,
+        VaultMapping, VaultNoteIssue, VaultPlannedAttributeType, VaultPlannedDataType,
+        VaultPlannedNoun, VaultPlannedType,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct VaultNote {
+    pub path: String,
+    pub content: String,
+}
+
+struct ParsedNote {
+    path: String,
+    name: String,
+    noun_type: String,
+    fields: Vec<(String, Value)>,
+    body: String,
+}
+
+struct LinkIndex(HashMap<String, usize>);
+
+impl LinkIndex {
+    fn resolve(&self, target: &str) -> Option<usize> {
+        let target = target.trim().to_lowercase();
+        self.0
+            .get(target.strip_suffix(".md").unwrap_or(&target))
+            .copied()
+    }
+}
+
+pub fn plan_vault(
+    notes: &[VaultNote],
+    mapping: &VaultMapping,
+    noun_types: &[NounType],
+    data_types: &[DataType],
+    attribute_types: &[AttributeType],
+) -> anyhow::Result<VaultImportPlan> {
+    let mut notes: Vec<&VaultNote> = notes.iter().collect();
+    notes.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut parsed_notes = Vec::new();
+    let mut aliases = Vec::new();
+    for note in notes {
+        let (front_matter, body) = split_front_matter(&note.content);
+        let mut fields: Vec<(String, Value)> = match front_matter {
+            Some(front_matter) if !front_matter.trim().is_empty() => {
+                serde_yaml::from_str::<serde_yaml::Mapping>(front_matter)
+                    .map_err(|error| ClwmError::InvalidFrontMatter {
+                        path: note.path.clone(),
+                        message: error.to_string(),
+                    })?
+                    .into_iter()
+                    .map(|(key, value)| (value_to_text(&key), value))
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        fields.retain(|(key, _)| !mapping.ignore_fields.contains(key));
+
+        let noun_type = match fields.iter().position(|(key, _)| *key == mapping.type_key) {
+            Some(index) => match fields.remove(index).1 {
+                Value::String(noun_type) if !noun_type.trim().is_empty() => {
+                    Some(noun_type.trim().to_owned())
+                }
+                _ => None,
+            },
+            None => None,
+        };
+        let mut note_aliases = Vec::new();
+        if let Some(index) = fields.iter().position(|(key, _)| key == "aliases") {
+            match fields.remove(index).1 {
+                Value::String(alias) => note_aliases.push(alias),
+                Value::Sequence(sequence) => {
+                    note_aliases.extend(sequence.iter().map(value_to_text))
+                }
+                _ => {}
+            }
+        }
+        aliases.push(note_aliases);
+
+        let folder = note.path.rsplit_once('/').map_or("", |(folder, _)| folder);
+        parsed_notes.push(ParsedNote {
+            path: note.path.clone(),
+            name: note
+                .path
+                .rsplit('/')
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches(".md")
+                .to_owned(),
+            noun_type: noun_type.unwrap_or_else(|| folder_noun_type(folder, mapping)),
+            fields,
+            body: body.trim().to_owned(),
+        });
+    }
+
+    let mut link_index = HashMap::new();
+    for (index, note) in parsed_notes.iter().enumerate() {
+        link_index
+            .entry(note.path.trim_end_matches(".md").to_lowercase())
+            .or_insert(index);
+    }
+    for (index, note) in parsed_notes.iter().enumerate() {
+        link_index.entry(note.name.to_lowercase()).or_insert(index);
+    }
+    for (index, note_aliases) in aliases.iter().enumerate() {
+        for alias in note_aliases.iter() {
+            link_index
+                .entry(alias.trim().to_lowercase())
+                .or_insert(index);
+        }
+    }
+    let link_index = LinkIndex(link_index);
+
+    // Each note's values keyed by attribute name, with body text and body links
+    // treated as two more fields.
+    let mut attribute_sources: Vec<(String, String)> = Vec::new();
+    let mut inferred: HashMap<String, DataTypeDefinition> = HashMap::new();
+    let mut note_values: Vec<Vec<(String, Value)>> = Vec::new();
+    for note in parsed_notes.iter() {
+        let mut values = Vec::new();
+        for (key, value) in note.fields.iter() {
+            let attribute_name = mapping.fields.get(key).unwrap_or(key).clone();
+            if !attribute_sources
+                .iter()
+                .any(|(name, _)| *name == attribute_name)
+            {
+                attribute_sources.push((attribute_name.clone(), key.clone()));
+            }
+            if let Some(definition) = infer_definition(value) {
+                let definition = match inferred.remove(&attribute_name) {
+                    Some(existing) => unify_definitions(existing, definition),
+                    None => definition,
+                };
+                inferred.insert(attribute_name.clone(), definition);
+            }
+            values.push((attribute_name, value.clone()));
+        }
+        if !note.body.is_empty() {
+            values.push((
+                mapping.body_attribute.clone(),
+                Value::String(note.body.clone()),
+            ));
+            let mut links: Vec<Value> = Vec::new();
+            for target in wiki_links(&note.body) {
+                let link = Value::String(format!("[[{}]]", target));
+                if !links.contains(&link) {
+                    links.push(link);
+                }
+            }
+            if !links.is_empty() {
+                values.push((mapping.links_attribute.clone(), Value::Sequence(links)));
+            }
+        }
+        note_values.push(values);
+    }
+    if note_values
+        .iter()
+        .flatten()
+        .any(|(name, _)| *name == mapping.body_attribute)
+        && !inferred.contains_key(&mapping.body_attribute)
+    {
+        attribute_sources.push((mapping.body_attribute.clone(), "note body".to_owned()));
+        inferred.insert(mapping.body_attribute.clone(), DataTypeDefinition::LongText);
+    }
+    if note_values
+        .iter()
+        .flatten()
+        .any(|(name, _)| *name == mapping.links_attribute)
+        && !inferred.contains_key(&mapping.links_attribute)
+    {
+        attribute_sources.push((mapping.links_attribute.clone(), "note links".to_owned()));
+        inferred.insert(
+            mapping.links_attribute.clone(),
+            DataTypeDefinition::Array(Box::new(DataTypeDefinition::NounReference)),
+        );
+    }
+
+    let mut latest_data_types: HashMap<&str, &DataType> = HashMap::new();
+    for data_type in data_types.iter() {
+        match latest_data_types.get(data_type.name.as_str()) {
+            Some(latest) if latest.version >= data_type.version => {}
+            _ => {
+                latest_data_types.insert(&data_type.name, data_type);
+            }
+        }
+    }
+
+    let mut plan = VaultImportPlan::default();
+    let mut definitions: HashMap<String, DataTypeDefinition> = HashMap::new();
+    for (attribute_name, source) in attribute_sources {
+        let inferred_definition = match inferred.remove(&attribute_name) {
+            Some(definition) => definition,
+            None => continue,
+        };
+        let existing = attribute_types
+            .iter()
+            .find(|attribute_type| attribute_type.attribute_name == attribute_name);
+        let data_type_name = match existing {
+            Some(attribute_type) => attribute_type.data_type.clone(),
+            None => data_type_name(&inferred_definition),
+        };
+        let found_data_type = latest_data_types.get(data_type_name.as_str());
+        if found_data_type.is_none() && existing.is_some() {
+            anyhow::bail!(ClwmError::DataTypeNotFound)
+        }
+        let definition = found_data_type.map_or(inferred_definition, |data_type| {
+            data_type.definition.clone()
+        });
+        if !plan
+            .data_types
+            .iter()
+            .any(|data_type| data_type.name == data_type_name)
+        {
+            plan.data_types.push(VaultPlannedDataType {
+                name: data_type_name.clone(),
+                definition: definition.clone(),
+                exists: found_data_type.is_some(),
+            });
+        }
+        plan.attribute_types.push(VaultPlannedAttributeType {
+            attribute_name: attribute_name.clone(),
+            data_type: data_type_name,
+            source,
+            exists: existing.is_some(),
+        });
+        definitions.insert(attribute_name, definition);
+    }
+
+    for (note, values) in parsed_notes.iter().zip(note_values) {
+        if !plan
+            .noun_types
+            .iter()
+            .any(|noun_type| noun_type.name == note.noun_type)
+        {
+            plan.noun_types.push(VaultPlannedType {
+                name: note.noun_type.clone(),
+                exists: noun_types
+                    .iter()
+                    .any(|noun_type| noun_type.noun_type == note.noun_type),
+            });
+        }
+
+        let mut attributes = Vec::new();
+        for (attribute_name, value) in values {
+            let definition = match definitions.get(&attribute_name) {
+                Some(definition) => definition,
+                None => continue,
+            };
+            let mut unresolved = Vec::new();
+            match convert_value(&value, definition, &link_index, &mut unresolved) {
+                Some(data) => attributes.push((attribute_name.clone(), data)),
+                None if unresolved.is_empty() && value != Value::Null => {
+                    plan.skipped_fields.push(VaultNoteIssue {
+                        path: note.path.clone(),
+                        detail: format!(
+                            "{} could not be read as {}",
+                            attribute_name,
+                            data_type_name(definition)
+                        ),
+                    })
+                }
+                None => {}
+            }
+            for target in unresolved {
+                plan.unresolved_links.push(VaultNoteIssue {
+                    path: note.path.clone(),
+                    detail: format!("[[{}]] in {}", target, attribute_name),
+                });
+            }
+        }
+        plan.nouns.push(VaultPlannedNoun {
+            path: note.path.clone(),
+            name: note.name.clone(),
+            noun_type: note.noun_type.clone(),
+            attributes,
+        });
+    }
+    Ok(plan)
+}
+
+pub fn data_type_name(definition: &DataTypeDefinition) -> String {
+    match definition {
+        DataTypeDefinition::Text => "Text".to_owned(),
+        DataTypeDefinition::LongText => "LongText".to_owned(),
+        DataTypeDefinition::Boolean => "Boolean".to_owned(),
+        DataTypeDefinition::Integer => "Integer".to_owned(),
+        DataTypeDefinition::Float => "Float".to_owned(),
+        DataTypeDefinition::NounReference => "NounReference".to_owned(),
+        DataTypeDefinition::Asset => "Asset".to_owned(),
+        DataTypeDefinition::Dice => "Dice".to_owned(),
+        DataTypeDefinition::Array(item) => format!("{} List", data_type_name(item)),
+        DataTypeDefinition::Map(key, value) => {
+            format!("{} {} Map", data_type_name(key), data_type_name(value))
+        }
+        DataTypeDefinition::Custom(_) => "Custom".to_owned(),
+    }
+}
+
+fn split_front_matter(content: &str) -> (Option<&str>, &str) {
+    let content = content.trim_start_matches('\u{feff}');
+    let rest = match content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    {
+        Some(rest) => rest,
+        None => return (None, content),
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if matches!(line.trim_end(), "---" | "...") {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    (None, content)
+}
+
+fn folder_noun_type(folder: &str, mapping: &VaultMapping) -> String {
+    let mapped = mapping
+        .folders
+        .iter()
+        .filter(|(mapped_folder, _)| {
+            let mapped_folder = mapped_folder.trim_matches('/');
+            folder == mapped_folder || folder.starts_with(&format!("{}/", mapped_folder))
+        })
+        .max_by_key(|(mapped_folder, _)| mapped_folder.trim_matches('/').len());
+    match mapped {
+        Some((_, noun_type)) => noun_type.clone(),
+        None => match folder.split('/').next() {
+            Some(top_folder) if !top_folder.is_empty() => top_folder.to_owned(),
+            _ => mapping.default_noun_type.clone(),
+        },
+    }
+}
+
+fn wiki_links(text: &str) -> Vec<String> {
+    let mut links = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("[[") {
+        let embedded = rest[..start].ends_with('!');
+        rest = &rest[start + 2..];
+        let end = match rest.find("]]") {
+            Some(end) => end,
+            None => break,
+        };
+        let target = rest[..end]
+            .split(['|', '#'])
+            .next()
+            .unwrap_or_default()
+            .trim();
+        if !embedded && !target.is_empty() {
+            links.push(target.to_owned());
+        }
+        rest = &rest[end + 2..];
+    }
+    links
+}
+
+fn link_target(text: &str) -> Option<String> {
+    let inner = text.trim().strip_prefix("[[")?.strip_suffix("]]")?;
+    if inner.contains("[[") || inner.contains("]]") {
+        return None;
+    }
+    wiki_links(text.trim()).into_iter().next()
+}
+
+fn infer_definition(value: &Value) -> Option<DataTypeDefinition> {
+    match value {
+        Value::Null => None,
+        Value::Bool(_) => Some(DataTypeDefinition::Boolean),
+        Value::Number(number) if number.is_i64() => Some(DataTypeDefinition::Integer),
+        Value::Number(_) => Some(DataTypeDefinition::Float),
+        Value::String(text) if link_target(text).is_some() => {
+            Some(DataTypeDefinition::NounReference)
+        }
+        Value::String(_) | Value::Mapping(_) => Some(DataTypeDefinition::Text),
+        Value::Sequence(sequence) => Some(DataTypeDefinition::Array(Box::new(
+            sequence
+                .iter()
+                .filter_map(infer_definition)
+                .reduce(unify_definitions)
+                .unwrap_or(DataTypeDefinition::Text),
+        ))),
+        Value::Tagged(tagged) => infer_definition(&tagged.value),
+    }
+}
+
+fn unify_definitions(a: DataTypeDefinition, b: DataTypeDefinition) -> DataTypeDefinition {
+    match (a, b) {
+        (a, b) if a == b => a,
+        (DataTypeDefinition::Integer, DataTypeDefinition::Float)
+        | (DataTypeDefinition::Float, DataTypeDefinition::Integer) => DataTypeDefinition::Float,
+        (DataTypeDefinition::Array(a), DataTypeDefinition::Array(b)) => {
+            DataTypeDefinition::Array(Box::new(unify_definitions(*a, *b)))
+        }
+        (DataTypeDefinition::Array(item), other) | (other, DataTypeDefinition::Array(item)) => {
+            DataTypeDefinition::Array(Box::new(unify_definitions(*item, other)))
+        }
+        _ => DataTypeDefinition::Text,
+    }
+}
+
+fn convert_value(
+    value: &Value,
+    definition: &DataTypeDefinition,
+    link_index: &LinkIndex,
+    unresolved: &mut Vec<String>,
+) -> Option<DataObject> {
+    if let Value::Tagged(tagged) = value {
+        return convert_value(&tagged.value, definition, link_index, unresolved);
+    }
+    match (definition, value) {
+        (_, Value::Null) => None,
+        (DataTypeDefinition::Array(item), Value::Sequence(sequence)) => {
+            let items: Vec<DataObject> = sequence
+                .iter()
+                .filter_map(|x| convert_value(x, item, link_index, unresolved))
+                .collect();
+            (!items.is_empty() || sequence.is_empty()).then_some(DataObject::Array(items))
+        }
+        (DataTypeDefinition::Array(item), value) => {
+            convert_value(value, item, link_index, unresolved).map(|x| DataObject::Array(vec![x]))
+        }
+        (DataTypeDefinition::Text, value) => Some(DataObject::Text(value_to_text(value))),
+        (DataTypeDefinition::LongText, Value::String(text)) => {
+            Some(DataObject::LongText(text.clone()))
+        }
+        (DataTypeDefinition::LongText, value) => Some(DataObject::LongText(value_to_text(value))),
+        (DataTypeDefinition::Boolean, Value::Bool(value)) => Some(DataObject::Boolean(*value)),
+        (DataTypeDefinition::Integer, Value::Number(number)) => {
+            number.as_i64().map(DataObject::Integer)
+        }
+        (DataTypeDefinition::Float, Value::Number(number)) => {
+            number.as_f64().map(DataObject::Float)
+        }
+        (DataTypeDefinition::Dice, Value::String(text)) => Some(DataObject::Dice(text.clone())),
+        (DataTypeDefinition::NounReference, Value::String(text)) => {
+            let target = link_target(text).unwrap_or_else(|| text.trim().to_owned());
+            match link_index.resolve(&target) {
+                Some(index) => Some(DataObject::NounReference(index as i64)),
+                None => {
+                    unresolved.push(target);
+                    None
+                }
+            }
+        }
+        (DataTypeDefinition::Boolean, Value::String(text)) => {
+            text.trim().parse().ok().map(DataObject::Boolean)
+        }
+        (DataTypeDefinition::Integer, Value::String(text)) => {
+            text.trim().parse().ok().map(DataObject::Integer)
+        }
+        (DataTypeDefinition::Float, Value::String(text)) => {
+            text.trim().parse().ok().map(DataObject::Float)
+        }
+        _ => None,
+    }
+}
+
+fn value_to_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Bool(value) => value.to_string(),
+        Value::Number(number) => number.to_string(),
+        Value::String(text) => link_target(text).unwrap_or_else(|| text.clone()),
+        Value::Sequence(sequence) => sequence
+            .iter()
+            .map(value_to_text)
+            .collect::<Vec<String>>()
+            .join(", "),
+        Value::Mapping(_) => serde_yaml::to_string(value)
+            .unwrap_or_default()
+            .trim()
+            .to_owned(),
+        Value::Tagged(tagged) => value_to_text(&tagged.value),
+    }
+}
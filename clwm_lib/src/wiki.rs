@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+
+use crate::model::{Attribute, AttributeType, DataObject, Noun, NounType};
+
+#[derive(Debug, Clone)]
+pub struct WikiPage {
+    pub path: String,
+    pub content: String,
+}
+
+struct WikiContext<'a> {
+    nouns: HashMap<i64, &'a Noun>,
+    attribute_names: HashMap<i64, &'a str>,
+}
+
+pub fn render_wiki(
+    nouns: &[Noun],
+    noun_types: &[NounType],
+    attribute_types: &[AttributeType],
+) -> Vec<WikiPage> {
+    let context = WikiContext {
+        nouns: nouns
+            .iter()
+            .filter_map(|noun| noun.noun_id.map(|noun_id| (noun_id, noun)))
+            .collect(),
+        attribute_names: attribute_types
+            .iter()
+            .filter_map(|attribute_type| {
+                attribute_type
+                    .attribute_type_id
+                    .map(|id| (id, attribute_type.attribute_name.as_str()))
+            })
+            .collect(),
+    };
+
+    let mut backlinks: HashMap<i64, Vec<(i64, i64)>> = HashMap::new();
+    for noun in nouns.iter() {
+        let mut references = Vec::new();
+        for attribute in noun.attributes.iter().flatten() {
+            collect_references(attribute, &mut references);
+        }
+        for (attribute_type_id, target_noun_id) in references {
+            let entry = backlinks.entry(target_noun_id).or_default();
+            if !entry.contains(&(noun.noun_id.unwrap(), attribute_type_id)) {
+                entry.push((noun.noun_id.unwrap(), attribute_type_id));
+            }
+        }
+    }
+
+    let mut pages: Vec<WikiPage> = nouns
+        .iter()
+        .map(|noun| WikiPage {
+            path: format!("nouns/{}", noun_file_name(noun)),
+            content: render_noun_page(
+                noun,
+                backlinks.get(&noun.noun_id.unwrap()).map_or(&[], |x| x),
+                &context,
+            ),
+        })
+        .collect();
+    for noun_type in noun_types.iter() {
+        pages.push(WikiPage {
+            path: format!("types/{}.md", slug(&noun_type.noun_type)),
+            content: render_noun_type_page(noun_type, noun_types, nouns),
+        });
+    }
+    pages.push(WikiPage {
+        path: "index.md".to_owned(),
+        content: render_index_page(noun_types, nouns),
+    });
+    pages
+}
+
+pub fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_owned()
+}
+
+/// Escapes the characters that would end or break the text of a Markdown link.
+fn link_text(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        if matches!(c, '\\' | '[' | ']' | '(' | ')') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+pub fn noun_file_name(noun: &Noun) -> String {
+    match slug(&noun.name).as_str() {
+        "" => format!("{}.md", noun.noun_id.unwrap()),
+        name => format!("{}-{}.md", noun.noun_id.unwrap(), name),
+    }
+}
+
+fn render_noun_page(noun: &Noun, backlinks: &[(i64, i64)], context: &WikiContext) -> String {
+    let mut page = format!("# {}\n\n", noun.name);
+    page.push_str(&format!(
+        "**Type:** [{}](../types/{}.md)\n",
+        link_text(&noun.noun_type),
+        slug(&noun.noun_type)
+    ));
+    if let Some(parent_noun_id) = noun.parent_noun_id {
+        page.push_str(&format!(
+            "\n**Inside:** {}\n",
+            noun_link(parent_noun_id, context)
+        ));
+    }
+    if !noun.metadata.trim().is_empty() {
+        page.push_str(&format!("\n{}\n", noun.metadata.trim()));
+    }
+
+    let attributes = noun.attributes.as_deref().unwrap_or_default();
+    if !attributes.is_empty() {
+        page.push_str("\n## Attributes\n\n");
+        for attribute in attributes.iter() {
+            render_attribute(attribute, 0, context, &mut page);
+        }
+    }
+
+    let mut children: Vec<i64> = context
+        .nouns
+        .values()
+        .filter(|child| child.parent_noun_id == noun.noun_id)
+        .filter_map(|child| child.noun_id)
+        .collect();
+    if !children.is_empty() {
+        page.push_str("\n## Contains\n\n");
+        children.sort();
+        for child_id in children {
+            page.push_str(&format!("- {}\n", noun_link(child_id, context)));
+        }
+    }
+
+    if !backlinks.is_empty() {
+        page.push_str("\n## Backlinks\n\n");
+        for (source_noun_id, attribute_type_id) in backlinks.iter() {
+            page.push_str(&format!(
+                "- {} ({})\n",
+                noun_link(*source_noun_id, context),
+                attribute_name(*attribute_type_id, context)
+            ));
+        }
+    }
+    page
+}
+
+fn render_noun_type_page(noun_type: &NounType, noun_types: &[NounType], nouns: &[Noun]) -> String {
+    let mut page = format!("# {}\n", noun_type.noun_type);
+    if let Some(parent) = noun_type
+        .parent_noun_type_id
+        .and_then(|parent_noun_type_id| {
+            noun_types
+                .iter()
+                .find(|parent| parent.noun_type_id == Some(parent_noun_type_id))
+        })
+    {
+        page.push_str(&format!(
+            "\n**Subtype of:** [{}]({}.md)\n",
+            link_text(&parent.noun_type),
+            slug(&parent.noun_type)
+        ));
+    }
+    if !noun_type.metadata.trim().is_empty() {
+        page.push_str(&format!("\n{}\n", noun_type.metadata.trim()));
+    }
+
+    let subtypes: Vec<&NounType> = noun_types
+        .iter()
+        .filter(|subtype| {
+            subtype.parent_noun_type_id.is_some()
+                && subtype.parent_noun_type_id == noun_type.noun_type_id
+        })
+        .collect();
+    if !subtypes.is_empty() {
+        page.push_str("\n## Subtypes\n\n");
+        for subtype in subtypes {
+            page.push_str(&format!(
+                "- [{}]({}.md)\n",
+                link_text(&subtype.noun_type),
+                slug(&subtype.noun_type)
+            ));
+        }
+    }
+
+    page.push_str("\n## Nouns\n\n");
+    for noun in nouns
+        .iter()
+        .filter(|noun| noun.noun_type == noun_type.noun_type)
+    {
+        page.push_str(&format!(
+            "- [{}](../nouns/{})\n",
+            link_text(&noun.name),
+            noun_file_name(noun)
+        ));
+    }
+    page
+}
+
+fn render_index_page(noun_types: &[NounType], nouns: &[Noun]) -> String {
+    let mut page = "# Index\n\n".to_owned();
+    for noun_type in noun_types.iter() {
+        let count = nouns
+            .iter()
+            .filter(|noun| noun.noun_type == noun_type.noun_type)
+            .count();
+        page.push_str(&format!(
+            "- [{}](types/{}.md) ({})\n",
+            link_text(&noun_type.noun_type),
+            slug(&noun_type.noun_type),
+            count
+        ));
+    }
+    page
+}
+
+fn render_attribute(attribute: &Attribute, depth: usize, context: &WikiContext, page: &mut String) {
+    let name = attribute_name(attribute.attribute_type_id, context);
+    render_value(&name, &attribute.data, depth, context, page);
+    for child in attribute.children.iter().flatten() {
+        render_attribute(child, depth + 1, context, page);
+    }
+}
+
+fn render_value(
+    label: &str,
+    data: &DataObject,
+    depth: usize,
+    context: &WikiContext,
+    page: &mut String,
+) {
+    let indent = "  ".repeat(depth);
+    match data {
+        DataObject::Array(array) => {
+            page.push_str(&format!("{}- **{}:**\n", indent, label));
+            for (index, item) in array.iter().enumerate() {
+                render_value(&(index + 1).to_string(), item, depth + 1, context, page);
+            }
+        }
+        DataObject::Map(map) => {
+            page.push_str(&format!("{}- **{}:**\n", indent, label));
+            for (key, value) in map.0.iter() {
+                render_value(&inline_value(key, context), value, depth + 1, context, page);
+            }
+        }
+        DataObject::Custom(custom) => {
+            page.push_str(&format!("{}- **{}:**\n", indent, label));
+            let mut fields: Vec<(&String, &DataObject)> = custom.0.iter().collect();
+            fields.sort_by_key(|(field, _)| *field);
+            for (field, value) in fields {
+                render_value(field, value, depth + 1, context, page);
+            }
+        }
+        DataObject::LongText(text) => {
+            page.push_str(&format!("{}- **{}:**\n\n", indent, label));
+            for line in text.lines() {
+                page.push_str(&format!("{}  {}\n", indent, line));
+            }
+            page.push('\n');
+        }
+        _ => page.push_str(&format!(
+            "{}- **{}:** {}\n",
+            indent,
+            label,
+            inline_value(data, context)
+        )),
+    }
+}
+
+fn inline_value(data: &DataObject, context: &WikiContext) -> String {
+    match data {
+        DataObject::Null => "_empty_".to_owned(),
+        DataObject::Text(text) | DataObject::LongText(text) => text.clone(),
+        DataObject::Boolean(value) => value.to_string(),
+        DataObject::Integer(value) => value.to_string(),
+        DataObject::Float(value) => value.to_string(),
+        DataObject::NounReference(noun_id) => noun_link(*noun_id, context),
+        DataObject::Asset(asset) => format!("`asset:{}` ({})", asset.asset_hash, asset.mime_type),
+        DataObject::Dice(dice) => format!("`{}`", dice),
+        DataObject::Array(_) | DataObject::Map(_) | DataObject::Custom(_) => "…".to_owned(),
+    }
+}
+
+fn noun_link(noun_id: i64, context: &WikiContext) -> String {
+    match context.nouns.get(&noun_id) {
+        Some(noun) => format!("[{}]({})", link_text(&noun.name), noun_file_name(noun)),
+        None => format!("#{}", noun_id),
+    }
+}
+
+fn attribute_name(attribute_type_id: i64, context: &WikiContext) -> String {
+    context
+        .attribute_names
+        .get(&attribute_type_id)
+        .map_or(format!("#{}", attribute_type_id), |name| name.to_string())
+}
+
+fn collect_references(attribute: &Attribute, references: &mut Vec<(i64, i64)>) {
+    collect_data_references(&attribute.data, attribute.attribute_type_id, references);
+    for child in attribute.children.iter().flatten() {
+        collect_references(child, references);
+    }
+}
+
+fn collect_data_references(
+    data: &DataObject,
+    attribute_type_id: i64,
+    references: &mut Vec<(i64, i64)>,
+) {
+    match data {
+        DataObject::NounReference(noun_id) => references.push((attribute_type_id, *noun_id)),
+        DataObject::Array(array) => array
+            .iter()
+            .for_each(|x| collect_data_references(x, attribute_type_id, references)),
+        DataObject::Map(map) => map.0.iter().for_each(|(key, value)| {
+            collect_data_references(key, attribute_type_id, references);
+            collect_data_references(value, attribute_type_id, references);
+        }),
+        DataObject::Custom(custom) => custom
+            .0
+            .values()
+            .for_each(|x| collect_data_references(x, attribute_type_id, references)),
+        _ => {}
+    }
+}